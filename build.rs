@@ -7,22 +7,136 @@ extern crate serde_derive;
 extern crate tera;
 
 use std::io::{Write, BufReader, BufWriter};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::path::Path;
 use std::env;
 
-const DATA_PATH: &'static str = "misc/data.json";
-const SUPPORTED_LANG_PATH: &'static str = "misc/supported_languages.csv";
-const TEMPLATE_LANG_RS_PATH: &'static str = "templates/lang.rs";
+const DATA_PATH: &str = "misc/data.json";
+const ROMANIZED_DATA_PATH: &str = "misc/romanized.json";
+const SUPPORTED_LANG_PATH: &str = "misc/supported_languages.csv";
+const TEMPLATE_LANG_RS_PATH: &str = "templates/lang.rs";
+const UNICODE_SCRIPTS_PATH: &str = "misc/Scripts.txt";
+const TEMPLATE_SCRIPT_RANGES_RS_PATH: &str = "templates/script_ranges.rs";
+const TEMPLATE_RAW_PROFILES_RS_PATH: &str = "templates/raw_profiles.rs";
 const TRIGRAM_COUNT: usize = 300;
 
+// Scripts from Scripts.txt, which are detected by whatlang, with their names in `Script` enum.
+const SUPPORTED_SCRIPTS: &[(&str, &str)] = &[
+    ("Arabic", "Arabic"),
+    ("Armenian", "Armenian"),
+    ("Balinese", "Balinese"),
+    ("Bengali", "Bengali"),
+    ("Canadian_Aboriginal", "CanadianAboriginal"),
+    ("Cherokee", "Cherokee"),
+    ("Cyrillic", "Cyrillic"),
+    ("Devanagari", "Devanagari"),
+    ("Ethiopic", "Ethiopic"),
+    ("Georgian", "Georgian"),
+    ("Greek", "Greek"),
+    ("Gujarati", "Gujarati"),
+    ("Gurmukhi", "Gurmukhi"),
+    ("Han", "Mandarin"),
+    ("Hangul", "Hangul"),
+    ("Hebrew", "Hebrew"),
+    ("Hiragana", "Hiragana"),
+    ("Javanese", "Javanese"),
+    ("Kannada", "Kannada"),
+    ("Katakana", "Katakana"),
+    ("Khmer", "Khmer"),
+    ("Lao", "Lao"),
+    ("Latin", "Latin"),
+    ("Malayalam", "Malayalam"),
+    ("Mongolian", "Mongolian"),
+    ("Myanmar", "Myanmar"),
+    ("Nko", "Nko"),
+    ("Oriya", "Oriya"),
+    ("Sinhala", "Sinhala"),
+    ("Sundanese", "Sundanese"),
+    ("Tamil", "Tamil"),
+    ("Telugu", "Telugu"),
+    ("Thaana", "Thaana"),
+    ("Thai", "Thai"),
+    ("Tifinagh", "Tifinagh"),
+];
+
+// Scripts of historic manuscripts and inscriptions, supported with `historic-scripts` feature.
+// They are used by no supported language, so only the script is detected.
+const HISTORIC_SCRIPTS: &[(&str, &str)] = &[
+    ("Coptic", "Coptic"),
+    ("Glagolitic", "Glagolitic"),
+    ("Gothic", "Gothic"),
+    ("Old_Italic", "OldItalic"),
+    ("Phoenician", "Phoenician"),
+    ("Runic", "Runic"),
+];
+
+// Combining marks, which are used with letters of several scripts, so they take the script
+// of the letter before them.
+const INHERITED_SCRIPT: &str = "Inherited";
+
+// Blocks, which are shared by several scripts, so their chars are not counted for any of them,
+// whatever Scripts.txt assigns them to: CJK Symbols and Punctuation contains the ideographic space
+// and punctuation, but also marks like 々 and 〇 (Han) and tone marks of Hangul.
+const NEUTRAL_RANGES: &[(u32, u32)] = &[(0x3000, 0x303F)];
+
+// Groups of languages, which can be compiled in by `langs-<group>` features, in addition to
+// groups of all languages of a script (`langs-latin`, `langs-cyrillic`, etc).
+const LANG_GROUPS: &[(&str, &[&str])] = &[
+    ("european", &[
+        "bel", "bul", "cat", "ces", "cym", "dan", "deu", "eng", "est", "eus", "fao", "fin", "fra", "gla", "gle",
+        "glg", "hrv", "hun", "isl", "ita", "lat", "lav", "lit", "mkd", "nld", "nno", "nob", "pol", "por", "ron",
+        "rus", "slk", "slv", "spa", "srp", "swe", "ukr",
+    ]),
+];
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 struct LangInfo {
     code: String,
     eng_name: String,
     name: String,
     native_speakers: Option<f64>,
+    iso639_1: Option<String>,
+    family: String,
+    // Discriminant of `Lang`, which must never change. New languages get the next free id.
+    id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ScriptRange {
+    start: u32,
+    end: u32,
+    script: String,
+}
+
+// A list of profiles encoded as indices of n-grams in the dictionary of all n-grams.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ProfileList {
+    name: String,
+    data: Vec<u16>,
+    table: NgramTable,
+}
+
+// A perfect hash table of the n-grams of a profile list, see src/ngram_table.rs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct NgramTable {
+    langs: Vec<String>,
+    sizes: Vec<usize>,
+    displacements: Vec<u16>,
+    keys: Vec<u64>,
+    offsets: Vec<u16>,
+    postings: Vec<u16>,
+}
+
+// A trigram profile, which is rendered as a public constant of `raw_profiles` module.
+// Trigrams are rendered as string literals, since they may contain quotes and backslashes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RawProfile {
+    name: String,
+    lang: String,
+    eng_name: String,
+    script: String,
+    trigrams: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -30,12 +144,32 @@ struct Lang {
     info: LangInfo,
     script: String,
     trigrams: Vec<String>,
+    bigrams: Vec<String>,
+    fourgrams: Vec<String>,
+}
+
+impl Lang {
+    fn new(info: LangInfo, script: String, trigrams: Vec<String>) -> Self {
+        let bigrams = derive_bigrams(&trigrams);
+        let fourgrams = derive_fourgrams(&trigrams);
+        Lang { info, script, trigrams, bigrams, fourgrams }
+    }
 }
 
 fn main() {
     println!("cargo:rerun-if-changed={}", DATA_PATH);
+    println!("cargo:rerun-if-changed={}", ROMANIZED_DATA_PATH);
     println!("cargo:rerun-if-changed={}", SUPPORTED_LANG_PATH);
     println!("cargo:rerun-if-changed={}", TEMPLATE_LANG_RS_PATH);
+    println!("cargo:rerun-if-changed={}", UNICODE_SCRIPTS_PATH);
+    println!("cargo:rerun-if-changed={}", TEMPLATE_SCRIPT_RANGES_RS_PATH);
+    println!("cargo:rerun-if-changed={}", TEMPLATE_RAW_PROFILES_RS_PATH);
+
+    // `parallel` feature is compiled out for WebAssembly, where rayon can not spawn threads
+    println!("cargo:rustc-check-cfg=cfg(parallel)");
+    if env::var_os("CARGO_FEATURE_PARALLEL").is_some() && env::var("CARGO_CFG_TARGET_ARCH").unwrap() != "wasm32" {
+        println!("cargo:rustc-cfg=parallel");
+    }
 
     generate_source_files();
     skeptic::generate_doc_tests(&["README.md"]);
@@ -46,57 +180,414 @@ fn generate_source_files() {
     let dest_path = Path::new(&out_dir).join("lang.rs");
     let mut lang_def = BufWriter::new(File::create(&dest_path).unwrap());
 
-    let (lang_infos, scripts) = load_data();
+    let (lang_infos, mut scripts) = load_data();
+    let mut romanized = load_romanized_data(&lang_infos);
+
+    let selected_groups = selected_lang_groups();
+    if !selected_groups.is_empty() {
+        for (script, langs) in scripts.iter_mut() {
+            langs.retain(|lang| is_profile_selected(&selected_groups, script, &lang.info.code));
+        }
+        romanized.retain(|lang| is_profile_selected(&selected_groups, &lang.script, &lang.info.code));
+    }
+
+    let lang_code = render_lang_rs(&mut lang_def, &lang_infos, &scripts, &romanized);
+
+    // Profiles as arrays of string literals are compiled in only on request
+    if env::var_os("CARGO_FEATURE_RAW_PROFILES").is_some() {
+        let dest_path = Path::new(&out_dir).join("raw_profiles.rs");
+        let mut raw_profiles_def = BufWriter::new(File::create(&dest_path).unwrap());
+        render_raw_profiles_rs(&mut raw_profiles_def, &scripts, &romanized);
+    }
+
+    let dest_path = Path::new(&out_dir).join("script_ranges.rs");
+    let mut script_ranges_def = BufWriter::new(File::create(&dest_path).unwrap());
+    let script_ranges_code = render_script_ranges_rs(&mut script_ranges_def, &load_script_ranges());
+
+    // The generated code identifies the built-in data, including the profiles selected by features
+    let dest_path = Path::new(&out_dir).join("data_hash.rs");
+    let mut data_hash_def = BufWriter::new(File::create(&dest_path).unwrap());
+    let mut data_hash = fnv1a(fnv1a(FNV_OFFSET_BASIS, &lang_code), &script_ranges_code);
+    // Scripts of chars are taken from unicode-script crate instead of the ranges
+    if env::var_os("CARGO_FEATURE_UNICODE_SCRIPT").is_some() {
+        data_hash = fnv1a(data_hash, "unicode-script");
+    }
+    writeln!(data_hash_def, "const DATA_HASH: u32 = {:#010x};", data_hash).unwrap();
+}
+
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+// 32-bit FNV-1a hash of the text, continued from the given hash.
+fn fnv1a(hash: u32, text: &str) -> u32 {
+    text.bytes().fold(hash, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(FNV_PRIME))
+}
+
+const FEATURE_LANGS_PREFIX: &str = "CARGO_FEATURE_LANGS_";
 
-    render_lang_rs(&mut lang_def, &lang_infos, &scripts);
+// Names of groups of languages enabled by `langs-*` features. Without any of them
+// profiles of all languages are compiled in.
+fn selected_lang_groups() -> Vec<String> {
+    env::vars()
+        .filter(|(name, _)| name.starts_with(FEATURE_LANGS_PREFIX))
+        .map(|(name, _)| name[FEATURE_LANGS_PREFIX.len()..].to_lowercase())
+        .collect()
 }
 
-fn load_data() -> (Vec<LangInfo>, HashMap<String, Vec<Lang>>) {
+// A profile is compiled in, when its script or its language is in one of the selected groups.
+fn is_profile_selected(selected_groups: &[String], script: &str, code: &str) -> bool {
+    selected_groups.iter().any(|group| {
+        *group == script.to_lowercase() ||
+            LANG_GROUPS.iter().any(|&(name, codes)| name == group && codes.contains(&code))
+    })
+}
+
+// Parse lines like `0041..005A    ; Latin # L&  [26] LATIN CAPITAL LETTER A..LATIN CAPITAL LETTER Z`.
+// Only supported scripts are kept, adjacent ranges of the same script are merged.
+fn load_script_ranges() -> Vec<ScriptRange> {
+    let content = std::fs::read_to_string(UNICODE_SCRIPTS_PATH).unwrap();
+    let mut supported_scripts = SUPPORTED_SCRIPTS.to_vec();
+    if env::var_os("CARGO_FEATURE_HISTORIC_SCRIPTS").is_some() {
+        supported_scripts.extend_from_slice(HISTORIC_SCRIPTS);
+    }
+
+    let mut ranges: Vec<ScriptRange> = Vec::new();
+    for line in content.lines() {
+        let data = line.split('#').next().unwrap().trim();
+        if data.is_empty() {
+            continue;
+        }
+        let mut fields = data.split(';').map(str::trim);
+        let code_points = fields.next().unwrap();
+        let ucd_script = fields.next().unwrap_or_else(|| panic!("Invalid line in {}: {}", UNICODE_SCRIPTS_PATH, line));
+
+        let script = match supported_scripts.iter().find(|&&(name, _)| name == ucd_script) {
+            Some(&(_, script)) => script,
+            None if ucd_script == INHERITED_SCRIPT => INHERITED_SCRIPT,
+            None => continue,
+        };
+        let mut bounds = code_points.split("..").map(|code| u32::from_str_radix(code, 16).unwrap());
+        let start = bounds.next().unwrap();
+        let end = bounds.next().unwrap_or(start);
+        if NEUTRAL_RANGES.iter().any(|&(neutral_start, neutral_end)| start >= neutral_start && end <= neutral_end) {
+            continue;
+        }
+        ranges.push(ScriptRange { start, end, script: script.to_string() });
+    }
+
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<ScriptRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if let Some(last) = merged.last_mut() {
+            if last.script == range.script && last.end + 1 == range.start {
+                last.end = range.end;
+                continue;
+            }
+            if last.end >= range.start {
+                panic!("Overlapping ranges in {}: {:X} and {:X}", UNICODE_SCRIPTS_PATH, last.start, range.start);
+            }
+        }
+        merged.push(range);
+    }
+    merged
+}
+
+// Languages and scripts are kept sorted, so the generated code (and therefore the order
+// in which ties between languages are resolved) does not change from build to build.
+fn load_data() -> (Vec<LangInfo>, BTreeMap<String, Vec<Lang>>) {
     let data_file = BufReader::new(File::open(DATA_PATH).unwrap());
     let mut lang_reader = csv::ReaderBuilder::new().flexible(true).from_path(SUPPORTED_LANG_PATH).unwrap();
 
     let mut lang_infos: Vec<LangInfo> = lang_reader.deserialize().map(Result::unwrap).collect();
     lang_infos.sort_by(|left, right| left.code.cmp(&right.code));
 
+    let mut ids: Vec<u32> = lang_infos.iter().map(|info| info.id).collect();
+    ids.sort_unstable();
+    if ids.first() == Some(&0) || ids.windows(2).any(|pair| pair[0] == pair[1]) {
+        panic!("Language ids in {} must be unique and non-zero", SUPPORTED_LANG_PATH);
+    }
+
     let supported_lang_codes: HashMap<String, LangInfo> = lang_infos.iter()
         .map(|lang| (lang.code.clone(), lang.clone()))
         .collect();
 
-    let lang_data: HashMap<String, HashMap<String, String>> = serde_json::from_reader(data_file).unwrap();
+    let lang_data: BTreeMap<String, BTreeMap<String, String>> = serde_json::from_reader(data_file).unwrap();
 
-    let mut scripts: HashMap<String, Vec<Lang>> = HashMap::with_capacity(lang_data.len());
-    let mut all_langs: Vec<Lang> = Vec::new();
+    let mut scripts: BTreeMap<String, Vec<Lang>> = BTreeMap::new();
     for (script, langs) in &lang_data {
         for (code, trigrams) in langs {
             let info = match supported_lang_codes.get(&**code) {
                 Some(info) => info,
                 None => continue,
             };
-            let lang = Lang {
-                info: (*info).clone(),
-                script: script.clone(),
-                trigrams: trigrams.split('|').map(Into::into).collect()
-            };
-            if lang.trigrams.len() != TRIGRAM_COUNT {
-                panic!("Language {} has {} trigrams, instead of {}", code, lang.trigrams.len(), TRIGRAM_COUNT);
+            let lang = Lang::new((*info).clone(), script.clone(), parse_trigrams(code, trigrams));
+            scripts.entry(script.clone()).or_default().push(lang);
+        }
+    }
+
+    (lang_infos, scripts)
+}
+
+// Profiles of languages written in Latin script instead of their native one (e.g. Russian translit),
+// they are used only when enabled by options.
+fn load_romanized_data(lang_infos: &[LangInfo]) -> Vec<Lang> {
+    let data_file = BufReader::new(File::open(ROMANIZED_DATA_PATH).unwrap());
+    let lang_data: BTreeMap<String, String> = serde_json::from_reader(data_file).unwrap();
+
+    lang_data.iter().map(|(code, trigrams)| {
+        let info = lang_infos.iter().find(|info| info.code == *code)
+            .unwrap_or_else(|| panic!("Unsupported language {} in {}", code, ROMANIZED_DATA_PATH));
+        Lang::new(info.clone(), "Latin".to_string(), parse_trigrams(code, trigrams))
+    }).collect()
+}
+
+fn parse_trigrams(code: &str, trigrams: &str) -> Vec<String> {
+    let trigrams: Vec<String> = trigrams.split('|').map(Into::into).collect();
+    if trigrams.len() != TRIGRAM_COUNT {
+        panic!("Language {} has {} trigrams, instead of {}", code, trigrams.len(), TRIGRAM_COUNT);
+    }
+    trigrams
+}
+
+// There is no corpus in the repository to build profiles of other n-gram orders,
+// so they are derived from trigram profiles: a trigram weighs more the higher it's ranked
+// (TRIGRAM_COUNT for the first one, 1 for the last one).
+
+// A bigram weighs as much as all the trigrams, which contain it.
+fn derive_bigrams(trigrams: &[String]) -> Vec<String> {
+    let mut weights: HashMap<String, usize> = HashMap::new();
+    for (i, trigram) in trigrams.iter().enumerate() {
+        let chars: Vec<char> = trigram.chars().collect();
+        for bigram in chars.windows(2) {
+            *weights.entry(bigram.iter().collect()).or_insert(0) += TRIGRAM_COUNT - i;
+        }
+    }
+    most_weighted(weights)
+}
+
+// A 4-gram `abcd` is made of overlapping trigrams `abc` and `bcd`, and weighs as their product.
+fn derive_fourgrams(trigrams: &[String]) -> Vec<String> {
+    let chars: Vec<Vec<char>> = trigrams.iter().map(|trigram| trigram.chars().collect()).collect();
+    let mut weights: HashMap<String, usize> = HashMap::new();
+    for (i, first) in chars.iter().enumerate() {
+        for (j, second) in chars.iter().enumerate() {
+            if first[1..] == second[..2] {
+                let fourgram: String = first.iter().chain(second.last()).collect();
+                *weights.entry(fourgram).or_insert(0) += (TRIGRAM_COUNT - i) * (TRIGRAM_COUNT - j);
             }
+        }
+    }
+    most_weighted(weights)
+}
+
+// At most TRIGRAM_COUNT n-grams with the highest weights, ties are ordered alphabetically.
+fn most_weighted(weights: HashMap<String, usize>) -> Vec<String> {
+    let mut ngrams: Vec<(usize, String)> = weights.into_iter().map(|(ngram, weight)| (weight, ngram)).collect();
+    ngrams.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    ngrams.into_iter().take(TRIGRAM_COUNT).map(|(_, ngram)| ngram).collect()
+}
 
-            all_langs.push(lang.clone());
-            scripts.entry(script.clone()).or_insert_with(Vec::new).push(lang);
+// N-grams of one order of a language profile.
+type NgramsOf = fn(&Lang) -> &[String];
+
+// Instead of arrays of string literals (which take 16 bytes and a relocation per n-gram),
+// profiles are stored as indices of n-grams in a dictionary of all the unique n-grams.
+// Every profile of a list is encoded as: the index of its language in `ALL_LANGS`,
+// the number of n-grams and their indices. Returns the dictionary joined by `|` and the lists.
+fn encode_profiles(lang_infos: &[LangInfo], scripts: &BTreeMap<String, Vec<Lang>>, romanized: &[Lang]) -> (String, Vec<ProfileList>) {
+    let mut lists: Vec<(String, &[Lang])> = scripts.iter()
+        .map(|(script, langs)| (format!("{}_LANGS", script.to_uppercase()), &langs[..]))
+        .collect();
+    lists.push(("ROMANIZED_LANGS".to_string(), romanized));
+
+    let orders: [(&str, NgramsOf); 3] = [
+        ("", |lang| &lang.trigrams),
+        ("_BIGRAMS", |lang| &lang.bigrams),
+        ("_FOURGRAMS", |lang| &lang.fourgrams),
+    ];
+
+    let ngrams: BTreeSet<&str> = lists.iter()
+        .flat_map(|&(_, langs)| langs.iter())
+        .flat_map(|lang| orders.iter().flat_map(move |&(_, get)| get(lang).iter().map(String::as_str)))
+        .collect();
+    if ngrams.len() > std::u16::MAX as usize {
+        panic!("{} unique n-grams do not fit into u16 indices", ngrams.len());
+    }
+    let indices: HashMap<&str, u16> = ngrams.iter().enumerate().map(|(i, &ngram)| (ngram, i as u16)).collect();
+
+    let mut profile_lists = Vec::new();
+    for &(ref name, langs) in &lists {
+        for &(suffix, get) in &orders {
+            let mut data = Vec::new();
+            for lang in langs {
+                let lang_index = lang_infos.iter().position(|info| info.code == lang.info.code).unwrap();
+                data.push(lang_index as u16);
+                data.push(get(lang).len() as u16);
+                data.extend(get(lang).iter().map(|ngram| indices[ngram.as_str()]));
+            }
+            let profiles: Vec<(&str, &[String])> = langs.iter().map(|lang| (&lang.info.code[..], get(lang))).collect();
+            let table = build_ngram_table(&profiles);
+            profile_lists.push(ProfileList { name: format!("{}{}", name, suffix), data, table });
         }
     }
 
-    (lang_infos, scripts)
+    let dictionary = ngrams.into_iter().collect::<Vec<&str>>().join("|");
+    (dictionary, profile_lists)
+}
+
+// The same as in src/ngram_table.rs.
+const MAX_NGRAM_SIZE: usize = 4;
+const KEY_CHAR_BITS: usize = 16;
+const RANK_BITS: u32 = 9;
+
+// Chars of the built-in profiles are in the Basic Multilingual Plane, so an n-gram is packed into
+// 64 bits: every char plus one takes 16 bits, the first char takes the highest ones.
+fn pack_ngram(ngram: &str) -> u64 {
+    let size = ngram.chars().count();
+    assert!(size <= MAX_NGRAM_SIZE, "n-gram {:?} is too long", ngram);
+    let packed = ngram.chars().fold(0, |packed, ch| {
+        assert!((ch as u64 + 1) < 1 << KEY_CHAR_BITS, "n-gram {:?} has a char out of the BMP", ngram);
+        (packed << KEY_CHAR_BITS) | (ch as u64 + 1)
+    });
+    packed << (KEY_CHAR_BITS * (MAX_NGRAM_SIZE - size))
+}
+
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn ngram_hash(key: u64, seed: u64) -> u64 {
+    mix(key ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15))
 }
 
-fn render_lang_rs(buf: &mut BufWriter<File>, lang_infos: &[LangInfo], scripts: &HashMap<String, Vec<Lang>>) {
+// Builds a perfect hash table of the n-grams of profiles by "hash and displace": keys are split into
+// buckets by one hash, then, starting from the largest bucket, every bucket gets the first displacement
+// (a seed of the second hash), which puts all its keys into free slots.
+// Every slot holds postings of its n-gram: the index of a profile and the rank of the n-gram in it.
+// Displacements and offsets of postings are u16, so the tables stay small and dense.
+fn build_ngram_table(profiles: &[(&str, &[String])]) -> NgramTable {
+    let mut postings_of: BTreeMap<u64, Vec<u16>> = BTreeMap::new();
+    for (index, &(_, ngrams)) in profiles.iter().enumerate() {
+        assert!(index < 1 << (16 - RANK_BITS), "too many profiles in a list");
+        assert!(ngrams.len() <= 1 << RANK_BITS, "too many n-grams in a profile");
+        for (rank, ngram) in ngrams.iter().enumerate() {
+            postings_of.entry(pack_ngram(ngram)).or_default().push(((index as u16) << RANK_BITS) | rank as u16);
+        }
+    }
+
+    let key_count = postings_of.len();
+    let bucket_count = (key_count + 3) / 4;
+    let slot_count = key_count + key_count / 4;
+    let mut buckets: Vec<(usize, Vec<u64>)> = (0..bucket_count).map(|bucket| (bucket, Vec::new())).collect();
+    for &key in postings_of.keys() {
+        buckets[(ngram_hash(key, 0) % bucket_count as u64) as usize].1.push(key);
+    }
+    buckets.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+
+    let mut slots: Vec<Option<u64>> = vec![None; slot_count];
+    let mut displacements = vec![0; bucket_count];
+    for &(bucket, ref keys) in &buckets {
+        let positions_of = |displacement: u16| -> Vec<usize> {
+            keys.iter()
+                .map(|&key| (ngram_hash(key, u64::from(displacement) + 1) % slot_count as u64) as usize)
+                .collect()
+        };
+        let displacement = (0..=std::u16::MAX)
+            .find(|&displacement| {
+                let positions = positions_of(displacement);
+                positions.iter().enumerate().all(|(i, &pos)| slots[pos].is_none() && !positions[..i].contains(&pos))
+            })
+            .expect("no displacement puts a bucket into free slots");
+        for (&key, pos) in keys.iter().zip(positions_of(displacement)) {
+            slots[pos] = Some(key);
+        }
+        displacements[bucket] = displacement;
+    }
+
+    let mut offsets = vec![0];
+    let mut postings = Vec::new();
+    for slot in &slots {
+        if let Some(key) = *slot {
+            postings.extend(&postings_of[&key]);
+        }
+        assert!(postings.len() <= std::u16::MAX as usize, "too many postings in a list");
+        offsets.push(postings.len() as u16);
+    }
+
+    NgramTable {
+        langs: profiles.iter().map(|&(code, _)| capitalize(code)).collect(),
+        sizes: profiles.iter().map(|&(_, ngrams)| ngrams.len()).collect(),
+        displacements,
+        keys: slots.iter().map(|slot| slot.unwrap_or(0)).collect(),
+        offsets,
+        postings,
+    }
+}
+
+fn capitalize(code: &str) -> String {
+    let mut chars = code.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+}
+
+// Returns the rendered code.
+fn render_lang_rs(buf: &mut BufWriter<File>, lang_infos: &[LangInfo], scripts: &BTreeMap<String, Vec<Lang>>, romanized: &[Lang]) -> String {
     let mut tera = tera::Tera::default();
     tera.add_template_file(TEMPLATE_LANG_RS_PATH, Some("lang.rs")).unwrap();
 
+    let (ngrams, profile_lists) = encode_profiles(lang_infos, scripts, romanized);
+
     let mut ctx = tera::Context::new();
     ctx.insert("lang_infos", lang_infos);
     ctx.insert("scripts", scripts);
+    ctx.insert("ngrams", &ngrams);
+    ctx.insert("profile_lists", &profile_lists);
 
     let code = tera.render("lang.rs", &ctx).unwrap();
     writeln!(buf, "{}", code).unwrap();
+    code
+}
+
+fn render_raw_profiles_rs(buf: &mut BufWriter<File>, scripts: &BTreeMap<String, Vec<Lang>>, romanized: &[Lang]) {
+    let mut tera = tera::Tera::default();
+    tera.add_template_file(TEMPLATE_RAW_PROFILES_RS_PATH, Some("raw_profiles.rs")).unwrap();
+
+    let raw_profile = |prefix: &str, lang: &Lang| RawProfile {
+        name: format!("{}_{}", prefix, lang.info.code.to_uppercase()),
+        lang: capitalize(&lang.info.code),
+        eng_name: lang.info.eng_name.clone(),
+        script: lang.script.clone(),
+        trigrams: lang.trigrams.iter().map(|trigram| format!("{:?}", trigram)).collect(),
+    };
+    let profiles: Vec<RawProfile> = scripts.iter()
+        .flat_map(|(script, langs)| langs.iter().map(move |lang| raw_profile(&script.to_uppercase(), lang)))
+        .collect();
+    let romanized: Vec<RawProfile> = romanized.iter().map(|lang| raw_profile("ROMANIZED", lang)).collect();
+
+    let mut ctx = tera::Context::new();
+    ctx.insert("profiles", &profiles);
+    ctx.insert("romanized", &romanized);
+
+    let code = tera.render("raw_profiles.rs", &ctx).unwrap();
+    writeln!(buf, "{}", code).unwrap();
+}
+
+// Returns the rendered code.
+fn render_script_ranges_rs(buf: &mut BufWriter<File>, script_ranges: &[ScriptRange]) -> String {
+    let mut tera = tera::Tera::default();
+    tera.add_template_file(TEMPLATE_SCRIPT_RANGES_RS_PATH, Some("script_ranges.rs")).unwrap();
+
+    let (inherited_ranges, script_ranges): (Vec<&ScriptRange>, Vec<&ScriptRange>) = script_ranges.iter()
+        .partition(|range| range.script == INHERITED_SCRIPT);
+    let mut ctx = tera::Context::new();
+    ctx.insert("script_ranges", &script_ranges);
+    ctx.insert("inherited_ranges", &inherited_ranges);
+
+    let code = tera.render("script_ranges.rs", &ctx).unwrap();
+    writeln!(buf, "{}", code).unwrap();
+    code
 }
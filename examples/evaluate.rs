@@ -0,0 +1,134 @@
+// Evaluate accuracy of detection on a labeled corpus:
+//
+//     cargo run --release --example evaluate -- corpus_dir
+//
+// The corpus directory contains a file per language named by its code (e.g. `deu.txt`),
+// every non-empty line of a file is a separate sample. Without arguments the examples
+// from tests/examples.json are evaluated.
+extern crate whatlang;
+extern crate serde_json;
+
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use whatlang::{detect, Lang};
+
+const EXAMPLES_PATH: &str = "tests/examples.json";
+
+fn main() {
+    let samples = match env::args().nth(1) {
+        Some(dir) => load_corpus(Path::new(&dir)),
+        None => load_examples()
+    };
+    if samples.is_empty() {
+        eprintln!("No samples found");
+        process::exit(1);
+    }
+
+    // (expected, detected) -> number of samples, None stands for undetected ones
+    let mut confusion: HashMap<(Lang, Option<Lang>), usize> = HashMap::new();
+    for &(lang, ref text) in &samples {
+        let detected = detect(text).map(|info| info.lang());
+        *confusion.entry((lang, detected)).or_insert(0) += 1;
+    }
+
+    print_scores(&confusion, samples.len());
+    println!();
+    print_confusion_matrix(&confusion);
+}
+
+fn load_corpus(dir: &Path) -> Vec<(Lang, String)> {
+    let entries = fs::read_dir(dir).unwrap_or_else(|err| fail(&format!("Can not read {}: {}", dir.display(), err)));
+    let mut samples = Vec::new();
+    for entry in entries {
+        let path = entry.unwrap().path();
+        let code = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+        let lang = match Lang::from_code(code) {
+            Some(lang) => lang,
+            None => {
+                eprintln!("Skipping {}: unknown language code", path.display());
+                continue;
+            }
+        };
+        let content = fs::read_to_string(&path).unwrap_or_else(|err| fail(&format!("Can not read {}: {}", path.display(), err)));
+        samples.extend(content.lines().filter(|line| !line.trim().is_empty()).map(|line| (lang, line.to_string())));
+    }
+    samples
+}
+
+fn load_examples() -> Vec<(Lang, String)> {
+    let content = fs::read_to_string(EXAMPLES_PATH).unwrap_or_else(|err| fail(&format!("Can not read {}: {}", EXAMPLES_PATH, err)));
+    let examples: BTreeMap<String, String> = serde_json::from_str(&content).unwrap();
+    examples.into_iter()
+        .filter_map(|(code, text)| Lang::from_code(code).map(|lang| (lang, text)))
+        .collect()
+}
+
+fn print_scores(confusion: &HashMap<(Lang, Option<Lang>), usize>, total: usize) {
+    println!("{:<6} {:>8} {:>10} {:>8}", "Lang", "Samples", "Precision", "Recall");
+
+    let expected_langs = sorted(confusion.keys().map(|&(lang, _)| Some(lang)));
+    let mut correct_total = 0;
+    for lang in expected_langs.into_iter().flatten() {
+        let count = |filter: &dyn Fn(Lang, Option<Lang>) -> bool| -> usize {
+            confusion.iter().filter(|&(&(expected, detected), _)| filter(expected, detected)).map(|(_, &n)| n).sum()
+        };
+        let samples = count(&|expected, _| expected == lang);
+        let detected = count(&|_, detected| detected == Some(lang));
+        let correct = count(&|expected, detected| expected == lang && detected == Some(lang));
+        correct_total += correct;
+
+        let precision = if detected == 0 { 0.0 } else { correct as f64 / detected as f64 };
+        let recall = correct as f64 / samples as f64;
+        println!("{:<6} {:>8} {:>10.3} {:>8.3}", lang.code(), samples, precision, recall);
+    }
+    println!("Accuracy: {:.3} ({} of {})", correct_total as f64 / total as f64, correct_total, total);
+}
+
+// Rows are expected languages, columns are detected ones (`-` for undetected samples).
+// Only languages, which are confused with others, are shown.
+fn print_confusion_matrix(confusion: &HashMap<(Lang, Option<Lang>), usize>) {
+    let errors: Vec<(&(Lang, Option<Lang>), &usize)> = confusion.iter()
+        .filter(|&(&(expected, detected), _)| detected != Some(expected))
+        .collect();
+    if errors.is_empty() {
+        println!("No confusions");
+        return;
+    }
+
+    let rows: Vec<Lang> = sorted(errors.iter().map(|&(&(expected, _), _)| Some(expected))).into_iter().flatten().collect();
+    let columns = sorted(errors.iter().map(|&(&(_, detected), _)| detected).chain(rows.iter().map(|&lang| Some(lang))));
+
+    println!("Confusion matrix (rows are expected languages, columns are detected ones):");
+    print!("{:<6}", "");
+    for &column in &columns {
+        print!(" {:>5}", column.as_ref().map_or("-", Lang::code));
+    }
+    println!();
+    for &row in &rows {
+        print!("{:<6}", row.code());
+        for &column in &columns {
+            match confusion.get(&(row, column)) {
+                Some(n) => print!(" {:>5}", n),
+                None => print!(" {:>5}", "."),
+            }
+        }
+        println!();
+    }
+}
+
+// Unique languages sorted by their codes, `None` goes first.
+fn sorted<I: Iterator<Item = Option<Lang>>>(langs: I) -> Vec<Option<Lang>> {
+    let mut langs: Vec<Option<Lang>> = langs.collect();
+    langs.sort_by_key(|lang| lang.map(|lang| lang.code().to_string()));
+    langs.dedup();
+    langs
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
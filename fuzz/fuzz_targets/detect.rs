@@ -0,0 +1,14 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate whatlang;
+
+use whatlang::{detect_with_options, Options};
+
+// Detection of any text with any options never panics.
+fuzz_target!(|input: (Options, String)| {
+    let (options, text) = input;
+    if let Some(info) = detect_with_options(&text, &options) {
+        assert!(info.confidence() >= 0.0 && info.confidence() <= 1.0, "{:?}", info);
+    }
+});
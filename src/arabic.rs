@@ -0,0 +1,106 @@
+use lang::Lang;
+use script::Script;
+
+// Letters, which Persian, Urdu, Saraiki and Uyghur add to the Arabic alphabet
+// for the sounds which Standard Arabic does not have.
+const PERSIAN_LETTERS: &[char] = &['پ', 'چ', 'ژ', 'گ'];
+
+// Keheh and Farsi yeh, which Persian, Urdu and Saraiki use instead of Arabic kaf and yeh ("ك" and "ي").
+const KEHEH_AND_FARSI_YEH: &[char] = &['ک', 'ی'];
+
+// Retroflex consonants, noon ghunna and yeh barree of Urdu and Saraiki.
+const URDU_LETTERS: &[char] = &['ٹ', 'ڈ', 'ڑ', 'ں', 'ہ', 'ے'];
+
+// Implosive consonants of Saraiki.
+const SARAIKI_LETTERS: &[char] = &['ٻ', 'ڄ', 'ڳ', 'ݙ'];
+
+// Vowels and ng of Uyghur, which writes all vowels with letters.
+const UYGHUR_LETTERS: &[char] = &['ڭ', 'ۆ', 'ۇ', 'ۈ', 'ۋ', 'ې', 'ە'];
+
+// Letters, which are never used by a language of the Arabic script, so a text with them is not
+// written in the language.
+const FOREIGN_LETTERS: [(Lang, &[&[char]]); 5] = [
+    (Lang::Arb, &[PERSIAN_LETTERS, KEHEH_AND_FARSI_YEH, URDU_LETTERS, SARAIKI_LETTERS, UYGHUR_LETTERS]),
+    (Lang::Pes, &[URDU_LETTERS, SARAIKI_LETTERS, UYGHUR_LETTERS]),
+    (Lang::Urd, &[SARAIKI_LETTERS, UYGHUR_LETTERS]),
+    (Lang::Skr, &[UYGHUR_LETTERS]),
+    (Lang::Uig, &[KEHEH_AND_FARSI_YEH, URDU_LETTERS, SARAIKI_LETTERS]),
+];
+
+// A language is not a candidate, when a larger share of the Arabic script chars of a text are
+// foreign to it. A few foreign letters are allowed in longer texts, e.g. in names or quotes.
+const MAX_FOREIGN_SHARE: f64 = 0.01;
+
+// Remove the candidates of a text of the Arabic script, which do not use some of its letters,
+// so they are not scored by trigrams at all. If every candidate would be removed, none is.
+pub(crate) fn filter_arabic_langs(langs: Vec<Lang>, counts: &[(Script, usize)], letters: ArabicLetters) -> Vec<Lang> {
+    let arabic = counts.iter().find(|&&(script, _)| script == Script::Arabic).map_or(0, |&(_, count)| count);
+    let is_foreign = |lang: Lang| FOREIGN_LETTERS.iter().zip(letters.foreign.iter())
+        .any(|(&(foreign_lang, _), &count)| foreign_lang == lang && count as f64 > arabic as f64 * MAX_FOREIGN_SHARE);
+
+    if langs.iter().all(|&lang| is_foreign(lang)) {
+        return langs;
+    }
+    langs.into_iter().filter(|&lang| !is_foreign(lang)).collect()
+}
+
+// Numbers of letters, which are foreign to the languages of the Arabic script,
+// in the same order as FOREIGN_LETTERS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ArabicLetters {
+    pub foreign: [usize; 5]
+}
+
+impl ArabicLetters {
+    pub fn add(&mut self, other: ArabicLetters) {
+        for (count, other_count) in self.foreign.iter_mut().zip(other.foreign.iter()) {
+            *count += other_count;
+        }
+    }
+}
+
+pub(crate) fn count_arabic_letters(text: &str) -> ArabicLetters {
+    let mut result = ArabicLetters::default();
+    for ch in text.chars() {
+        for (count, &(_, groups)) in result.foreign.iter_mut().zip(FOREIGN_LETTERS.iter()) {
+            if groups.iter().any(|group| group.contains(&ch)) {
+                *count += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use script::script_counts;
+
+    const ARABIC_LANGS: &[Lang] = &[Lang::Arb, Lang::Pes, Lang::Urd, Lang::Skr, Lang::Uig];
+
+    fn filter(text: &str) -> Vec<Lang> {
+        filter_arabic_langs(ARABIC_LANGS.to_vec(), &script_counts(text), count_arabic_letters(text))
+    }
+
+    #[test]
+    fn test_filter_arabic_langs() {
+        assert_eq!(filter("ذهب الولد إلى المدرسة صباحا"), ARABIC_LANGS.to_vec());
+        assert_eq!(filter("من به مدرسه رفتم و کتاب خواندم"), vec![Lang::Pes, Lang::Urd, Lang::Skr]);
+        assert_eq!(filter("میں نے کل بازار سے کتابیں خریدیں"), vec![Lang::Urd, Lang::Skr]);
+        assert_eq!(filter("ݙبھاری سرائیکی"), vec![Lang::Skr]);
+        assert_eq!(filter("ئۇيغۇر تىلى گۈزەل تىل"), vec![Lang::Uig]);
+    }
+
+    #[test]
+    fn test_filter_arabic_langs_allows_few_foreign_letters() {
+        // A Persian name in a longer Arabic text
+        let text = "زار الرئيس الإيراني مدينة بندر عباس وقال إن المشروع الجديد سيساعد على تطوير الموانئ \
+                    في المنطقة وسيوفر فرص عمل كثيرة للشباب في السنوات القادمة، وأضاف أن الحكومة ستدعم \
+                    المستثمرين في قطاع النقل البحري والتجارة مع الدول المجاورة. وزار أيضا مدينة گرگان";
+        assert!(filter(text).contains(&Lang::Arb));
+
+        // Letters foreign to all candidates do not remove them all
+        assert_eq!(filter_arabic_langs(vec![Lang::Arb, Lang::Uig], &script_counts("ٹ"), count_arabic_letters("ٹ")),
+                   vec![Lang::Arb, Lang::Uig]);
+    }
+}
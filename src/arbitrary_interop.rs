@@ -0,0 +1,115 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use lang::Lang;
+use script::Script;
+use options::{NgramOrder, Options, ScoringMetric};
+#[cfg(feature = "normalization")]
+use options::Normalization;
+
+impl<'a> Arbitrary<'a> for Lang {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(Lang::all())?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Script {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(Script::all())?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for NgramOrder {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&[NgramOrder::Bigrams, NgramOrder::Trigrams, NgramOrder::Fourgrams])?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for ScoringMetric {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&[ScoringMetric::OutOfPlace, ScoringMetric::Cosine, ScoringMetric::LogProbability,
+                       ScoringMetric::CountWeighted])?)
+    }
+}
+
+// A share from 0 to 1 in steps of 0.01.
+fn arbitrary_share(u: &mut Unstructured) -> Result<f64> {
+    Ok(f64::from(u.int_in_range(0..=100u8)?) / 100.0)
+}
+
+/// Options with settings in their valid ranges, e.g. confidence from 0 to 1 and sizes of at least 1.
+/// Settings, which make detection depend on anything but the text (the time limit and the thread pool),
+/// and the char filter are never set.
+impl<'a> Arbitrary<'a> for Options {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut options = Options::new();
+        match u.int_in_range(0..=2u8)? {
+            1 => options = options.set_whitelist(u.arbitrary()?),
+            2 => options = options.set_blacklist(u.arbitrary()?),
+            _ => {}
+        }
+        options = options.set_min_confidence(arbitrary_share(u)?)
+            .set_min_length(u.int_in_range(0..=20)?);
+        let priors: Vec<Lang> = u.arbitrary()?;
+        options = options.set_priors(priors.into_iter().map(|lang| Ok((lang, arbitrary_share(u)?))).collect::<Result<_>>()?);
+        if u.arbitrary()? {
+            options = options.set_sample_size(u.int_in_range(1..=64 * 1024)?);
+        }
+        if u.arbitrary()? {
+            options = options.set_max_chars(u.int_in_range(1..=64 * 1024)?);
+        }
+        if u.arbitrary()? {
+            options = options.set_max_word_length(u.int_in_range(1..=100)?);
+        }
+        if u.arbitrary()? {
+            options = options.set_script_exit_threshold(arbitrary_share(u)?, u.int_in_range(1..=1000)?);
+        }
+        if u.arbitrary()? {
+            options = options.set_scripts(u.arbitrary()?);
+        }
+        if u.arbitrary()? {
+            options = options.set_unseen_trigram_penalty(arbitrary_share(u)?);
+        }
+        options = options.set_parallel_threshold(u.int_in_range(1..=1024 * 1024)?)
+            .set_romanized(u.arbitrary()?)
+            .set_filter_social_tokens(u.arbitrary()?)
+            .set_skip_code(u.arbitrary()?)
+            .set_ngram_order(u.arbitrary()?)
+            .set_scoring_metric(u.arbitrary()?)
+            .set_calibrated_confidence(u.arbitrary()?)
+            .set_resolve_close_langs(u.arbitrary()?);
+        #[cfg(feature = "html")]
+        {
+            options = options.set_strip_html(u.arbitrary()?);
+        }
+        #[cfg(feature = "normalization")]
+        {
+            if u.arbitrary()? {
+                options = options.set_normalization(*u.choose(&[Normalization::Nfc, Normalization::Nfkc])?);
+            }
+        }
+        #[cfg(feature = "segmentation")]
+        {
+            options = options.set_segment_words(u.arbitrary()?);
+        }
+        Ok(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use detect::detect_with_options;
+
+    #[test]
+    fn test_arbitrary_options() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        for _ in 0..20 {
+            let options = Options::arbitrary(&mut u).unwrap();
+            assert!(options.min_confidence >= 0.0 && options.min_confidence <= 1.0);
+            detect_with_options("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", &options);
+        }
+        assert!(Lang::all().contains(&Lang::arbitrary(&mut u).unwrap()));
+        assert!(Script::all().contains(&Script::arbitrary(&mut u).unwrap()));
+    }
+}
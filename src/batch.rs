@@ -0,0 +1,80 @@
+#[cfg(parallel)]
+use rayon::prelude::*;
+
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+/// Detect languages and scripts of many texts, the results go in the order of the texts.
+///
+/// With `parallel` feature the texts are detected in parallel with rayon,
+/// one text per task, otherwise they are detected one by one.
+/// See [Options::set_thread_pool](struct.Options.html#method.set_thread_pool) to choose the pool.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_many, Lang};
+///
+/// let texts = ["Ĉu vi ne volas eklerni Esperanton? Bonvolu!", "42!"];
+/// let results = detect_many(&texts);
+/// assert_eq!(results[0].unwrap().lang(), Lang::Epo);
+/// assert_eq!(results[1], None);
+/// ```
+pub fn detect_many<S: AsRef<str> + Sync>(texts: &[S]) -> Vec<Option<Info>> {
+    detect_many_with_options(texts, &Options::default())
+}
+
+/// Detect languages and scripts of many texts, using the given options.
+///
+/// See [`detect_many`](fn.detect_many.html) for details.
+pub fn detect_many_with_options<S: AsRef<str> + Sync>(texts: &[S], options: &Options) -> Vec<Option<Info>> {
+    #[cfg(parallel)]
+    {
+        options.install(|| {
+            texts.par_iter()
+                .map(|text| detect_with_options(text.as_ref(), options))
+                .collect()
+        })
+    }
+    #[cfg(not(parallel))]
+    {
+        texts.iter()
+            .map(|text| detect_with_options(text.as_ref(), options))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use detect::detect;
+
+    #[test]
+    fn test_detect_many() {
+        let texts: Vec<String> = vec![
+            "Ĉu vi ne volas eklerni Esperanton? Bonvolu!".to_string(),
+            "Введите запрос, чтобы найти нужную информацию".to_string(),
+            "".to_string(),
+            "東京都知事選挙の結果発表".to_string(),
+        ];
+        let expected: Vec<Option<Info>> = texts.iter().map(|text| detect(text)).collect();
+        assert_eq!(detect_many(&texts), expected);
+
+        let empty: &[&str] = &[];
+        assert!(detect_many(empty).is_empty());
+    }
+
+    #[cfg(parallel)]
+    #[test]
+    fn test_detect_many_in_thread_pool() {
+        use std::sync::Arc;
+        use rayon::ThreadPoolBuilder;
+
+        let texts = ["Ĉu vi ne volas eklerni Esperanton? Bonvolu!", "Введите запрос, чтобы найти нужную информацию"];
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let options = Options::new().set_thread_pool(Arc::new(pool));
+        assert_eq!(detect_many_with_options(&texts, &options), detect_many(&texts));
+        assert_eq!(options, options.clone());
+        assert_ne!(options, Options::new());
+    }
+}
@@ -0,0 +1,139 @@
+use lang::Lang;
+
+// Bulgarian and Macedonian have very similar trigram profiles, so they are often confused
+// on short texts. But each of them has letters which the other one does not use:
+// Macedonian has "ј" instead of "й", no "щ" and "ъ", and its own "ѓ", "ќ", "ѕ", "љ", "њ", "џ".
+// The most common words also differ: prepositions ("от" and "од", "в" and "во"),
+// the future particle ("ще" and "ќе"), pronouns ("това" and "ова").
+const BULGARIAN_LETTERS: &[char] = &['й', 'щ', 'ъ', 'ь', 'ю', 'я'];
+const MACEDONIAN_LETTERS: &[char] = &['ѓ', 'ѕ', 'ј', 'љ', 'њ', 'ќ', 'џ'];
+
+// Words are sorted, to be found with binary search.
+const BULGARIAN_WORDS: &[&str] = &[
+    "в", "във", "днес", "защо", "защото", "като", "колко", "много", "от", "още", "съм",
+    "със", "тази", "това", "този", "тук", "че", "ще",
+];
+
+const MACEDONIAN_WORDS: &[&str] = &[
+    "бидејќи", "во", "дека", "денес", "зошто", "како", "колку", "многу", "ова", "оваа",
+    "овде", "овој", "од", "сум", "што",
+];
+
+// Choose between Bulgarian and Macedonian by the letters and words, which are used only
+// in one of the languages. Returns None if `lang` is neither Bulgarian nor Macedonian,
+// or if the text has as much evidence of one language as of the other one.
+pub(crate) fn resolve_bulgarian_macedonian(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_bulgarian_macedonian(lang) {
+        return None;
+    }
+    let mut evidence = count_bulgarian_macedonian_letters(text);
+    evidence.add(count_bulgarian_macedonian_words(text));
+    resolve_bulgarian_macedonian_by_counts(evidence, lang)
+}
+
+// Same as resolve_bulgarian_macedonian, but with the letters and words already counted.
+pub(crate) fn resolve_bulgarian_macedonian_by_counts(evidence: BulgarianMacedonianEvidence, lang: Lang) -> Option<Lang> {
+    if !is_bulgarian_macedonian(lang) {
+        return None;
+    }
+
+    if evidence.bulgarian > evidence.macedonian {
+        Some(Lang::Bul)
+    } else if evidence.macedonian > evidence.bulgarian {
+        Some(Lang::Mkd)
+    } else {
+        None
+    }
+}
+
+// Numbers of letters and words, which are used only in Bulgarian or only in Macedonian.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct BulgarianMacedonianEvidence {
+    pub bulgarian: usize,
+    pub macedonian: usize
+}
+
+impl BulgarianMacedonianEvidence {
+    pub fn add(&mut self, other: BulgarianMacedonianEvidence) {
+        self.bulgarian += other.bulgarian;
+        self.macedonian += other.macedonian;
+    }
+}
+
+// Letters are counted separately from words, so texts which come in chunks
+// can count them in every chunk, while words are counted only once they end.
+pub(crate) fn count_bulgarian_macedonian_letters(text: &str) -> BulgarianMacedonianEvidence {
+    let mut result = BulgarianMacedonianEvidence::default();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if BULGARIAN_LETTERS.contains(&ch) {
+            result.bulgarian += 1;
+        } else if MACEDONIAN_LETTERS.contains(&ch) {
+            result.macedonian += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn count_bulgarian_macedonian_words(text: &str) -> BulgarianMacedonianEvidence {
+    let mut result = BulgarianMacedonianEvidence::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(BULGARIAN_WORDS, &word) {
+            result.bulgarian += 1;
+        } else if contains(MACEDONIAN_WORDS, &word) {
+            result.macedonian += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_bulgarian_macedonian(lang: Lang) -> bool {
+    lang == Lang::Bul || lang == Lang::Mkd
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [BULGARIAN_WORDS, MACEDONIAN_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in BULGARIAN_WORDS.iter() {
+            assert!(!contains(MACEDONIAN_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_bulgarian_macedonian() {
+        let text = "Тој рече дека ќе дојде утре наутро.";
+        assert_eq!(resolve_bulgarian_macedonian(text, Lang::Bul), Some(Lang::Mkd));
+
+        let text = "Той каза, че ще дойде утре сутринта.";
+        assert_eq!(resolve_bulgarian_macedonian(text, Lang::Mkd), Some(Lang::Bul));
+
+        // Without evidence the language is not resolved
+        let text = "Утре имаме важна среда.";
+        assert_eq!(resolve_bulgarian_macedonian(text, Lang::Mkd), None);
+
+        assert_eq!(resolve_bulgarian_macedonian(text, Lang::Rus), None);
+    }
+
+    #[test]
+    fn test_count_bulgarian_macedonian_evidence() {
+        let text = "Ова е мојот пријател од училиште.";
+        assert_eq!(count_bulgarian_macedonian_letters(text), BulgarianMacedonianEvidence { bulgarian: 0, macedonian: 2 });
+        assert_eq!(count_bulgarian_macedonian_words(text), BulgarianMacedonianEvidence { bulgarian: 0, macedonian: 2 });
+
+        let text = "ЩЕ СЕ ВИДИМ ЛИ В СЪБОТА?";
+        assert_eq!(count_bulgarian_macedonian_letters(text), BulgarianMacedonianEvidence { bulgarian: 2, macedonian: 0 });
+        assert_eq!(count_bulgarian_macedonian_words(text), BulgarianMacedonianEvidence { bulgarian: 2, macedonian: 0 });
+    }
+}
@@ -0,0 +1,440 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::ops::Deref;
+use std::path::Path;
+use std::str;
+
+use fnv::FnvHashMap;
+
+use lang::Lang;
+use script::Script;
+use options::{NgramOrder, Options};
+use profile::LanguageProfile;
+use model::{LanguageModel, Ranking, detect_lang_by_profiles, resolve_close_langs};
+use trigrams::Scratch;
+use markers::count_marker_words;
+use lazy::Lazy;
+
+const MAGIC: &[u8] = b"WLPB";
+const FORMAT_VERSION: u8 = 1;
+const HEADER_SIZE: usize = 8;
+// Script code (4 bytes), language code (3 bytes), n-gram size, flags and the number of n-grams.
+const PROFILE_HEADER_SIZE: usize = 13;
+const ROMANIZED_FLAG: u8 = 1;
+
+/// A bundle of profiles in a file, which is used in place instead of being parsed.
+///
+/// With `mmap` feature (on Unix) the file is memory-mapped, so processes which open the same
+/// bundle (e.g. forked workers) share its pages. Otherwise the file is read into memory.
+/// The bundle is a [LanguageModel](trait.LanguageModel.html), which uses only its profiles.
+/// A bundle of the built-in profiles is written by [write_builtin](#method.write_builtin)
+/// or by `whatlang-train --bundle <file>`.
+///
+/// The format of a bundle (numbers are little endian):
+/// * header: `WLPB`, version, 3 reserved bytes;
+/// * dictionary: the size in bytes and all the unique n-grams joined into one UTF-8 string;
+/// * the number of n-grams in the dictionary and `number + 1` offsets of them in the string;
+/// * the number of profiles and the profiles: codes of the script and the language, the n-gram size,
+///   flags (1 for romanized profiles), the number of n-grams and their indices in the dictionary.
+///
+/// # Example
+/// ```
+/// use whatlang::{Detector, Lang, Options, ProfileBundle};
+///
+/// let mut bytes = Vec::new();
+/// ProfileBundle::write_builtin(&mut bytes).unwrap();
+/// let bundle = ProfileBundle::from_bytes(bytes).unwrap();
+///
+/// let detector = Detector::with_model(bundle, Options::new());
+/// assert_eq!(detector.detect_lang("Та нічого, все нормально. А в тебе як?"), Some(Lang::Ukr));
+/// ```
+pub struct ProfileBundle {
+    // Decoded lists refer to `data`, so they must be dropped before it.
+    lists: Vec<ProfileList>,
+    data: BundleData
+}
+
+// Profiles of a script for n-grams of one order. They are decoded at first use.
+struct ProfileList {
+    script: Script,
+    ngram_order: NgramOrder,
+    // Positions of profiles in the data, see `decode_profile`
+    positions: Vec<usize>,
+    decoded: Lazy<Vec<DecodedProfile>>
+}
+
+// N-grams refer to the data of the bundle, they are never exposed with the static lifetime.
+struct DecodedProfile {
+    lang: Lang,
+    romanized: bool,
+    ngrams: Vec<&'static str>
+}
+
+impl ProfileBundle {
+    /// Open a bundle written by [write](#method.write). The file must not be modified while it's open.
+    /// Returns an error of kind `InvalidData` if the file is not a valid bundle.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::with_data(BundleData::open(file)?)
+    }
+
+    /// Use a bundle, which is already in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> io::Result<Self> {
+        Self::with_data(BundleData::Owned(bytes))
+    }
+
+    /// Write a bundle of the profiles.
+    pub fn write<W: Write>(profiles: &[LanguageProfile], mut writer: W) -> io::Result<()> {
+        let mut dictionary = String::new();
+        let mut offsets: Vec<u32> = vec![0];
+        let mut indices: FnvHashMap<&str, u32> = FnvHashMap::default();
+        for profile in profiles {
+            for ngram in profile.ngrams() {
+                if !indices.contains_key(ngram.as_str()) {
+                    indices.insert(ngram, offsets.len() as u32 - 1);
+                    dictionary.push_str(ngram);
+                    offsets.push(dictionary.len() as u32);
+                }
+            }
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, 0, 0, 0])?;
+        write_u32(&mut writer, dictionary.len() as u32)?;
+        writer.write_all(dictionary.as_bytes())?;
+        write_u32(&mut writer, offsets.len() as u32 - 1)?;
+        for &offset in &offsets {
+            write_u32(&mut writer, offset)?;
+        }
+
+        write_u32(&mut writer, profiles.len() as u32)?;
+        for profile in profiles {
+            writer.write_all(profile.script().code().as_bytes())?;
+            writer.write_all(profile.lang().code().as_bytes())?;
+            let flags = if profile.is_romanized() { ROMANIZED_FLAG } else { 0 };
+            writer.write_all(&[profile.ngram_order().size() as u8, flags])?;
+            write_u32(&mut writer, profile.ngrams().len() as u32)?;
+            for ngram in profile.ngrams() {
+                write_u32(&mut writer, indices[ngram.as_str()])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a bundle of all the built-in profiles.
+    pub fn write_builtin<W: Write>(writer: W) -> io::Result<()> {
+        Self::write(&LanguageProfile::builtin_all(), writer)
+    }
+
+    // The whole bundle is validated here, so profiles can be decoded later without checks.
+    fn with_data(data: BundleData) -> io::Result<Self> {
+        let mut reader = BundleReader { data: &data, pos: 0 };
+        let header = reader.bytes(HEADER_SIZE)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data("not a profile bundle"));
+        }
+        if header[4] != FORMAT_VERSION {
+            return Err(invalid_data("unsupported version of a profile bundle"));
+        }
+
+        let dictionary_size = reader.u32()? as usize;
+        let dictionary = str::from_utf8(reader.bytes(dictionary_size)?)
+            .map_err(|_| invalid_data("dictionary is not valid UTF-8"))?;
+        let ngram_count = reader.u32()? as usize;
+        let mut prev_offset = 0;
+        for _ in 0..=ngram_count {
+            let offset = reader.u32()? as usize;
+            if offset < prev_offset || offset > dictionary.len() || !dictionary.is_char_boundary(offset) {
+                return Err(invalid_data("invalid offset of an n-gram"));
+            }
+            prev_offset = offset;
+        }
+
+        let mut lists: Vec<ProfileList> = Vec::new();
+        let profile_count = reader.u32()?;
+        for _ in 0..profile_count {
+            let position = reader.pos;
+            let header = reader.bytes(PROFILE_HEADER_SIZE)?;
+            let script = str::from_utf8(&header[..4]).ok().and_then(Script::from_code)
+                .ok_or_else(|| invalid_data("unknown script"))?;
+            str::from_utf8(&header[4..7]).ok().and_then(Lang::from_code)
+                .ok_or_else(|| invalid_data("unknown language"))?;
+            let ngram_order = ngram_order(header[7]).ok_or_else(|| invalid_data("unsupported n-gram size"))?;
+            let size = read_u32(&header[9..]) as usize;
+            for _ in 0..size {
+                if reader.u32()? as usize >= ngram_count {
+                    return Err(invalid_data("invalid index of an n-gram"));
+                }
+            }
+
+            match lists.iter_mut().find(|list| list.script == script && list.ngram_order == ngram_order) {
+                Some(list) => list.positions.push(position),
+                None => lists.push(ProfileList { script, ngram_order, positions: vec![position], decoded: Lazy::new() })
+            }
+        }
+        Ok(ProfileBundle { lists, data })
+    }
+
+    // Profiles of the script for n-grams of the order from options, romanized ones are included if enabled.
+    fn script_profiles(&self, script: Script, options: &Options) -> Vec<(Lang, &[&str])> {
+        let list = match self.lists.iter().find(|list| list.script == script && list.ngram_order == options.ngram_order) {
+            Some(list) => list,
+            None => return vec![]
+        };
+        list.decoded.get_or_init(|| list.positions.iter().map(|&position| self.decode_profile(position)).collect())
+            .iter()
+            .filter(|profile| !profile.romanized || options.romanized)
+            .map(|profile| (profile.lang, &profile.ngrams[..]))
+            .collect()
+    }
+
+    fn decode_profile(&self, position: usize) -> DecodedProfile {
+        let data: &[u8] = &self.data;
+        let header = &data[position..position + PROFILE_HEADER_SIZE];
+        let lang = str::from_utf8(&header[4..7]).ok().and_then(Lang::from_code).unwrap();
+        let size = read_u32(&header[9..]) as usize;
+
+        let dictionary_size = read_u32(&data[HEADER_SIZE..]) as usize;
+        let dictionary_start = HEADER_SIZE + 4;
+        let dictionary = str::from_utf8(&data[dictionary_start..dictionary_start + dictionary_size]).unwrap();
+        let offsets_start = dictionary_start + dictionary_size + 4;
+        let offset = |index: usize| read_u32(&data[offsets_start + 4 * index..]) as usize;
+
+        let indices_start = position + PROFILE_HEADER_SIZE;
+        let ngrams = (0..size).map(|i| {
+            let index = read_u32(&data[indices_start + 4 * i..]) as usize;
+            let ngram = &dictionary[offset(index)..offset(index + 1)];
+            // The data is neither moved nor modified while the bundle exists,
+            // and decoded profiles are dropped before it.
+            unsafe { &*(ngram as *const str) }
+        }).collect();
+        DecodedProfile { lang, romanized: header[8] & ROMANIZED_FLAG != 0, ngrams }
+    }
+}
+
+impl LanguageModel for ProfileBundle {
+    fn detect_lang(&self, text: &str, script: Script, candidates: &[Lang], options: &Options) -> Option<(Lang, f64)> {
+        self.detect_lang_in(text, script, candidates, options, &mut Scratch::new())
+    }
+
+    fn detect_lang_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options, scratch: &mut Scratch) -> Option<(Lang, f64)> {
+        self.detect_lang_with_second_best_in(text, script, candidates, options, scratch)
+            .map(|(lang, confidence, _)| (lang, confidence))
+    }
+
+    fn detect_lang_with_second_best_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options,
+                                       scratch: &mut Scratch) -> Option<Ranking> {
+        let profiles = self.script_profiles(script, options);
+        let trigrams = scratch.ngrams_with_positions(text, options.ngram_order.size());
+        let markers = count_marker_words(text, options);
+        detect_lang_by_profiles(trigrams, &markers, &profiles, script, candidates, options,
+                                |lang, rivals| resolve_close_langs(text, lang, rivals))
+    }
+
+    fn script_langs(&self, script: Script, options: &Options) -> Vec<Lang> {
+        self.script_profiles(script, options).into_iter().map(|(lang, _)| lang).collect()
+    }
+}
+
+impl ::std::fmt::Debug for ProfileBundle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.debug_struct("ProfileBundle").field("size", &self.data.len()).finish()
+    }
+}
+
+enum BundleData {
+    Owned(Vec<u8>),
+    #[cfg(all(feature = "mmap", unix))]
+    Mapped(mmap::Mmap)
+}
+
+impl BundleData {
+    #[cfg(all(feature = "mmap", unix))]
+    fn open(file: File) -> io::Result<Self> {
+        mmap::Mmap::new(&file).map(BundleData::Mapped)
+    }
+
+    #[cfg(not(all(feature = "mmap", unix)))]
+    fn open(mut file: File) -> io::Result<Self> {
+        use std::io::Read;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        Ok(BundleData::Owned(bytes))
+    }
+}
+
+impl Deref for BundleData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match *self {
+            BundleData::Owned(ref bytes) => bytes,
+            #[cfg(all(feature = "mmap", unix))]
+            BundleData::Mapped(ref mmap) => mmap
+        }
+    }
+}
+
+#[cfg(all(feature = "mmap", unix))]
+mod mmap {
+    use std::fs::File;
+    use std::io;
+    use std::ops::Deref;
+    use std::os::unix::io::AsRawFd;
+    use std::ptr;
+    use std::slice;
+
+    use libc;
+
+    // A read-only memory mapping of a whole file.
+    pub struct Mmap {
+        ptr: *mut libc::c_void,
+        len: usize
+    }
+
+    // The mapping is read-only, so it can be shared between threads.
+    unsafe impl Send for Mmap {}
+    unsafe impl Sync for Mmap {}
+
+    impl Mmap {
+        pub fn new(file: &File) -> io::Result<Self> {
+            let len = file.metadata()?.len() as usize;
+            if len == 0 {
+                // Empty files can not be mapped
+                return Ok(Mmap { ptr: ptr::null_mut(), len });
+            }
+            let ptr = unsafe {
+                libc::mmap(ptr::null_mut(), len, libc::PROT_READ, libc::MAP_SHARED, file.as_raw_fd(), 0)
+            };
+            if ptr == libc::MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Mmap { ptr, len })
+        }
+    }
+
+    impl Deref for Mmap {
+        type Target = [u8];
+
+        fn deref(&self) -> &[u8] {
+            if self.len == 0 {
+                return &[];
+            }
+            unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for Mmap {
+        fn drop(&mut self) {
+            if self.len > 0 {
+                unsafe { libc::munmap(self.ptr, self.len) };
+            }
+        }
+    }
+}
+
+struct BundleReader<'a> {
+    data: &'a [u8],
+    pos: usize
+}
+
+impl<'a> BundleReader<'a> {
+    fn bytes(&mut self, size: usize) -> io::Result<&'a [u8]> {
+        if self.data.len() - self.pos < size {
+            return Err(invalid_data("unexpected end of a profile bundle"));
+        }
+        let bytes = &self.data[self.pos..self.pos + size];
+        self.pos += size;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        self.bytes(4).map(read_u32)
+    }
+}
+
+fn ngram_order(size: u8) -> Option<NgramOrder> {
+    match size {
+        2 => Some(NgramOrder::Bigrams),
+        3 => Some(NgramOrder::Trigrams),
+        4 => Some(NgramOrder::Fourgrams),
+        _ => None
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::TrigramModel;
+
+    fn builtin_bundle() -> ProfileBundle {
+        let mut bytes = Vec::new();
+        ProfileBundle::write_builtin(&mut bytes).unwrap();
+        ProfileBundle::from_bytes(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_bundle_matches_builtin_model() {
+        let bundle = builtin_bundle();
+        let texts = [
+            (Script::Latin, "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten."),
+            (Script::Latin, "Yaar tum log kahan ho, main kab se intezaar kar raha hoon."),
+            (Script::Cyrillic, "Та нічого, все нормально. А в тебе як?"),
+            (Script::Devanagari, "मैं ठीक हूँ, धन्यवाद"),
+        ];
+        for &order in [NgramOrder::Bigrams, NgramOrder::Trigrams, NgramOrder::Fourgrams].iter() {
+            for &romanized in [false, true].iter() {
+                let options = Options::new().set_ngram_order(order).set_romanized(romanized);
+                for &(script, text) in texts.iter() {
+                    let candidates = TrigramModel.script_langs(script, &options);
+                    assert_eq!(bundle.script_langs(script, &options), candidates);
+                    assert_eq!(bundle.detect_lang(text, script, &candidates, &options),
+                               TrigramModel.detect_lang(text, script, &candidates, &options), "{}", text);
+                }
+            }
+        }
+        assert_eq!(bundle.script_langs(Script::Greek, &Options::new()), vec![]);
+    }
+
+    #[test]
+    fn test_open() {
+        let path = ::std::env::temp_dir().join(format!("whatlang-bundle-{}.wlpb", ::std::process::id()));
+        let profile = LanguageProfile::train(Lang::Ell, Script::Latin, &["Kalimera, ti kaneis?"]);
+        ProfileBundle::write(&[profile], File::create(&path).unwrap()).unwrap();
+        let bundle = ProfileBundle::open(&path).unwrap();
+        ::std::fs::remove_file(&path).unwrap();
+        assert_eq!(bundle.script_langs(Script::Latin, &Options::new()), vec![Lang::Ell]);
+
+        assert!(ProfileBundle::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_invalid_bundle() {
+        let mut bytes = Vec::new();
+        ProfileBundle::write_builtin(&mut bytes).unwrap();
+
+        let error = |bytes: &[u8]| ProfileBundle::from_bytes(bytes.to_vec()).unwrap_err().kind();
+        assert_eq!(error(b""), io::ErrorKind::InvalidData);
+        assert_eq!(error(b"WLPF\x01\x00\x00\x00"), io::ErrorKind::InvalidData);
+        assert_eq!(error(b"WLPB\x02\x00\x00\x00"), io::ErrorKind::InvalidData);
+        assert_eq!(error(&bytes[..bytes.len() - 1]), io::ErrorKind::InvalidData);
+
+        // An index of an n-gram out of the dictionary
+        let last = bytes.len() - 4;
+        bytes[last..].copy_from_slice(&[0xFF; 4]);
+        assert_eq!(error(&bytes), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "encoding")]
+use chardetng::{EncodingDetector, Iso2022JpDetection, Utf8Detection};
+
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const UTF16LE_BOM: &[u8] = b"\xFF\xFE";
+const UTF16BE_BOM: &[u8] = b"\xFE\xFF";
+
+// How many bytes are inspected to guess UTF-16 without BOM.
+const UTF16_SNIFF_LENGTH: usize = 1024;
+
+// UTF-16 without BOM is recognized by zero bytes in every other position, which
+// are the high bytes of ASCII chars (spaces, digits, punctuation, Latin letters).
+const UTF16_ZERO_SHARE: f64 = 0.2;
+
+/// Detect a language and a script by a given text in unknown encoding.
+///
+/// UTF-8 and UTF-16 (with or without BOM) are recognized. With `encoding` feature
+/// legacy encodings (e.g. Windows-1251, Shift_JIS, GBK) are recognized as well,
+/// otherwise invalid UTF-8 sequences are replaced with `U+FFFD`.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_bytes, Lang};
+///
+/// let text = "Ĉu vi ne volas eklerni Esperanton? Bonvolu!";
+/// let info = detect_bytes(text.as_bytes()).unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+///
+/// let utf16: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_le_bytes().to_vec()).collect();
+/// assert_eq!(detect_bytes(&utf16).unwrap().lang(), Lang::Epo);
+/// ```
+pub fn detect_bytes(bytes: &[u8]) -> Option<Info> {
+    detect_bytes_with_options(bytes, &Options::default())
+}
+
+/// Detect a language and a script by a given text in unknown encoding, using the given options.
+///
+/// See [`detect_bytes`](fn.detect_bytes.html) for the recognized encodings.
+pub fn detect_bytes_with_options(bytes: &[u8], options: &Options) -> Option<Info> {
+    detect_with_options(&decode(bytes), options)
+}
+
+// Decode a text in unknown encoding.
+pub(crate) fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    if bytes.starts_with(UTF8_BOM) {
+        return String::from_utf8_lossy(&bytes[UTF8_BOM.len()..]);
+    }
+    if bytes.starts_with(UTF16LE_BOM) {
+        return Cow::Owned(decode_utf16(&bytes[UTF16LE_BOM.len()..], u16::from_le_bytes));
+    }
+    if bytes.starts_with(UTF16BE_BOM) {
+        return Cow::Owned(decode_utf16(&bytes[UTF16BE_BOM.len()..], u16::from_be_bytes));
+    }
+
+    // Zero bytes are valid UTF-8, but never appear in texts
+    let (even_zeros, odd_zeros) = count_zeros(bytes);
+    let threshold = (bytes.len().min(UTF16_SNIFF_LENGTH) / 2) as f64 * UTF16_ZERO_SHARE;
+    if odd_zeros as f64 > threshold && even_zeros < odd_zeros / 4 {
+        return Cow::Owned(decode_utf16(bytes, u16::from_le_bytes));
+    }
+    if even_zeros as f64 > threshold && odd_zeros < even_zeros / 4 {
+        return Cow::Owned(decode_utf16(bytes, u16::from_be_bytes));
+    }
+
+    if let Ok(text) = ::std::str::from_utf8(bytes) {
+        return Cow::Borrowed(text);
+    }
+
+    decode_legacy(bytes)
+}
+
+#[cfg(feature = "encoding")]
+fn decode_legacy(bytes: &[u8]) -> Cow<'_, str> {
+    // The bytes are known to be invalid UTF-8 and ISO-2022-JP is a 7-bit encoding,
+    // so neither of them is a candidate.
+    let mut detector = EncodingDetector::new(Iso2022JpDetection::Deny);
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, Utf8Detection::Deny);
+    encoding.decode_without_bom_handling(bytes).0
+}
+
+#[cfg(not(feature = "encoding"))]
+fn decode_legacy(bytes: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(bytes)
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units = bytes.chunks(2).map(|pair| {
+        if pair.len() == 2 {
+            to_unit([pair[0], pair[1]])
+        } else {
+            // A trailing odd byte can not be decoded
+            0xFFFD
+        }
+    });
+    ::std::char::decode_utf16(units)
+        .map(|ch| ch.unwrap_or(::std::char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+// Count zero bytes at even and odd positions in the beginning of the bytes.
+fn count_zeros(bytes: &[u8]) -> (usize, usize) {
+    let mut even = 0;
+    let mut odd = 0;
+    for (i, &byte) in bytes.iter().take(UTF16_SNIFF_LENGTH).enumerate() {
+        if byte == 0 {
+            if i % 2 == 0 {
+                even += 1;
+            } else {
+                odd += 1;
+            }
+        }
+    }
+    (even, odd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lang::Lang;
+
+    const TEXT: &str = "Ĉu vi ne volas eklerni Esperanton? Bonvolu!";
+
+    fn utf16(text: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+        text.encode_utf16().flat_map(|unit| to_bytes(unit).to_vec()).collect()
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        assert_eq!(decode(TEXT.as_bytes()), TEXT);
+
+        let mut bytes = UTF8_BOM.to_vec();
+        bytes.extend_from_slice(TEXT.as_bytes());
+        assert_eq!(decode(&bytes), TEXT);
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        assert_eq!(decode(&utf16(TEXT, u16::to_le_bytes)), TEXT);
+        assert_eq!(decode(&utf16(TEXT, u16::to_be_bytes)), TEXT);
+
+        let mut bytes = UTF16LE_BOM.to_vec();
+        bytes.extend(utf16("Привет, мир", u16::to_le_bytes));
+        assert_eq!(decode(&bytes), "Привет, мир");
+
+        let mut bytes = UTF16BE_BOM.to_vec();
+        bytes.extend(utf16("Привет, мир", u16::to_be_bytes));
+        assert_eq!(decode(&bytes), "Привет, мир");
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        // "Ĉu vi" in Latin-3
+        let bytes = b"\xC6u vi";
+        if cfg!(feature = "encoding") {
+            assert!(!decode(bytes).contains('\u{FFFD}'));
+        } else {
+            assert_eq!(decode(bytes), "\u{FFFD}u vi");
+        }
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn test_detect_bytes_legacy_encoding() {
+        // Windows-1251
+        let bytes = b"\xcd\xe0\xf1\xf2\xf3\xef\xe8\xeb\xe0 \xee\xf1\xe5\xed\xfc, \xe4\xe5\xf0\xe5\xe2\xfc\xff \xf1\xf2\xee\xff\xf2 \xe2 \xe7\xee\xeb\xee\xf2\xee\xec \xf3\xe1\xee\xf0\xe5";
+        assert_eq!(decode(bytes), "Наступила осень, деревья стоят в золотом уборе");
+        assert_eq!(detect_bytes(bytes).unwrap().lang(), Lang::Rus);
+    }
+
+    #[test]
+    fn test_detect_bytes() {
+        assert_eq!(detect_bytes(TEXT.as_bytes()).unwrap().lang(), Lang::Epo);
+        assert_eq!(detect_bytes(&utf16(TEXT, u16::to_be_bytes)).unwrap().lang(), Lang::Epo);
+        assert_eq!(detect_bytes(b""), None);
+    }
+}
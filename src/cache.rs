@@ -0,0 +1,233 @@
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::{Mutex, MutexGuard};
+
+use fnv::{FnvHashMap, FnvHasher};
+
+use info::Info;
+use detect::DetectError;
+
+// Marks the absence of a neighbour in the list of entries.
+const NONE: usize = ::std::usize::MAX;
+
+// Results of detection of the recently used texts, at most `capacity` of them.
+// Entries are found by 64-bit hashes of texts and keep the texts, so a text with the same hash
+// as a cached one (e.g. a crafted collision) is detected anew. Partial results of detection,
+// which ran out of time (see Options::set_max_duration), are not cached.
+pub(crate) struct DetectionCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+}
+
+impl DetectionCache {
+    pub fn new(capacity: usize) -> Self {
+        DetectionCache { capacity, lru: Mutex::new(Lru::default()) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // Returns the cached result for the text, or detects and caches it.
+    pub fn get_or_detect<F>(&self, text: &str, detect: F) -> Result<Info, DetectError>
+        where F: FnOnce() -> Result<Info, DetectError>
+    {
+        if self.capacity == 0 {
+            return detect();
+        }
+        let key = hash_text(text);
+        if let Some(result) = self.lock().get(key, text) {
+            return result;
+        }
+        // The lock is not held during detection, so other threads are not blocked by it
+        let result = detect();
+        if !is_partial(&result) {
+            self.lock().insert(key, text, result.clone(), self.capacity);
+        }
+        result
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        // The cache is consistent after every operation, so it is usable even if another thread panicked
+        self.lru.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Cached results are not cloned, a clone starts with an empty cache of the same capacity.
+impl Clone for DetectionCache {
+    fn clone(&self) -> Self {
+        Self::new(self.capacity)
+    }
+}
+
+impl Default for DetectionCache {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl fmt::Debug for DetectionCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DetectionCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.lock().map.len())
+            .finish()
+    }
+}
+
+fn is_partial(result: &Result<Info, DetectError>) -> bool {
+    match result {
+        Ok(info) | Err(DetectError::LowConfidence(info)) => info.partial,
+        Err(_) => false
+    }
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(text.as_bytes());
+    hasher.finish()
+}
+
+struct Entry {
+    key: u64,
+    text: Box<str>,
+    result: Result<Info, DetectError>,
+    prev: usize,
+    next: usize,
+}
+
+// A doubly linked list of entries from the most to the least recently used one,
+// stored in a vector, with a map from keys to positions in it.
+struct Lru {
+    map: FnvHashMap<u64, usize>,
+    entries: Vec<Entry>,
+    head: usize,
+    tail: usize,
+}
+
+impl Default for Lru {
+    fn default() -> Self {
+        Lru { map: FnvHashMap::default(), entries: Vec::new(), head: NONE, tail: NONE }
+    }
+}
+
+impl Lru {
+    fn get(&mut self, key: u64, text: &str) -> Option<Result<Info, DetectError>> {
+        let index = *self.map.get(&key)?;
+        if &*self.entries[index].text != text {
+            return None;
+        }
+        self.unlink(index);
+        self.push_front(index);
+        Some(self.entries[index].result.clone())
+    }
+
+    // An entry of another text with the same hash is replaced.
+    fn insert(&mut self, key: u64, text: &str, result: Result<Info, DetectError>, capacity: usize) {
+        if let Some(&index) = self.map.get(&key) {
+            self.entries[index].text = text.into();
+            self.entries[index].result = result;
+            self.unlink(index);
+            self.push_front(index);
+            return;
+        }
+
+        let index = if self.entries.len() < capacity {
+            self.entries.push(Entry { key, text: text.into(), result, prev: NONE, next: NONE });
+            self.entries.len() - 1
+        } else {
+            // Reuse the least recently used entry
+            let index = self.tail;
+            self.unlink(index);
+            self.map.remove(&self.entries[index].key);
+            self.entries[index].key = key;
+            self.entries[index].text = text.into();
+            self.entries[index].result = result;
+            index
+        };
+        self.map.insert(key, index);
+        self.push_front(index);
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = (self.entries[index].prev, self.entries[index].next);
+        if prev == NONE { self.head = next; } else { self.entries[prev].next = next; }
+        if next == NONE { self.tail = prev; } else { self.entries[next].prev = prev; }
+    }
+
+    fn push_front(&mut self, index: usize) {
+        self.entries[index].prev = NONE;
+        self.entries[index].next = self.head;
+        if self.head == NONE { self.tail = index; } else { self.entries[self.head].prev = index; }
+        self.head = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use lang::Lang;
+    use script::Script;
+    use version::model_version;
+
+    fn info(lang: Lang) -> Result<Info, DetectError> {
+        Ok(Info { lang, script: Script::Latin, confidence: 1.0, secondary_script: None, second_best: None,
+                  custom_lang: None, model_version: model_version(), partial: false })
+    }
+
+    #[test]
+    fn test_detection_cache_evicts_least_recently_used() {
+        let cache = DetectionCache::new(2);
+        let calls = &Cell::new(0);
+        let detect = |lang| move || { calls.set(calls.get() + 1); info(lang) };
+
+        assert_eq!(cache.get_or_detect("a", detect(Lang::Eng)), info(Lang::Eng));
+        assert_eq!(cache.get_or_detect("b", detect(Lang::Deu)), info(Lang::Deu));
+        // Cached, "b" becomes the least recently used
+        assert_eq!(cache.get_or_detect("a", detect(Lang::Fra)), info(Lang::Eng));
+        assert_eq!(calls.get(), 2);
+
+        assert_eq!(cache.get_or_detect("c", detect(Lang::Spa)), info(Lang::Spa));
+        assert_eq!(cache.get_or_detect("a", detect(Lang::Fra)), info(Lang::Eng));
+        assert_eq!(cache.get_or_detect("b", detect(Lang::Ita)), info(Lang::Ita));
+        assert_eq!(calls.get(), 4);
+        assert_eq!(cache.lock().map.len(), 2);
+    }
+
+    #[test]
+    fn test_detection_cache_keeps_errors() {
+        let cache = DetectionCache::new(10);
+        assert_eq!(cache.get_or_detect("", || Err(DetectError::EmptyInput)), Err(DetectError::EmptyInput));
+        assert_eq!(cache.get_or_detect("", || panic!("not cached")), Err(DetectError::EmptyInput));
+    }
+
+    #[test]
+    fn test_detection_cache_checks_texts() {
+        let cache = DetectionCache::new(10);
+        // Entries of different texts with the same hash, as if it were a collision
+        cache.lock().insert(hash_text("a"), "b", info(Lang::Deu), 10);
+        assert_eq!(cache.get_or_detect("a", || info(Lang::Eng)), info(Lang::Eng));
+        assert_eq!(cache.get_or_detect("a", || panic!("not cached")), info(Lang::Eng));
+        assert_eq!(cache.lock().map.len(), 1);
+    }
+
+    #[test]
+    fn test_detection_cache_skips_partial_results() {
+        let cache = DetectionCache::new(10);
+        let partial = Info { partial: true, ..info(Lang::Eng).unwrap() };
+        assert_eq!(cache.get_or_detect("a", || Ok(partial)), Ok(partial));
+        assert_eq!(cache.get_or_detect("a", || Err(DetectError::LowConfidence(partial))),
+                   Err(DetectError::LowConfidence(partial)));
+        assert_eq!(cache.get_or_detect("a", || info(Lang::Deu)), info(Lang::Deu));
+        assert_eq!(cache.get_or_detect("a", || panic!("not cached")), info(Lang::Deu));
+    }
+
+    #[test]
+    fn test_detection_cache_with_zero_capacity() {
+        let cache = DetectionCache::new(0);
+        assert_eq!(cache.get_or_detect("a", || info(Lang::Eng)), info(Lang::Eng));
+        assert_eq!(cache.get_or_detect("a", || info(Lang::Deu)), info(Lang::Deu));
+        assert!(cache.lock().entries.is_empty());
+    }
+}
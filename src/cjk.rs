@@ -0,0 +1,218 @@
+use lang::Lang;
+use options::Options;
+use script::Script;
+
+// Languages, which are detected in texts where Han is the most common script.
+pub(crate) const HAN_LANGS: &[Lang] = &[Lang::Cmn, Lang::Jpn, Lang::Kor, Lang::Yue];
+
+// Japanese texts mix Han (Kanji) with Hiragana and Katakana, often with more Kanji than Kana.
+// Chinese texts contain almost no Kana, so already a small share of it means Japanese.
+const JAPANESE_KANA_SHARE: f64 = 0.05;
+
+// Korean texts may contain Han (Hanja) mixed with Hangul, e.g. in legal, academic or news texts,
+// where Hanja can outnumber Hangul. Hangul is not used in Chinese or Japanese,
+// so it is weighted strongly as well.
+const KOREAN_HANGUL_SHARE: f64 = 0.05;
+
+// Written Cantonese uses its own particles and pronouns in almost every sentence, while Standard
+// Written Chinese (read in Cantonese too) avoids them, except for a word or a quote.
+const CANTONESE_HAN_SHARE: f64 = 0.05;
+
+// A Hangul syllable combines 2-4 letters (jamo), so a Korean text with many English terms
+// may contain more Latin letters than Hangul syllables.
+const HANGUL_SYLLABLE_WEIGHT: usize = 2;
+
+// Common Han chars, which are written only in Japanese: Shinjitai forms
+// (e.g. 駅, 気, 図), which differ from both Simplified and Traditional Chinese, and Kokuji.
+// Sorted, to be found with binary search.
+const JAPANESE_ONLY_HAN: &[char] = &[
+    '亜', '仏', '価', '働', '児', '円', '剤', '労', '勧', '単', '営', '団', '囲', '図', '圧', '塩',
+    '売', '変', '姫', '実', '対', '峠', '巣', '帯', '帰', '庁', '広', '廃', '弾', '従', '徳', '恵',
+    '悩', '悪', '戦', '戻', '払', '拝', '拡', '挙', '捜', '掲', '摂', '斉', '斎', '暦', '枠', '査',
+    '栃', '栄', '桜', '楽', '様', '権', '歩', '歳', '殻', '毎', '気', '沢', '浄', '渋', '満', '滝',
+    '焼', '犠', '獣', '畑', '発', '県', '稲', '穏', '窓', '竜', '粋', '粛', '経', '絵', '続', '総',
+    '緑', '縁', '縦', '繊', '聴', '脳', '舗', '荘', '蔵', '薬', '覚', '覧', '観', '訳', '読', '譲',
+    '賛', '転', '軽', '辻', '込', '遅', '郷', '酔', '鉄', '鉱', '銭', '関', '闘', '陥', '険', '隠',
+    '隣', '雑', '霊', '頼', '顔', '駅', '駆', '騒', '験', '髪', '鶏', '黒', '黙', '齢',
+];
+
+// Common Han chars, which are not used in modern Japanese: Simplified Chinese forms
+// (e.g. 们, 这, 说) and Traditional Chinese forms, replaced by Shinjitai in Japan (e.g. 國, 學).
+// Sorted, to be found with binary search.
+const CHINESE_ONLY_HAN: &[char] = &[
+    '东', '为', '么', '书', '们', '來', '們', '关', '办', '动', '发', '吗', '嗎', '國', '學', '實',
+    '对', '對', '应', '开', '從', '总', '應', '时', '會', '样', '樣', '氣', '热', '电', '發', '种',
+    '經', '總', '经', '给', '聽', '覺', '见', '觉', '說', '讓', '认', '让', '识', '该', '说', '谁',
+    '车', '辦', '边', '过', '还', '这', '进', '這', '邊', '錢', '钟', '钱', '银', '长', '關', '门',
+    '问', '闹', '鬧', '麼',
+];
+
+// Common Han chars of written Cantonese, which are not used in Standard Written Chinese:
+// particles (e.g. 嘅, 咗, 啲), pronouns (佢, 我哋), negations (唔, 冇) and verbs (喺, 睇, 嚟).
+// Sorted, to be found with binary search.
+const CANTONESE_ONLY_HAN: &[char] = &[
+    '乜', '佢', '冇', '咁', '咗', '咩', '哋', '唔', '啲', '喺', '嗰', '嘅', '嘢', '噉', '嚟', '畀',
+    '睇', '諗',
+];
+
+// Resolve a language of a text, where Han is the most common script,
+// using the counts of all scripts in the text.
+// Texts with Han only are told apart by chars which are used only in Japanese or only in Chinese,
+// and Cantonese by its own chars.
+// Returns None, if there is not enough evidence to tell the language, or the language
+// is not allowed by options.
+pub(crate) fn detect_han_lang(text: &str, counts: &[(Script, usize)], options: &Options) -> Option<Lang> {
+    detect_han_lang_by_counts(counts, count_han_chars(text), options)
+}
+
+// Same as detect_han_lang, but with chars, which are used only in Japanese, only in Chinese
+// or only in Cantonese, already counted by count_han_chars.
+pub(crate) fn detect_han_lang_by_counts(counts: &[(Script, usize)], han_chars: HanChars,
+                                        options: &Options) -> Option<Lang> {
+    let han = count_of(counts, Script::Mandarin);
+    let hangul = count_of(counts, Script::Hangul);
+    let kana = count_of(counts, Script::Hiragana) + count_of(counts, Script::Katakana);
+
+    if hangul > kana && hangul as f64 >= (han + hangul) as f64 * KOREAN_HANGUL_SHARE && options.is_allowed(Lang::Kor) {
+        return Some(Lang::Kor);
+    }
+    if kana > 0 && kana as f64 >= (han + kana) as f64 * JAPANESE_KANA_SHARE && options.is_allowed(Lang::Jpn) {
+        return Some(Lang::Jpn);
+    }
+    if han_chars.japanese > han_chars.chinese && options.is_allowed(Lang::Jpn) {
+        return Some(Lang::Jpn);
+    }
+    if han_chars.cantonese > 0 && han_chars.cantonese as f64 >= han as f64 * CANTONESE_HAN_SHARE
+        && options.is_allowed(Lang::Yue) {
+        return Some(Lang::Yue);
+    }
+    None
+}
+
+// Numbers of Han chars, which are used only in Japanese, only in Chinese or only in Cantonese.
+// Chars of Cantonese are Chinese as well, so they are not counted as `chinese`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct HanChars {
+    pub japanese: usize,
+    pub chinese: usize,
+    pub cantonese: usize
+}
+
+impl HanChars {
+    pub fn add(&mut self, other: HanChars) {
+        self.japanese += other.japanese;
+        self.chinese += other.chinese;
+        self.cantonese += other.cantonese;
+    }
+}
+
+pub(crate) fn count_han_chars(text: &str) -> HanChars {
+    let mut result = HanChars::default();
+    for ch in text.chars() {
+        if JAPANESE_ONLY_HAN.binary_search(&ch).is_ok() {
+            result.japanese += 1;
+        } else if CHINESE_ONLY_HAN.binary_search(&ch).is_ok() {
+            result.chinese += 1;
+        } else if CANTONESE_ONLY_HAN.binary_search(&ch).is_ok() {
+            result.cantonese += 1;
+        }
+    }
+    result
+}
+
+// Resolve Korean in a text, where Latin is the most common script. Returns None, if Korean
+// is not allowed by options, so the language is chosen among the ones of Latin.
+pub(crate) fn detect_latin_lang(counts: &[(Script, usize)], options: &Options) -> Option<Lang> {
+    let latin = count_of(counts, Script::Latin);
+    let hangul = count_of(counts, Script::Hangul);
+
+    if hangul * HANGUL_SYLLABLE_WEIGHT > latin && options.is_allowed(Lang::Kor) {
+        return Some(Lang::Kor);
+    }
+    None
+}
+
+fn count_of(counts: &[(Script, usize)], script: Script) -> usize {
+    counts.iter().find(|&&(s, _)| s == script).map_or(0, |&(_, count)| count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use script::script_counts;
+
+    #[test]
+    fn test_detect_han_lang_japanese() {
+        // Kanji-heavy Japanese
+        let text = "東京都知事選挙の結果発表";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Jpn));
+
+        let text = "国立大学法人東京大学大学院情報理工学系研究科のホームページ";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Jpn));
+    }
+
+    #[test]
+    fn test_detect_han_lang_korean_with_hanja() {
+        let text = "大韓民國憲法 第1條 ① 大韓民國은 民主共和國이다. ② 大韓民國의 主權은 國民에게 있고, 모든 權力은 國民으로부터 나온다.";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Kor));
+
+        let text = "漢字語는 韓國語 語彙의 大部分을 차지한다";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Kor));
+    }
+
+    #[test]
+    fn test_detect_latin_lang() {
+        let text = "삼성전자는 Galaxy S24 Ultra를 서울에서 공개했다";
+        assert_eq!(detect_latin_lang(&script_counts(text), &Options::default()), Some(Lang::Kor));
+
+        let text = "BTS (방탄소년단) is a South Korean boy band formed in Seoul";
+        assert_eq!(detect_latin_lang(&script_counts(text), &Options::default()), None);
+    }
+
+    #[test]
+    fn test_han_tables_are_sorted() {
+        for table in [JAPANESE_ONLY_HAN, CHINESE_ONLY_HAN, CANTONESE_ONLY_HAN].iter() {
+            for pair in table.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+            for &ch in table.iter() {
+                assert_eq!(Script::from_char(ch), Some(Script::Mandarin));
+            }
+        }
+        for ch in JAPANESE_ONLY_HAN.iter() {
+            assert!(!CHINESE_ONLY_HAN.contains(ch));
+        }
+    }
+
+    #[test]
+    fn test_detect_han_lang_kanji_only_japanese() {
+        for &text in ["東京駅", "国際経済研究所", "鉄道関係者", "県立図書館"].iter() {
+            assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Jpn), "{}", text);
+        }
+        // Chinese
+        for &text in ["北京东站", "国际经济研究所", "這個問題", "我们的学校"].iter() {
+            assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_han_lang_cantonese() {
+        for &text in ["我哋今日去咗公園玩", "佢唔喺屋企", "呢啲嘢好好食", "你講乜嘢呀？我聽唔明"].iter() {
+            assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), Some(Lang::Yue), "{}", text);
+        }
+
+        // A Cantonese word quoted in a long Chinese text
+        let text = "香港人常说的“唔该”有谢谢和劳驾两种意思，这个词在广东省和澳门也很常用，外地游客来到这里以后很快就学会了";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), None);
+    }
+
+    #[test]
+    fn test_detect_han_lang_without_kana() {
+        let text = "县见夜上温国阪题富贩";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), None);
+
+        // A single stray Kana char in a long Chinese text
+        let text = "我们今天去北京大学参观了图书馆和博物馆，然后在食堂吃了午饭，下午还去了颐和园和圆明园の遗址";
+        assert_eq!(detect_han_lang(text, &script_counts(text), &Options::default()), None);
+    }
+}
@@ -0,0 +1,105 @@
+// Chars, which are common in source code, JSON and markup, but almost never appear in words
+// of natural language texts.
+const CODE_CHARS: &[char] = &['{', '}', '[', ']', '<', '>', '=', ';', '\\', '|', '$', '`', '^', '~', '_'];
+
+// Pairs of chars, which are common in code, but chars of each pair are common in texts.
+const CODE_PAIRS: &[&str] = &["\":", "()", ").", "::", "//", "/*", "*/", "&&", "++", "://"];
+
+// A line is code, when at least this share of its words look like code.
+const CODE_WORD_SHARE: f64 = 0.3;
+
+// A text is code, when at least this share of its lines are code. Lines are counted instead
+// of words, because lines of code (e.g. frames of a stack trace) often contain only one
+// or two long words of code besides keywords.
+const CODE_LINE_SHARE: f64 = 0.5;
+
+/// Tell if a text is predominantly source code, structured data (e.g. JSON, YAML, config files),
+/// markup or a stack trace rather than a natural language text. The check is cheap: words
+/// are looked up for syntax, which is rare in natural languages (e.g. brackets, `=`, `_`,
+/// `foo.bar`, `fooBar` and `foo(`), without parsing the text.
+///
+/// Such texts are mostly written with English keywords and identifiers, so they are often
+/// detected as English (or as some random language) with high confidence, see
+/// [Options::set_skip_code](struct.Options.html#method.set_skip_code).
+///
+/// # Example
+/// ```
+/// use whatlang::is_code;
+///
+/// assert!(is_code(r#"{"user": {"id": 42, "name": "alice", "roles": ["admin"]}}"#));
+/// assert!(is_code("java.lang.NullPointerException\n\tat com.example.App.main(App.java:5)"));
+/// assert!(!is_code("The quick brown fox jumps over the lazy dog."));
+/// ```
+pub fn is_code(text: &str) -> bool {
+    let mut lines = 0;
+    let mut code_lines = 0;
+    for line in text.lines() {
+        let mut words = 0;
+        let mut code_words = 0;
+        for (index, word) in line.split_whitespace().enumerate() {
+            if is_code_word(word, index == 0) {
+                code_words += 1;
+            } else if word.chars().any(char::is_alphabetic) {
+                words += 1;
+            }
+        }
+        // Lines without letters and code (e.g. numbers) are skipped
+        if words + code_words > 0 {
+            lines += 1;
+            if code_words as f64 >= (words + code_words) as f64 * CODE_WORD_SHARE {
+                code_lines += 1;
+            }
+        }
+    }
+    lines > 0 && code_lines as f64 >= lines as f64 * CODE_LINE_SHARE
+}
+
+// Whether a word (any chars between spaces) looks like code. A word at the start of a line
+// ending with a colon is a key of YAML or a similar format.
+fn is_code_word(word: &str, is_first: bool) -> bool {
+    if word.contains(CODE_CHARS) || CODE_PAIRS.iter().any(|pair| word.contains(pair)) {
+        return true;
+    }
+    if is_first && word.len() > 1 && word.ends_with(':') {
+        return true;
+    }
+    let chars: Vec<char> = word.chars().collect();
+    chars.windows(2).any(|pair| (pair[0].is_lowercase() && pair[1].is_uppercase()) || (pair[0].is_alphabetic() && pair[1] == '('))
+        || chars.windows(3).any(|triple| triple[0].is_alphabetic() && triple[1] == '.' && triple[2].is_alphabetic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_code() {
+        let code = [
+            "fn main() {\n    let x = vec![1, 2, 3];\n    println!(\"{:?}\", x);\n}",
+            "def detect(text):\n    counts = {}\n    for ch in text:\n        if ch.isalpha():\n            counts[ch] = counts.get(ch, 0) + 1\n    return counts",
+            "apiVersion: apps/v1\nkind: Deployment\nmetadata:\n  name: nginx-deployment\n  labels:\n    app: nginx",
+            "server.port=8080\nspring.datasource.url=jdbc:postgresql://localhost/db\nlogging.level.root=INFO",
+            "<div class=\"header\"><a href=\"/home\">Home</a> <span>Welcome back</span></div>",
+            "Exception in thread \"main\" java.lang.IllegalStateException: Connection is closed\n\
+             \tat com.example.db.Pool.acquire(Pool.java:118)\n\
+             \tat com.example.db.Repository.findAll(Repository.java:42)\n\
+             \tat com.example.App.main(App.java:17)",
+        ];
+        for text in code.iter() {
+            assert!(is_code(text), "{}", text);
+        }
+
+        let texts = [
+            "There is no reason not to learn Esperanto, e.g. it is easy (and fun) to speak.",
+            "Sa kabila ng lahat, nagpatuloy pa rin ang mga mag-aaral sa kanilang pag-aaral.",
+            "Der Sommer war sehr heiß: die Kinder spielten jeden Tag im Garten.",
+            "Note: the meeting starts at 10:30, see https://example.com for the agenda.",
+            "Привет! Как дела? Всё хорошо, спасибо.",
+        ];
+        for text in texts.iter() {
+            assert!(!is_code(text), "{}", text);
+        }
+        assert!(!is_code(""));
+        assert!(!is_code("42 + 17"));
+    }
+}
@@ -1,6 +1,9 @@
 // Maximum distance(difference) for a trigram in a language profile and text profile.
 pub const MAX_TRIGRAM_DISTANCE: u32 = 300;
 
+// Number of trigrams in a profile of a language.
+pub const LANG_PROFILE_SIZE: usize = 300;
+
 // 300 trigrams where each has MAX_TOTAL_DISTANCE=300, gives us 90_000.
 pub const MAX_TOTAL_DISTANCE: u32 = 90_000;
 
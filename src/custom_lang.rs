@@ -0,0 +1,96 @@
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use lazy::Lazy;
+
+// Codes of all custom languages, indexed by CustomLang. Codes are never removed, so they
+// are leaked to be returned as &'static str.
+static CODES: Lazy<Mutex<Vec<&'static str>>> = Lazy::new();
+
+/// A language, which is not in [Lang](enum.Lang.html), identified by an arbitrary code
+/// (e.g. `"tir"` for Tigrinya, `"wol"` for Wolof, or a pseudo-language like `"product-codes"`).
+///
+/// Custom languages are detected by profiles added with
+/// [ProfileModel::add_custom_profile](struct.ProfileModel.html#method.add_custom_profile),
+/// and reported by [Info::custom_lang](struct.Info.html#method.custom_lang).
+/// Languages with the same code are equal, a code is registered once per process.
+///
+/// # Example
+/// ```
+/// use whatlang::CustomLang;
+///
+/// let wolof = CustomLang::new("wol");
+/// assert_eq!(wolof, CustomLang::new("wol"));
+/// assert_eq!(wolof.code(), "wol");
+/// assert_eq!(wolof.to_string(), "wol");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CustomLang(u32);
+
+impl CustomLang {
+    /// The custom language with the code, registered on the first use of the code.
+    pub fn new<S: AsRef<str>>(code: S) -> Self {
+        let code = code.as_ref();
+        let mut codes = lock_codes();
+        let index = match codes.iter().position(|&other| other == code) {
+            Some(index) => index,
+            None => {
+                codes.push(Box::leak(code.to_string().into_boxed_str()));
+                codes.len() - 1
+            }
+        };
+        CustomLang(index as u32)
+    }
+
+    pub fn code(self) -> &'static str {
+        lock_codes()[self.0 as usize]
+    }
+}
+
+fn lock_codes() -> MutexGuard<'static, Vec<&'static str>> {
+    // Codes are only read and pushed, so they stay consistent even if a thread panicked
+    let codes = CODES.get_or_init(|| Mutex::new(vec![]));
+    codes.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+impl fmt::Display for CustomLang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl fmt::Debug for CustomLang {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CustomLang({:?})", self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ::serde::Serialize for CustomLang {
+    fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::Deserialize<'de> for CustomLang {
+    fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(CustomLang::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_lang_is_interned() {
+        let tigrinya = CustomLang::new("tir-test");
+        let codes = CustomLang::new(String::from("product-codes-test"));
+        assert_eq!(tigrinya, CustomLang::new("tir-test"));
+        assert_ne!(tigrinya, codes);
+        assert_eq!(tigrinya.code(), "tir-test");
+        assert_eq!(codes.code(), "product-codes-test");
+        assert_eq!(format!("{:?}", tigrinya), "CustomLang(\"tir-test\")");
+    }
+}
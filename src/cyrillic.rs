@@ -0,0 +1,109 @@
+use lang::Lang;
+use script::Script;
+
+// Letters, which Turkic languages (and Mongolian) add to the Russian alphabet, "і" of Kazakh,
+// Ukrainian and Belarusian, and "ў" of Uzbek and Belarusian. Each language of the Cyrillic script
+// uses only some of them.
+const SPECIFIC_LETTERS: [char; 12] = ['ғ', 'җ', 'қ', 'ң', 'ү', 'ұ', 'ҳ', 'һ', 'і', 'ў', 'ә', 'ө'];
+
+// Specific letters used by the languages of the Cyrillic script, the other ones are never used
+// by the language, so a text with them is not written in it.
+const LANG_LETTERS: &[(Lang, &[char])] = &[
+    (Lang::Azj, &['ғ', 'ү', 'һ', 'ә', 'ө']),
+    (Lang::Bel, &['і', 'ў']),
+    (Lang::Bul, &[]),
+    (Lang::Kaz, &['ғ', 'қ', 'ң', 'ү', 'ұ', 'һ', 'і', 'ә', 'ө']),
+    (Lang::Khk, &['ү', 'ө']),
+    (Lang::Kir, &['ң', 'ү', 'ө']),
+    (Lang::Mkd, &[]),
+    (Lang::Rus, &[]),
+    (Lang::Srp, &[]),
+    (Lang::Tat, &['җ', 'ң', 'ү', 'һ', 'ә', 'ө']),
+    (Lang::Tuk, &['җ', 'ң', 'ү', 'ә', 'ө']),
+    (Lang::Ukr, &['і']),
+    (Lang::Uzb, &['ғ', 'қ', 'ҳ', 'ў']),
+];
+
+// A language is not a candidate, when a larger share of the Cyrillic chars of a text are
+// specific letters which it does not use. A few of them are allowed in longer texts, e.g. in names.
+const MAX_FOREIGN_SHARE: f64 = 0.01;
+
+// Remove the candidates of a text of the Cyrillic script, which do not use some of its letters,
+// so they are not scored by trigrams at all. If every candidate would be removed, none is.
+pub(crate) fn filter_cyrillic_langs(langs: Vec<Lang>, counts: &[(Script, usize)], letters: CyrillicLetters) -> Vec<Lang> {
+    let cyrillic = counts.iter().find(|&&(script, _)| script == Script::Cyrillic).map_or(0, |&(_, count)| count);
+    let is_foreign = |lang: Lang| {
+        let used = match LANG_LETTERS.iter().find(|&&(letters_lang, _)| letters_lang == lang) {
+            Some(&(_, used)) => used,
+            None => return false
+        };
+        let foreign: usize = SPECIFIC_LETTERS.iter().zip(letters.counts.iter())
+            .filter(|&(letter, _)| !used.contains(letter))
+            .map(|(_, &count)| count)
+            .sum();
+        foreign as f64 > cyrillic as f64 * MAX_FOREIGN_SHARE
+    };
+
+    if langs.iter().all(|&lang| is_foreign(lang)) {
+        return langs;
+    }
+    langs.into_iter().filter(|&lang| !is_foreign(lang)).collect()
+}
+
+// Numbers of every specific letter in a text, in the same order as SPECIFIC_LETTERS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CyrillicLetters {
+    pub counts: [usize; 12]
+}
+
+impl CyrillicLetters {
+    pub fn add(&mut self, other: CyrillicLetters) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other_count;
+        }
+    }
+}
+
+pub(crate) fn count_cyrillic_letters(text: &str) -> CyrillicLetters {
+    let mut result = CyrillicLetters::default();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if let Some(index) = SPECIFIC_LETTERS.iter().position(|&letter| letter == ch) {
+            result.counts[index] += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use script::script_counts;
+
+    fn filter(text: &str) -> Vec<Lang> {
+        let langs = LANG_LETTERS.iter().map(|&(lang, _)| lang).collect();
+        filter_cyrillic_langs(langs, &script_counts(text), count_cyrillic_letters(text))
+    }
+
+    #[test]
+    fn test_filter_cyrillic_langs() {
+        assert_eq!(filter("Биз эртең жолугабыз."), vec![Lang::Kaz, Lang::Kir, Lang::Tat, Lang::Tuk]);
+        assert_eq!(filter("Біз ертең кездесеміз."), vec![Lang::Kaz]);
+        assert_eq!(filter("Без иртәгә очрашабыз."), vec![Lang::Azj, Lang::Kaz, Lang::Tat, Lang::Tuk]);
+        assert_eq!(filter("ҚАЗАҚСТАН"), vec![Lang::Kaz, Lang::Uzb]);
+        assert_eq!(filter("Тошкент шаҳри"), vec![Lang::Uzb]);
+        assert_eq!(filter("Спасибо, всё хорошо.").len(), LANG_LETTERS.len());
+    }
+
+    #[test]
+    fn test_filter_cyrillic_langs_allows_few_foreign_letters() {
+        // A Kazakh name in a longer Russian text
+        let text = "Вчера в Москве прошла встреча министров иностранных дел, на которой обсуждались вопросы \
+                    торговли, транспорта и энергетики. Делегации договорились продолжить переговоры в следующем \
+                    месяце и подготовить новое соглашение о сотрудничестве. Казахстан представлял Мұхтар";
+        assert!(filter(text).contains(&Lang::Rus));
+
+        // Letters foreign to all candidates do not remove them all
+        assert_eq!(filter_cyrillic_langs(vec![Lang::Rus, Lang::Ukr], &script_counts("қ"), count_cyrillic_letters("қ")),
+                   vec![Lang::Rus, Lang::Ukr]);
+    }
+}
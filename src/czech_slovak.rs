@@ -0,0 +1,158 @@
+use lang::Lang;
+
+// Czech and Slovak are mutually intelligible and their trigram profiles are close, so short texts
+// are often detected as the other language. Their orthographies differ though: only Czech has
+// "ř", "ů" and "ě", only Slovak has "ľ", "ĺ", "ŕ", "ä" and "ô". The most common words also differ:
+// the reflexive pronoun ("se" and "sa"), forms of "to be" ("jsem" and "som"), conjunctions
+// ("nebo" and "alebo", "jako" and "ako").
+const CZECH_LETTERS: &[char] = &['ř', 'ů', 'ě'];
+const SLOVAK_LETTERS: &[char] = &['ľ', 'ĺ', 'ŕ', 'ä', 'ô'];
+
+// Words are sorted, to be found with binary search.
+const CZECH_WORDS: &[&str] = &[
+    "bych", "byl", "byla", "bylo", "být", "chci", "co", "jako", "jen", "ještě", "jsem", "jsi",
+    "jsme", "jsou", "jste", "když", "kterou", "která", "které", "který", "mezi", "ne", "nebo",
+    "není", "něco", "pro", "proto", "protože", "proč", "při", "se", "tady", "také", "teď", "ve",
+    "velmi", "ze",
+];
+
+const SLOVAK_WORDS: &[&str] = &[
+    "ako", "alebo", "bol", "bola", "bolo", "byť", "chcem", "ešte", "keď", "ktorá", "ktoré", "ktorú",
+    "ktorý", "len", "medzi", "nie", "niečo", "pre", "preto", "pretože", "prečo", "pri", "sa", "sme",
+    "som", "ste", "sú", "teraz", "tiež", "veľmi", "vo", "zo", "čo",
+];
+
+// Choose between Czech and Slovak by the letters and words, which are used only in one
+// of the languages, when `lang` (the best language) or one of its `rivals` is Czech or Slovak.
+// The words count only when both of them are near the top and one of them is the best, since some
+// of the words are common in other Slavic languages too ("se", "pri", "nie").
+// Returns None if the text has as much evidence of one language as of the other one.
+pub(crate) fn resolve_czech_slovak(text: &str, lang: Lang, rivals: &[Lang]) -> Option<Lang> {
+    if !is_czech_slovak(lang) && !rivals.iter().any(|&rival| is_czech_slovak(rival)) {
+        return None;
+    }
+    resolve_czech_slovak_by_counts(count_czech_slovak_letters(text), count_czech_slovak_words(text), lang, rivals)
+}
+
+// Same as resolve_czech_slovak, but with the letters and words already counted.
+pub(crate) fn resolve_czech_slovak_by_counts(letters: CzechSlovakEvidence, words: CzechSlovakEvidence,
+                                             lang: Lang, rivals: &[Lang]) -> Option<Lang> {
+    let is_near_top = |near_lang: Lang| lang == near_lang || rivals.contains(&near_lang);
+    if !is_near_top(Lang::Ces) && !is_near_top(Lang::Slk) {
+        return None;
+    }
+
+    let mut evidence = letters;
+    if is_czech_slovak(lang) && is_near_top(Lang::Ces) && is_near_top(Lang::Slk) {
+        evidence.add(words);
+    }
+
+    if evidence.czech > evidence.slovak {
+        Some(Lang::Ces)
+    } else if evidence.slovak > evidence.czech {
+        Some(Lang::Slk)
+    } else {
+        None
+    }
+}
+
+// Numbers of letters and words, which are used only in Czech or only in Slovak.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CzechSlovakEvidence {
+    pub czech: usize,
+    pub slovak: usize
+}
+
+impl CzechSlovakEvidence {
+    pub fn add(&mut self, other: CzechSlovakEvidence) {
+        self.czech += other.czech;
+        self.slovak += other.slovak;
+    }
+}
+
+// Letters are counted separately from words, the same as in bulgarian_macedonian.
+pub(crate) fn count_czech_slovak_letters(text: &str) -> CzechSlovakEvidence {
+    let mut result = CzechSlovakEvidence::default();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if CZECH_LETTERS.contains(&ch) {
+            result.czech += 1;
+        } else if SLOVAK_LETTERS.contains(&ch) {
+            result.slovak += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn count_czech_slovak_words(text: &str) -> CzechSlovakEvidence {
+    let mut result = CzechSlovakEvidence::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(CZECH_WORDS, &word) {
+            result.czech += 1;
+        } else if contains(SLOVAK_WORDS, &word) {
+            result.slovak += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_czech_slovak(lang: Lang) -> bool {
+    lang == Lang::Ces || lang == Lang::Slk
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [CZECH_WORDS, SLOVAK_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in CZECH_WORDS.iter() {
+            assert!(!contains(SLOVAK_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_czech_slovak() {
+        let text = "Studenti se připravují na zkoušku celý týden.";
+        assert_eq!(resolve_czech_slovak(text, Lang::Slk, &[Lang::Ces]), Some(Lang::Ces));
+
+        let text = "Študenti sa pripravujú na skúšku celý týždeň.";
+        assert_eq!(resolve_czech_slovak(text, Lang::Ces, &[Lang::Pol, Lang::Slk]), Some(Lang::Slk));
+
+        // Without evidence the language is not resolved
+        let text = "Kde je pošta?";
+        assert_eq!(resolve_czech_slovak(text, Lang::Slk, &[Lang::Ces]), None);
+
+        // Only the letters count, unless both languages are near the top and one of them is the best
+        let text = "Chci si koupit chleba a mléko, ale obchod je zavřený.";
+        assert_eq!(resolve_czech_slovak(text, Lang::Slv, &[Lang::Slk]), Some(Lang::Ces));
+        assert_eq!(resolve_czech_slovak(text, Lang::Slk, &[Lang::Pol]), Some(Lang::Ces));
+        let text = "Študenti sa pripravujú na skúšku celý týždeň.";
+        assert_eq!(resolve_czech_slovak(text, Lang::Slv, &[Lang::Ces, Lang::Slk]), None);
+        assert_eq!(resolve_czech_slovak(text, Lang::Ces, &[Lang::Pol]), None);
+
+        // Neither language is near the top
+        let text = "Chci si koupit chleba a mléko, ale obchod je zavřený.";
+        assert_eq!(resolve_czech_slovak(text, Lang::Slv, &[Lang::Hrv, Lang::Pol]), None);
+    }
+
+    #[test]
+    fn test_count_czech_slovak_evidence() {
+        let text = "Dnes je krásné počasí a půjdeme do parku, protože je teď teplo.";
+        assert_eq!(count_czech_slovak_letters(text), CzechSlovakEvidence { czech: 1, slovak: 0 });
+        assert_eq!(count_czech_slovak_words(text), CzechSlovakEvidence { czech: 2, slovak: 0 });
+
+        let text = "MÔJ KAMARÁT JE ZO ŠKOLY A BOL TU VČERA.";
+        assert_eq!(count_czech_slovak_letters(text), CzechSlovakEvidence { czech: 0, slovak: 1 });
+        assert_eq!(count_czech_slovak_words(text), CzechSlovakEvidence { czech: 0, slovak: 2 });
+    }
+}
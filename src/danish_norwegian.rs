@@ -0,0 +1,122 @@
+use lang::Lang;
+use norwegian::is_norwegian;
+
+// Danish and Norwegian have almost the same orthography, so their trigram profiles are very close
+// and texts of one language are often detected as the other one. They differ mostly in the spelling
+// of the most common words: prepositions ("af" and "av", "efter" and "etter"), pronouns
+// ("mig" and "meg", "hvad" and "hva"), adverbs ("nu" and "nå"), the infinitive marker ("at" and "å").
+// Norwegian words include the words of both Bokmål and Nynorsk.
+//
+// Words are sorted, to be found with binary search.
+const DANISH_WORDS: &[&str] = &[
+    "af", "blev", "bliver", "dig", "dreng", "efter", "end", "gør", "havde", "hedder", "hende", "hvad",
+    "hvornår", "igen", "jer", "købe", "lidt", "lige", "mellem", "mig", "mod", "måske", "nej", "nogen",
+    "noget", "nogle", "nu", "op", "pige", "sig", "siger", "sådan", "tak", "ud", "uden", "vej", "vores",
+];
+
+const NORWEGIAN_WORDS: &[&str] = &[
+    "av", "ble", "blir", "deg", "dere", "eg", "enn", "etter", "gjer", "gjør", "gutt", "hadde", "heiter",
+    "henne", "heter", "ho", "hva", "igjen", "ikkje", "jente", "kanskje", "kjøpe", "kva", "litt", "meg",
+    "mellom", "mot", "mye", "mykje", "nei", "noe", "noen", "noko", "nokon", "nå", "opp", "seg", "seier",
+    "sier", "slik", "sånn", "takk", "ut", "utan", "uten", "vart", "vei", "vår", "våre", "å",
+];
+
+// Choose between Danish and Norwegian by the words, which are used only in one of the languages.
+// Norwegian is returned as `lang` if it is one of the standards of Norwegian, and as Bokmål otherwise,
+// so the standard can be resolved afterwards. Returns None if `lang` is neither Danish nor Norwegian,
+// or if the text has as many words of one language as of the other one.
+pub(crate) fn resolve_danish_norwegian(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_danish_norwegian(lang) {
+        return None;
+    }
+    resolve_danish_norwegian_by_counts(count_danish_norwegian_words(text), lang)
+}
+
+// Same as resolve_danish_norwegian, but with the words already counted by count_danish_norwegian_words.
+pub(crate) fn resolve_danish_norwegian_by_counts(words: DanishNorwegianWords, lang: Lang) -> Option<Lang> {
+    if !is_danish_norwegian(lang) {
+        return None;
+    }
+
+    if words.danish > words.norwegian {
+        Some(Lang::Dan)
+    } else if words.norwegian > words.danish {
+        Some(if is_norwegian(lang) { lang } else { Lang::Nob })
+    } else {
+        None
+    }
+}
+
+// Numbers of words, which are used only in Danish or only in Norwegian.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DanishNorwegianWords {
+    pub danish: usize,
+    pub norwegian: usize
+}
+
+impl DanishNorwegianWords {
+    pub fn add(&mut self, other: DanishNorwegianWords) {
+        self.danish += other.danish;
+        self.norwegian += other.norwegian;
+    }
+}
+
+pub(crate) fn count_danish_norwegian_words(text: &str) -> DanishNorwegianWords {
+    let mut result = DanishNorwegianWords::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(DANISH_WORDS, &word) {
+            result.danish += 1;
+        } else if contains(NORWEGIAN_WORDS, &word) {
+            result.norwegian += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_danish_norwegian(lang: Lang) -> bool {
+    lang == Lang::Dan || is_norwegian(lang)
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [DANISH_WORDS, NORWEGIAN_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in DANISH_WORDS.iter() {
+            assert!(!contains(NORWEGIAN_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_danish_norwegian() {
+        let text = "Hvad siger du nu?";
+        assert_eq!(resolve_danish_norwegian(text, Lang::Nob), Some(Lang::Dan));
+
+        let text = "Hva sier du nå?";
+        assert_eq!(resolve_danish_norwegian(text, Lang::Dan), Some(Lang::Nob));
+        assert_eq!(resolve_danish_norwegian(text, Lang::Nno), Some(Lang::Nno));
+
+        // Without evidence the language is not resolved
+        let text = "Jeg har en hund.";
+        assert_eq!(resolve_danish_norwegian(text, Lang::Nob), None);
+
+        assert_eq!(resolve_danish_norwegian(text, Lang::Swe), None);
+    }
+
+    #[test]
+    fn test_count_danish_norwegian_words() {
+        let text = "Hun kom hjem efter skole. Hun kom hjem etter skolen, men ble syk.";
+        assert_eq!(count_danish_norwegian_words(text), DanishNorwegianWords { danish: 1, norwegian: 2 });
+    }
+}
@@ -1,11 +1,27 @@
-use fnv::FnvHashMap;
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+use std::time::Instant;
 
 use lang::*;
+use custom_lang::CustomLang;
 use script::*;
-use trigrams::*;
 use info::Info;
-use options::{Options, List};
-use constants::{MAX_TRIGRAM_DISTANCE, MAX_TOTAL_DISTANCE};
+use options::Options;
+use model::{LanguageModel, Ranking, TrigramModel};
+use trigrams::Scratch;
+use sample::sample;
+use cjk::{detect_han_lang, detect_latin_lang};
+use arabic::{count_arabic_letters, filter_arabic_langs};
+use cyrillic::{count_cyrillic_letters, filter_cyrillic_langs};
+use latin::{count_latin_letters, filter_latin_langs};
+use preprocess::preprocess;
+use code::is_code;
+use version::model_version;
+
+// The first sample of a text, which is detected with a time limit, and the growth of the next ones.
+const FIRST_TIMED_SAMPLE_SIZE: usize = 4 * 1024;
+const TIMED_SAMPLE_GROWTH: u32 = 4;
 
 /// Detect a language and a script by a given text.
 ///
@@ -33,163 +49,779 @@ pub fn detect_lang(text: &str) -> Option<Lang> {
     detect(text).map(|info| info.lang)
 }
 
+/// Detect only a language by a given text, using the given options.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_lang_with_options, Lang, Options};
+///
+/// let options = Options::new().set_whitelist(vec![Lang::Eng, Lang::Spa]);
+/// let lang = detect_lang_with_options("There is no reason not to learn Esperanto.", &options);
+/// assert_eq!(lang, Some(Lang::Eng));
+/// ```
 pub fn detect_lang_with_options(text: &str, options: &Options) -> Option<Lang> {
     detect_with_options(text, options).map(|info| info.lang)
 }
 
+/// Detect only a language by a given text, which is known to be written in the given script
+/// (e.g. the script is stored with the text). Scripts of chars are not looked up at all,
+/// so the language is detected faster, but chars of other scripts are scored as if they
+/// were of the given one.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_lang_with_script, Lang, Script};
+/// let lang = detect_lang_with_script("Введите запрос, чтобы найти нужную информацию", Script::Cyrillic);
+/// assert_eq!(lang, Some(Lang::Rus));
+/// ```
+pub fn detect_lang_with_script(text: &str, script: Script) -> Option<Lang> {
+    detect_lang_with_script_and_options(text, script, &Options::default())
+}
+
+/// Detect only a language by a given text, which is known to be written in the given script,
+/// using the given options, see [detect_lang_with_script](fn.detect_lang_with_script.html).
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_lang_with_script_and_options, Lang, Options, Script};
+///
+/// let options = Options::new().set_whitelist(vec![Lang::Rus, Lang::Ukr]);
+/// let lang = detect_lang_with_script_and_options("Ласкаво просимо до нашого міста!", Script::Cyrillic, &options);
+/// assert_eq!(lang, Some(Lang::Ukr));
+/// ```
+pub fn detect_lang_with_script_and_options(text: &str, script: Script, options: &Options) -> Option<Lang> {
+    detect_lang_with_script_and_model(text, script, options, &TrigramModel)
+}
+
+/// Detect a language and a script by a given text, using the given options.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_with_options, Lang, Options};
+///
+/// let options = Options::new()
+///     .set_blacklist(vec![Lang::Ita])
+///     .set_min_confidence(0.5);
+/// let text = "Ĉu vi ne volas eklerni Esperanton? Bonvolu! Estas unu de la plej bonaj aferoj!";
+/// let info = detect_with_options(text, &options).unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+/// ```
 pub fn detect_with_options(text: &str, options: &Options) -> Option<Info> {
-    detect_script(text).and_then(|script| {
-        detect_lang_based_on_script(text, options, script).map( |(lang, confidence)| {
-            Info { lang, script, confidence }
-        })
-    })
+    detect_with_model(text, options, &TrigramModel)
+}
+
+/// Detect a language and a script by a given text, reporting why detection failed.
+///
+/// # Example
+/// ```
+/// use whatlang::{try_detect, DetectError, Lang};
+///
+/// let info = try_detect("Ĉu vi ne volas eklerni Esperanton? Bonvolu!").unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+/// assert_eq!(try_detect("  "), Err(DetectError::EmptyInput));
+/// assert_eq!(try_detect("42!"), Err(DetectError::NoScript));
+/// ```
+pub fn try_detect(text: &str) -> Result<Info, DetectError> {
+    try_detect_with_options(text, &Options::default())
+}
+
+/// Detect a language and a script by a given text, using the given options and reporting
+/// why detection failed.
+///
+/// # Example
+/// ```
+/// use whatlang::{try_detect_with_options, DetectError, Options};
+///
+/// let options = Options::new().set_min_confidence(0.9);
+/// match try_detect_with_options("qwertyuioasdfghjklzxcvbnm", &options) {
+///     Err(DetectError::LowConfidence(info)) => assert!(info.confidence() < 0.9),
+///     result => panic!("Unexpected result: {:?}", result)
+/// }
+/// ```
+pub fn try_detect_with_options(text: &str, options: &Options) -> Result<Info, DetectError> {
+    try_detect_with_model(text, options, &TrigramModel)
+}
+
+/// An error returned by [try_detect](fn.try_detect.html), which tells why a language
+/// was not detected.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetectError {
+    /// The text is empty or consists of whitespace only.
+    EmptyInput,
+    /// The text contains no chars of supported scripts, e.g. only digits and punctuation.
+    NoScript,
+    /// The text contains less letters than required by `Options::set_min_length`.
+    TooShort,
+    /// The script is detected, but none of its languages is supported or allowed by options.
+    NoLanguage(Script),
+    /// The language is detected with confidence below `Options::set_min_confidence`.
+    LowConfidence(Info),
+    /// The text is source code, structured data or markup rather than a natural language text,
+    /// see `Options::set_skip_code`.
+    Code,
+}
+
+impl fmt::Display for DetectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DetectError::EmptyInput => write!(f, "empty input"),
+            DetectError::NoScript => write!(f, "no recognizable script"),
+            DetectError::TooShort => write!(f, "text is too short"),
+            DetectError::Code => write!(f, "text is code or markup"),
+            DetectError::NoLanguage(script) => write!(f, "no language detected for script {}", script),
+            DetectError::LowConfidence(ref info) => {
+                write!(f, "confidence {} of {} is below the threshold", info.confidence, info.lang.eng_name())
+            }
+        }
+    }
 }
 
-fn detect_lang_based_on_script(text: &str, options: &Options, script : Script) -> Option<(Lang, f64)> {
+impl Error for DetectError {}
+
+pub(crate) fn detect_with_model<M: LanguageModel + ?Sized>(text: &str, options: &Options, model: &M) -> Option<Info> {
+    try_detect_with_model(text, options, model).ok()
+}
+
+pub(crate) fn try_detect_with_model<M: LanguageModel + ?Sized>(text: &str, options: &Options, model: &M) -> Result<Info, DetectError> {
+    try_detect_with_model_in(text, options, model, &mut Scratch::new())
+}
+
+pub(crate) fn try_detect_with_model_in<M: LanguageModel + ?Sized>(text: &str, options: &Options, model: &M,
+                                                                   scratch: &mut Scratch) -> Result<Info, DetectError> {
+    enter_span!("detect", len = text.len());
+    // All scripts are counted (instead of detect_script_with_options) to report the secondary one
+    // and to resolve languages which mix several scripts.
+    detect_by_counted_scripts(text, options, model, scratch, |text| script_counts_with_options(text, options))
+}
+
+pub(crate) fn detect_lang_with_script_and_model<M: LanguageModel + ?Sized>(text: &str, script: Script, options: &Options,
+                                                                           model: &M) -> Option<Lang> {
+    enter_span!("detect_lang_with_script", len = text.len());
+    // Every letter is counted for the given script, so the checks of the other scripts
+    // (e.g. Kana in a Han text) do not apply.
+    let count_letters = |text: &str| match text.chars().filter(|ch| ch.is_alphabetic()).count() {
+        0 => vec![],
+        letters => vec![(script, letters)]
+    };
+    detect_by_counted_scripts(text, options, model, &mut Scratch::new(), count_letters).ok().map(|info| info.lang)
+}
+
+// Preprocess the text and detect it (or its samples, with a sample size or a time limit)
+// by chars of scripts, which are counted by `count_scripts`.
+fn detect_by_counted_scripts<M, S>(text: &str, options: &Options, model: &M, scratch: &mut Scratch,
+                                   count_scripts: S) -> Result<Info, DetectError>
+    where M: LanguageModel + ?Sized,
+          S: Fn(&str) -> Vec<(Script, usize)>
+{
+    let deadline = deadline(options);
+    let text = preprocess(text, options);
+    let text = text.as_ref();
+
+    if text.trim().is_empty() {
+        return Err(DetectError::EmptyInput);
+    }
+    if options.skip_code && is_code(text) {
+        return Err(DetectError::Code);
+    }
+
+    let text = match options.sample_size {
+        Some(size) => sample(text, size),
+        None => text.into()
+    };
+    let text = text.as_ref();
+
+    // Shared by the model for built-in and custom languages
+    let scratch = RefCell::new(scratch);
+    let detect = |text: &str| {
+        let counts = count_scripts(text);
+        detect_by_script_counts(&counts, options,
+                                |counts| detect_han_lang(text, counts, options),
+                                |script| match script {
+                                    Script::Arabic => filter_arabic_langs(model.script_langs(script, options), &counts,
+                                                                          count_arabic_letters(text)),
+                                    Script::Cyrillic => filter_cyrillic_langs(model.script_langs(script, options), &counts,
+                                                                              count_cyrillic_letters(text)),
+                                    Script::Latin => filter_latin_langs(model.script_langs(script, options), &counts,
+                                                                        count_latin_letters(text)),
+                                    _ => model.script_langs(script, options)
+                                },
+                                |script, candidates| model.detect_lang_with_second_best_in(text, script, candidates, options, &mut scratch.borrow_mut()),
+                                |script, lang| model.detect_custom_lang_in(text, script, lang, options, &mut scratch.borrow_mut()))
+    };
+    match deadline {
+        Some(deadline) => detect_in_time(text, deadline, detect),
+        None => detect(text)
+    }
+}
+
+// The clock is read only with a time limit. There is no clock on wasm32-unknown-unknown
+// (`Instant::now` panics), so the limit is ignored there.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn deadline(options: &Options) -> Option<Instant> {
+    options.max_duration.map(|duration| Instant::now() + duration)
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn deadline(_options: &Options) -> Option<Instant> {
+    None
+}
+
+// Detects growing samples of the text until the whole text is detected or the next sample
+// is not expected to be detected before the deadline. Time of detection is about linear
+// in the size of a sample, so the next one takes TIMED_SAMPLE_GROWTH times longer.
+fn detect_in_time<F>(text: &str, deadline: Instant, mut detect: F) -> Result<Info, DetectError>
+    where F: FnMut(&str) -> Result<Info, DetectError>
+{
+    let mut size = FIRST_TIMED_SAMPLE_SIZE;
+    loop {
+        let part = sample(text, size);
+        if part.len() == text.len() {
+            return detect(text);
+        }
+        let part_start = Instant::now();
+        let result = detect(&part);
+        let now = Instant::now();
+        if now + (now - part_start) * TIMED_SAMPLE_GROWTH >= deadline {
+            return match result {
+                Ok(info) => Ok(Info { partial: true, ..info }),
+                Err(DetectError::LowConfidence(info)) => Err(DetectError::LowConfidence(Info { partial: true, ..info })),
+                Err(err) => Err(err)
+            };
+        }
+        size *= TIMED_SAMPLE_GROWTH as usize;
+    }
+}
+
+// Detect a language by already counted chars of scripts. The text itself is accessed only
+// by `detect_han_lang` (for texts where Han is the most common script) and `detect_lang`
+// (to choose between candidate languages of a script, with the runner-up one). Candidates are taken from `script_langs`.
+pub(crate) fn detect_by_script_counts<H, L, D, C>(counts: &[(Script, usize)], options: &Options, detect_han_lang: H,
+                                                  script_langs: L, detect_lang: D, detect_custom_lang: C) -> Result<Info, DetectError>
+    where H: FnOnce(&[(Script, usize)]) -> Option<Lang>,
+          L: FnOnce(Script) -> Vec<Lang>,
+          D: FnOnce(Script, &[Lang]) -> Option<Ranking>,
+          C: FnOnce(Script, Lang) -> Option<(CustomLang, f64)>
+{
+    let scripts = script_shares(counts);
+    let script = scripts.first().ok_or(DetectError::NoScript)?.0;
+    if counts.iter().map(|&(_, count)| count).sum::<usize>() < options.min_length {
+        return Err(DetectError::TooShort);
+    }
+    let secondary_script = scripts.get(1).cloned();
+    debug_event!(?script, ?secondary_script, "detected script");
+
+    let lang_info = match script {
+        Script::Mandarin => detect_han_lang(counts).map(|lang| (lang, 1.0, None)),
+        Script::Latin => detect_latin_lang(counts, options).map(|lang| (lang, 1.0, None)),
+        _ => None
+    };
+
+    // Custom languages compete only with languages chosen by the model, not by the heuristics
+    // or by a script of one language.
+    let is_scored = lang_info.is_none() && script_lang(script).is_none();
+    let (lang, confidence, second_best) = lang_info.or_else(|| detect_lang_based_on_script(options, script, script_langs, detect_lang))
+        .ok_or(DetectError::NoLanguage(script))?;
+    let custom = if is_scored { detect_custom_lang(script, lang) } else { None };
+    let confidence = custom.map_or(confidence, |(_, custom_confidence)| custom_confidence);
+    let custom_lang = custom.map(|(custom_lang, _)| custom_lang);
+    let info = Info { lang, script, confidence, secondary_script, second_best, custom_lang,
+                      model_version: model_version(), partial: false };
+    if info.confidence < options.min_confidence {
+        debug_event!(?lang, confidence, "confidence is below the threshold");
+        return Err(DetectError::LowConfidence(info));
+    }
+    Ok(info)
+}
+
+fn detect_lang_based_on_script<L, D>(options: &Options, script: Script, script_langs: L,
+                                     detect_lang: D) -> Option<Ranking>
+    where L: FnOnce(Script) -> Vec<Lang>,
+          D: FnOnce(Script, &[Lang]) -> Option<Ranking>
+{
+    match script_lang(script) {
+        Some(lang) if options.is_allowed(lang) => Some((lang, 1.0, None)),
+        _ => detect_lang_in_candidates(options, script, script_langs, detect_lang)
+    }
+}
+
+// The only language of a script, or None if the script is used by several languages
+// or only by languages which are not supported.
+pub(crate) fn script_lang(script: Script) -> Option<Lang> {
     match script {
-        Script::Latin      => detect_lang_in_profiles(text, options, LATIN_LANGS),
-        Script::Cyrillic   => detect_lang_in_profiles(text, options, CYRILLIC_LANGS),
-        Script::Devanagari => detect_lang_in_profiles(text, options, DEVANAGARI_LANGS),
-        Script::Hebrew     => detect_lang_in_profiles(text, options, HEBREW_LANGS),
-        Script::Ethiopic   => detect_lang_in_profiles(text, options, ETHIOPIC_LANGS),
-        Script::Arabic     => detect_lang_in_profiles(text, options, ARABIC_LANGS),
-        Script::Mandarin  => Some((Lang::Cmn, 1.0)),
-        Script::Bengali   => Some((Lang::Ben, 1.0)),
-        Script::Hangul    => Some((Lang::Kor, 1.0)),
-        Script::Georgian  => Some((Lang::Kat, 1.0)),
-        Script::Greek     => Some((Lang::Ell, 1.0)),
-        Script::Kannada   => Some((Lang::Kan, 1.0)),
-        Script::Tamil     => Some((Lang::Tam, 1.0)),
-        Script::Thai      => Some((Lang::Tha, 1.0)),
-        Script::Gujarati  => Some((Lang::Guj, 1.0)),
-        Script::Gurmukhi  => Some((Lang::Pan, 1.0)),
-        Script::Telugu    => Some((Lang::Tel, 1.0)),
-        Script::Malayalam => Some((Lang::Mal, 1.0)),
-        Script::Oriya     => Some((Lang::Ori, 1.0)),
-        Script::Myanmar   => Some((Lang::Mya, 1.0)),
-        Script::Sinhala   => Some((Lang::Sin, 1.0)),
-        Script::Khmer     => Some((Lang::Khm, 1.0)),
-        Script::Katakana | Script::Hiragana  => Some((Lang::Jpn, 1.0))
-    }
-}
-
-fn detect_lang_in_profiles(text: &str, options: &Options, lang_profile_list : LangProfileList) -> Option<(Lang, f64)> {
-    let mut lang_distances : Vec<(Lang, u32)> = vec![];
-    let trigrams = get_trigrams_with_positions(text);
-
-    for &(ref lang, lang_trigrams) in lang_profile_list {
-        match options.list {
-            Some(List::White(ref whitelist)) if !whitelist.contains(lang) => continue,
-            Some(List::Black(ref blacklist)) if blacklist.contains(lang) => continue,
-            _ => {},
-        }
-        let dist = calculate_distance(lang_trigrams, &trigrams);
-        lang_distances.push(((*lang), dist));
-    }
-
-    // Sort languages by distance
-    lang_distances.sort_by_key(|key| key.1 );
-
-    // Return None if lang_distances is empty
-    // Return the only language with is_reliable=true if there is only 1 item
-    if lang_distances.len() < 2 {
-        return lang_distances.first().map(|pair| (pair.0, 1.0));
-    }
-
-    // Calculate is_reliable based on:
-    // - number of unique trigrams in the text
-    // - rate (diff between score of the first and second languages)
-    //
-    let lang_dist1 = lang_distances[0];
-    let lang_dist2 = lang_distances[1];
-    let score1 = MAX_TOTAL_DISTANCE - lang_dist1.1;
-    let score2 = MAX_TOTAL_DISTANCE - lang_dist2.1;
-
-    if score1 == 0 {
-        // If score1 is 0, score2 is 0 as well, because array is sorted.
-        // Therefore there is no language to return.
-        return None;
-    } else if score2 == 0 {
-        // If score2 is 0, return first language, to prevent division by zero in the rate formula.
-        // In this case confidence is calculated by another formula.
-        // At this point there are two options:
-        // * Text contains random characters that accidentally match trigrams of one of the languages
-        // * Text really matches one of the languages.
-        //
-        // Number 500.0 is based on experiments and common sense expectations.
-        let mut confidence = (score1 as f64) / 500.0;
-        if confidence > 1.0 {
-            confidence = 1.0;
-        }
-        return Some((lang_dist1.0, confidence));
-    }
-
-    let rate = (score1 - score2) as f64 / (score2 as f64);
-
-    // Hyperbola function. Everything that is above the function has confidence = 1.0
-    // If rate is below, confidence is calculated proportionally.
-    // Numbers 12.0 and 0.05 are obtained experimentally, so the function represents common sense.
-    //
-    let confident_rate = (12.0 / trigrams.len() as f64) + 0.05;
-    let confidence =
-        if rate > confident_rate {
-            1.0
-        } else {
-            rate / confident_rate
-        };
-
-    Some((lang_dist1.0, confidence))
-}
-
-fn calculate_distance(lang_trigrams: LangProfile,  text_trigrams: &FnvHashMap<String, u32>) -> u32 {
-    let mut total_dist = 0u32;
-
-    for (i, &trigram) in lang_trigrams.iter().enumerate() {
-        let dist = match text_trigrams.get(trigram) {
-            Some(&n) => (n as i32 - i as i32).abs() as u32,
-            None => MAX_TRIGRAM_DISTANCE
-        };
-        total_dist += dist;
-    }
-    total_dist
+        Script::Latin | Script::Cyrillic | Script::Devanagari |
+        Script::Hebrew | Script::Ethiopic | Script::Arabic => None,
+        Script::CanadianAboriginal | Script::Balinese | Script::Sundanese => None,
+        #[cfg(feature = "historic-scripts")]
+        Script::Coptic | Script::Glagolitic | Script::Gothic | Script::OldItalic |
+        Script::Phoenician | Script::Runic => None,
+        Script::Mandarin  => Some(Lang::Cmn),
+        Script::Bengali   => Some(Lang::Ben),
+        Script::Hangul    => Some(Lang::Kor),
+        Script::Georgian  => Some(Lang::Kat),
+        Script::Greek     => Some(Lang::Ell),
+        Script::Kannada   => Some(Lang::Kan),
+        Script::Tamil     => Some(Lang::Tam),
+        Script::Thai      => Some(Lang::Tha),
+        Script::Gujarati  => Some(Lang::Guj),
+        Script::Gurmukhi  => Some(Lang::Pan),
+        Script::Telugu    => Some(Lang::Tel),
+        Script::Malayalam => Some(Lang::Mal),
+        Script::Oriya     => Some(Lang::Ori),
+        Script::Myanmar   => Some(Lang::Mya),
+        Script::Sinhala   => Some(Lang::Sin),
+        Script::Khmer     => Some(Lang::Khm),
+        Script::Lao       => Some(Lang::Lao),
+        Script::Armenian  => Some(Lang::Hye),
+        Script::Mongolian => Some(Lang::Khk),
+        Script::Thaana    => Some(Lang::Div),
+        Script::Cherokee  => Some(Lang::Chr),
+        Script::Nko       => Some(Lang::Nqo),
+        Script::Tifinagh  => Some(Lang::Zgh),
+        Script::Javanese  => Some(Lang::Jav),
+        Script::Katakana | Script::Hiragana => Some(Lang::Jpn)
+    }
+}
+
+fn detect_lang_in_candidates<L, D>(options: &Options, script: Script, script_langs: L,
+                                   detect_lang: D) -> Option<Ranking>
+    where L: FnOnce(Script) -> Vec<Lang>,
+          D: FnOnce(Script, &[Lang]) -> Option<Ranking>
+{
+    let candidates: Vec<Lang> = script_langs(script).into_iter()
+        .filter(|&lang| options.is_allowed(lang))
+        .collect();
+    debug_event!(?script, ?candidates, "filtered candidates");
+
+    // Return None if there are no candidates
+    // Return the only language with is_reliable=true if there is only 1 candidate
+    if candidates.len() < 2 {
+        return candidates.first().map(|&lang| (lang, 1.0, None));
+    }
+
+    detect_lang(script, &candidates)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use script::Script;
+    use options::Preset;
 
     #[test]
     fn test_detect_spanish() {
         let text = "Además de todo lo anteriormente dicho, también encontramos...";
         let output = detect(text);
-        assert_eq!(output.is_some(), true);
+        assert!(output.is_some());
 
         let info = output.unwrap();
         assert_eq!(info.lang, Lang::Spa);
         assert_eq!(info.script, Script::Latin);
     }
 
+    #[test]
+    fn test_detect_tagalog() {
+        // Filipino texts, which mix Tagalog with English words
+        for &text in [
+            "Magandang umaga po sa inyong lahat.",
+            "Hindi ko alam kung saan siya nakatira.",
+            "Sobrang init ngayon sa Maynila, kaya nag-aircon kami buong araw.",
+            "Ang mga bata ay naglalaro sa labas ng bahay.",
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(Lang::Tgl), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_celtic_languages() {
+        // Parallel sentences of Welsh, Irish and Scottish Gaelic, which must not be taken for English
+        // or for each other (Irish and Scottish Gaelic share much of the spelling)
+        for &(text, lang) in [
+            ("Mae'r plant yn chwarae pêl-droed yn y parc ar ôl ysgol bob dydd.", Lang::Cym),
+            ("Bíonn na páistí ag imirt peile sa pháirc tar éis na scoile gach lá.", Lang::Gle),
+            ("Bidh a' chlann a' cluich ball-coise anns a' phàirc às dèidh na sgoile gach latha.", Lang::Gla),
+            ("The children play football in the park after school every day.", Lang::Eng),
+            ("Roedd y tywydd yn ofnadwy ddoe, felly arhoson ni yn y tŷ drwy'r dydd.", Lang::Cym),
+            ("Bhí an aimsir go huafásach inné, mar sin d'fhanamar sa teach an lá ar fad.", Lang::Gle),
+            ("Bha an aimsir uabhasach an-dè, agus mar sin dh'fhuirich sinn anns an taigh fad an latha.", Lang::Gla),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_latin() {
+        // Latin shares much of the spelling with its descendants, which must not be taken for it
+        for &(text, lang) in [
+            ("Pueri post scholam cotidie in horto pila ludunt.", Lang::Lat),
+            ("I bambini giocano a pallone nel parco ogni giorno dopo la scuola.", Lang::Ita),
+            ("Los niños juegan al fútbol en el parque todos los días después de la escuela.", Lang::Spa),
+            ("Heri tempestas mala erat, itaque totum diem domi mansimus.", Lang::Lat),
+            ("Ieri il tempo era brutto, quindi siamo rimasti a casa tutto il giorno.", Lang::Ita),
+            ("Roma caput mundi est, et omnes viae Romam ducunt.", Lang::Lat),
+            ("Roma è la capitale d'Italia e una città molto visitata.", Lang::Ita),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_icelandic_and_faroese() {
+        // Parallel sentences of the West Nordic languages, which must not be taken for each other
+        // or for Danish and Norwegian. Only Icelandic uses thorn, and only the two of them use eth
+        for &(text, lang) in [
+            ("Börnin leika sér í fótbolta í garðinum eftir skóla á hverjum degi.", Lang::Isl),
+            ("Børnini spæla fótbolt í garðinum eftir skúla hvønn dag.", Lang::Fao),
+            ("Børnene leger med en bold i parken efter skole hver dag.", Lang::Dan),
+            ("Ég bý í Reykjavík með konunni minni og tveimur börnum.", Lang::Isl),
+            ("Eg búgvi í Havn saman við konuni og báðum børnunum.", Lang::Fao),
+            ("Mig langar að kaupa miða til Kaupmannahafnar.", Lang::Isl),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_swahili_and_indonesian() {
+        // Both languages have few diacritics and many open syllables, so they share some trigrams
+        for &(text, lang) in [
+            ("Watoto wanacheza mpira nje ya nyumba.", Lang::Swh),
+            ("Tutaonana kesho jioni baada ya kazi.", Lang::Swh),
+            ("Anak-anak bermain bola di luar rumah.", Lang::Ind),
+            ("Kami akan bertemu besok sore setelah bekerja.", Lang::Ind),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_catalan_galician_and_basque() {
+        // Parallel sentences of the Iberian languages, Catalan, Galician and Basque must not be taken for
+        // Spanish, French or Portuguese
+        for &(text, lang) in [
+            ("El Govern ha anunciat avui que invertirà més diners a les escoles rurals perquè els nens no hagin de viatjar tants quilòmetres cada dia.", Lang::Cat),
+            ("A Xunta anunciou hoxe que vai investir máis diñeiro nas escolas rurais para que os nenos non teñan que viaxar tantos quilómetros cada día.", Lang::Glg),
+            ("El Gobierno ha anunciado hoy que invertirá más dinero en las escuelas rurales para que los niños no tengan que viajar tantos kilómetros cada día.", Lang::Spa),
+            ("O Governo anunciou hoje que vai investir mais dinheiro nas escolas rurais para que as crianças não tenham de viajar tantos quilómetros todos os dias.", Lang::Por),
+            ("Le gouvernement a annoncé aujourd'hui qu'il investira plus d'argent dans les écoles rurales pour que les enfants n'aient pas à parcourir tant de kilomètres chaque jour.", Lang::Fra),
+            ("Barcelona és la capital de Catalunya i una ciutat molt visitada.", Lang::Cat),
+            ("Santiago é a capital de Galicia e unha cidade moi visitada.", Lang::Glg),
+            ("Gobernuak gaur iragarri du diru gehiago inbertituko duela landa eremuko eskoletan, haurrek egunero hainbeste kilometro egin behar ez izateko.", Lang::Eus),
+            ("Bilbo Bizkaiko hiriburua da eta asko bisitatzen den hiria.", Lang::Eus),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_yoruba_hausa_and_igbo() {
+        // Tone marks of Yoruba, hooked letters of Hausa and dotted vowels of Igbo
+        for &(text, lang) in [
+            ("Ọmọ náà ń lọ sí ilé ìwé lójoojúmọ́.", Lang::Yor),
+            ("Mo fẹ́ràn láti jẹ ìrẹsì àti ẹ̀wà.", Lang::Yor),
+            ("Yara suna wasa a ƙofar gida kowace rana.", Lang::Hau),
+            ("Ɗan'uwana ya tafi kasuwa jiya da safe.", Lang::Hau),
+            ("Ụmụaka na-egwu egwu n'èzí ụlọ ha.", Lang::Ibo),
+            ("Nwanne m nwoke gara ahịa ụnyaahụ n'ụtụtụ.", Lang::Ibo),
+        ].iter() {
+            assert_eq!(detect_lang(text), Some(lang), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_japanese_with_halfwidth_katakana() {
+        let text = "【送料無料】ﾜｲﾔﾚｽｲﾔﾎﾝ ＢＴ５．０ 最大２０時間再生！ご注文はこちら";
+        assert_eq!(detect_lang(text), Some(Lang::Jpn));
+        assert_eq!(detect_lang("ｶﾞｿﾘﾝｽﾀﾝﾄﾞ"), Some(Lang::Jpn));
+    }
+
+    #[test]
+    fn test_detect_with_native_digits() {
+        let info = detect("فاتورة ١٢٣٤٥٦٧ ٨٩٠١٢٣٤ ٥٦٧٨٩٠١ ٢٣٤٥٦٧٨ المبلغ الإجمالي").unwrap();
+        assert_eq!(info.script(), Script::Arabic);
+        assert_eq!(info.lang(), Lang::Arb);
+
+        // Digits tell the script, but not a language of it
+        assert_eq!(try_detect("٠١٢٣ ٤٥٦ ٧٨٩"), Err(DetectError::NoLanguage(Script::Arabic)));
+    }
+
+    #[test]
+    fn test_detect_polytonic_greek() {
+        let info = detect("Ἐν ἀρχῇ ἦν ὁ λόγος, καὶ ὁ λόγος ἦν πρὸς τὸν θεόν.").unwrap();
+        assert_eq!(info.script(), Script::Greek);
+        assert_eq!(info.lang(), Lang::Ell);
+    }
+
+    #[test]
+    fn test_detect_uppercase_turkish() {
+        // "I" and "İ" of Turkish are lowercased to "ı" and "i"
+        let text = "İSTANBUL'DA HAVA BUGÜN ÇOK GÜZEL, DIŞARI ÇIKIP BIRAZ YÜRÜYÜŞ YAPALIM.";
+        assert_eq!(detect_lang(text), Some(Lang::Tur));
+    }
+
+    #[test]
+    fn test_detect_with_presets() {
+        let long_text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(1000);
+        for &text in ["Además de todo lo anteriormente dicho, también encontramos...",
+                      "Та нічого, все нормально. А в тебе як?", &long_text].iter() {
+            for &preset in [Preset::Fast, Preset::Balanced, Preset::Accurate].iter() {
+                assert_eq!(detect_lang_with_options(text, &Options::preset(preset)), detect_lang(text), "{:?}", preset);
+            }
+        }
+        assert_eq!(Options::preset(Preset::Accurate), Options::default());
+        assert_eq!(Options::preset(Preset::Balanced).sample_size, Some(8 * 1024));
+        assert!(!Options::preset(Preset::Fast).resolve_close_langs);
+    }
+
+    #[test]
+    fn test_detect_without_resolving_close_langs() {
+        let text = "Eg likar å lese bøker om kvelden.";
+        assert_eq!(detect_lang(text), Some(Lang::Nno));
+        assert_eq!(detect_lang_with_options(text, &Options::new().set_resolve_close_langs(false)), Some(Lang::Nob));
+    }
+
+    #[test]
+    fn test_detect_with_script_exit_threshold() {
+        // Only the beginning of the text is counted, so its script wins
+        let text = "Привет, как дела? ".repeat(20) + &"Hello, how are you? ".repeat(100);
+        assert_eq!(detect_with_options(&text, &Options::default()).unwrap().script(), Script::Latin);
+        let info = detect_with_options(&text, &Options::new().set_script_exit_threshold(0.8, 100)).unwrap();
+        assert_eq!(info.script(), Script::Cyrillic);
+        assert_eq!(info.lang(), Lang::Rus);
+    }
+
+    #[test]
+    fn test_detect_second_best() {
+        let info = detect("Além disso, também encontramos muitas coisas interessantes na cidade.").unwrap();
+        assert_eq!(info.lang(), Lang::Por);
+        let (lang, margin) = info.second_best().unwrap();
+        assert_ne!(lang, Lang::Por);
+        assert!(margin > 0.0 && margin < 1.0, "{}", margin);
+
+        // A closer pair has a smaller margin
+        let options = Options::new().set_whitelist(vec![Lang::Por, Lang::Spa, Lang::Eng]);
+        let close = detect_with_options("O gato está em casa.", &options).unwrap().second_best().unwrap();
+        let options = Options::new().set_whitelist(vec![Lang::Por, Lang::Eng]);
+        let far = detect_with_options("O gato está em casa.", &options).unwrap().second_best().unwrap();
+        assert_eq!(close.0, Lang::Spa);
+        assert_eq!(far.0, Lang::Eng);
+        assert!(close.1 < far.1);
+
+        // The only language of a script or the only candidate
+        assert_eq!(detect("Ἐν ἀρχῇ ἦν ὁ λόγος").unwrap().second_best(), None);
+        let options = Options::new().set_whitelist(vec![Lang::Por]);
+        assert_eq!(detect_with_options("O gato está em casa.", &options).unwrap().second_best(), None);
+    }
+
+    #[test]
+    fn test_detect_lang_with_script() {
+        for &text in [
+            "Además de todo lo anteriormente dicho, también encontramos...",
+            "Та нічого, все нормально. А в тебе як?",
+            "Ἐν ἀρχῇ ἦν ὁ λόγος, καὶ ὁ λόγος ἦν πρὸς τὸν θεόν.",
+            "فاتورة المبلغ الإجمالي للطلب",
+            "東京都知事選挙の結果発表",
+        ].iter() {
+            let info = detect(text).unwrap();
+            assert_eq!(detect_lang_with_script(text, info.script()), Some(info.lang()), "{}", text);
+        }
+
+        // Stray chars of other scripts do not change the script
+        let text = "Der Vertrag wurde gestern in 東京 unterzeichnet.";
+        assert_eq!(detect_lang_with_script(text, Script::Latin), Some(Lang::Deu));
+
+        assert_eq!(detect_lang_with_script("42!", Script::Latin), None);
+        assert_eq!(detect_lang_with_script(" \n\t ", Script::Latin), None);
+        let options = Options::new().set_skip_code(true);
+        assert_eq!(detect_lang_with_script_and_options("fn main() { let x = vec![1, 2]; }", Script::Latin, &options), None);
+        let options = Options::new().set_whitelist(vec![Lang::Eng]);
+        assert_eq!(detect_lang_with_script_and_options("Та нічого, все нормально.", Script::Cyrillic, &options), None);
+    }
+
     #[test]
     fn test_detect_lang_ukrainian() {
         let text = "Та нічого, все нормально. А в тебе як?";
         assert_eq!(detect_lang(text), Some(Lang::Ukr));
     }
 
+    #[test]
+    fn test_detect_mongolian_in_both_scripts() {
+        assert_eq!(detect_lang("Монгол Улсын нийслэл нь Улаанбаатар хот юм"), Some(Lang::Khk));
+        assert_eq!(detect_lang("Хүүхдүүд гадаа тоглож байна."), Some(Lang::Khk));
+        assert_eq!(detect_lang("Маргааш бид уулзах болно."), Some(Lang::Khk));
+        assert_eq!(detect_lang("Завтра мы встретимся вечером после работы."), Some(Lang::Rus));
+
+        let info = detect("ᠮᠣᠩᠭᠣᠯ ᠪᠢᠴᠢᠭ").unwrap();
+        assert_eq!(info.lang, Lang::Khk);
+        assert_eq!(info.script, Script::Mongolian);
+    }
+
+    #[test]
+    fn test_detect_uzbek_in_both_scripts() {
+        let info = detect("Ўзбекистон Марказий Осиёдаги давлат, пойтахти Тошкент шаҳри.").unwrap();
+        assert_eq!(info.lang, Lang::Uzb);
+        assert_eq!(info.script, Script::Cyrillic);
+
+        let info = detect("O'zbekiston Markaziy Osiyodagi davlat, poytaxti Toshkent shahri.").unwrap();
+        assert_eq!(info.lang, Lang::Uzb);
+        assert_eq!(info.script, Script::Latin);
+    }
+
+    #[test]
+    fn test_detect_serbian_in_both_scripts() {
+        assert_eq!(detect_lang("Свако има право на живот, слободу и безбедност личности."), Some(Lang::Srp));
+
+        let info = detect("Juče sam bio u prodavnici i kupio hleb, mleko i malo voća. Posle toga smo otišli kod prijatelja.").unwrap();
+        assert_eq!(info.lang, Lang::Srp);
+        assert_eq!(info.script, Script::Latin);
+    }
+
+    #[test]
+    fn test_detect_romanized() {
+        let texts = [
+            (Lang::Hin, "Yaar tum log kahan ho, main kab se intezaar kar raha hoon. Jaldi aao warna main chala jaunga."),
+            (Lang::Arb, "Ahlan ya 7abibi, ezzayak? Ana kwayes el7amdulillah, w enta 3amel eh? Yalla netqabel ba3d elshoghl."),
+            (Lang::Rus, "Privet, kak dela? U menya vse horosho, spasibo. Chto ty delaesh segodnya vecherom?"),
+            (Lang::Cmn, "Ni hao, zuijin zenmeyang? Wo hen hao, xiexie. Ni jintian wanshang zuo shenme?"),
+        ];
+        let options = Options::new().set_romanized(true);
+        for &(lang, text) in texts.iter() {
+            assert_ne!(detect_lang(text), Some(lang), "{}", text);
+
+            let info = detect_with_options(text, &options).unwrap();
+            assert_eq!(info.lang, lang, "{}", text);
+            assert_eq!(info.script, Script::Latin);
+        }
+
+        assert_eq!(detect_lang_with_options("There is no reason not to learn Esperanto.", &options), Some(Lang::Eng));
+    }
+
+    #[test]
+    fn test_detect_kanji_heavy_japanese() {
+        let info = detect("東京都知事選挙の結果発表").unwrap();
+        assert_eq!(info.lang, Lang::Jpn);
+        assert_eq!(info.script, Script::Mandarin);
+
+        assert_eq!(detect_lang("县见夜上温国阪题富贩"), Some(Lang::Cmn));
+
+        // Kanji only
+        assert_eq!(detect_lang("東京駅"), Some(Lang::Jpn));
+        assert_eq!(detect_lang("北京东站"), Some(Lang::Cmn));
+    }
+
+    #[test]
+    fn test_detect_cantonese_with_allowed_langs() {
+        let text = "我哋今日去咗公園玩";
+        assert_eq!(detect_lang(text), Some(Lang::Yue));
+
+        let options = Options::new().set_whitelist(vec![Lang::Cmn, Lang::Yue]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Yue));
+        let options = Options::new().set_whitelist(vec![Lang::Cmn]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Cmn));
+        let options = Options::new().set_blacklist(vec![Lang::Yue]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Cmn));
+    }
+
+    #[test]
+    fn test_detect_japanese_by_kana_with_blacklist() {
+        let text = "国立大学法人東京大学大学院情報理工学系研究科のホームページ";
+        assert_eq!(detect_lang(text), Some(Lang::Jpn));
+
+        let options = Options::new().set_blacklist(vec![Lang::Jpn]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Cmn));
+    }
+
+    #[test]
+    fn test_detect_kanji_only_japanese_with_whitelist() {
+        let text = "東京駅の国際経済研究所";
+        let options = Options::new().set_whitelist(vec![Lang::Cmn]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Cmn));
+
+        let text = "東京駅国際経済研究所";
+        assert_eq!(detect_lang(text), Some(Lang::Jpn));
+        let options = Options::new().set_blacklist(vec![Lang::Jpn]);
+        assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Cmn));
+    }
+
+    #[test]
+    fn test_detect_korean_mixed_with_hanja_and_latin() {
+        let texts = [
+            "大韓民國憲法 第1條 ① 大韓民國은 民主共和國이다. ② 大韓民國의 主權은 國民에게 있고, 모든 權力은 國民으로부터 나온다.",
+            "「朝鮮王朝實錄」은 太祖부터 哲宗까지 472年間의 歷史를 編年體로 記錄한 冊이다.",
+            "삼성전자(三星電子)는 Galaxy S24 Ultra를 서울에서 公開했다.",
+            "【速報】韓國經濟、成長率發表…올해 3.2％ 成長 豫想",
+        ];
+        for text in texts.iter() {
+            assert_eq!(detect_lang(text), Some(Lang::Kor), "{}", text);
+        }
+
+        // Korean is not detected, if it's not allowed: Latin languages are detected for
+        // a text with more Latin letters, and no language for a text with more Hangul
+        let options = Options::new().set_blacklist(vec![Lang::Kor]);
+        let text = "삼성전자는 Galaxy Ultra를 공개했다";
+        assert_eq!(detect_lang(text), Some(Lang::Kor));
+        let info = detect_with_options(text, &options).unwrap();
+        assert_eq!(info.script, Script::Latin);
+        assert_ne!(info.lang, Lang::Kor);
+        for text in texts.iter() {
+            assert_ne!(detect_lang_with_options(text, &options), Some(Lang::Kor), "{}", text);
+        }
+        let text = "삼성전자는 Galaxy S24 Ultra를 서울에서 공개했다";
+        assert_eq!(try_detect_with_options(text, &options), Err(DetectError::NoLanguage(Script::Hangul)));
+    }
+
+    #[test]
+    fn test_detect_script_without_languages() {
+        // Canadian Aboriginal syllabics are used by several languages, which have no profiles
+        assert_eq!(detect_script("ᐃᓄᒃᑎᑐᑦ"), Some(Script::CanadianAboriginal));
+        assert_eq!(detect("ᐃᓄᒃᑎᑐᑦ"), None);
+        assert_eq!(detect("ᮘᮞ ᮞᮥᮔ᮪ᮓ"), None);
+    }
+
+    #[test]
+    fn test_detect_cyrillic_turkic_langs_by_letters() {
+        assert_eq!(detect_lang("Біз ертең кездесеміз."), Some(Lang::Kaz));
+        assert_eq!(detect_lang("Мен бүгін мектепке бардым."), Some(Lang::Kaz));
+        assert_eq!(detect_lang("Биз эртең жолугабыз."), Some(Lang::Kir));
+        assert_eq!(detect_lang("Мин бүген мәктәпкә бардым."), Some(Lang::Tat));
+    }
+
+    #[test]
+    fn test_detect_arabic_script_langs_by_letters() {
+        assert_eq!(detect_lang("ذهبت أمس إلى السوق واشتريت كتبا"), Some(Lang::Arb));
+        assert_eq!(detect_lang("من دیروز به بازار رفتم و کتاب خریدم"), Some(Lang::Pes));
+        assert_eq!(detect_lang("میں نے کل بازار سے کتابیں خریدیں"), Some(Lang::Urd));
+        assert_eq!(detect_lang("مەن تۈنۈگۈن بازارغا باردىم"), Some(Lang::Uig));
+    }
+
+    #[test]
+    fn test_detect_javanese_script() {
+        let info = detect("ꦧꦱꦗꦮ ꦲꦶꦏꦸ ꦧꦱꦲꦶꦁꦒꦶꦤꦸꦁꦒꦺ").unwrap();
+        assert_eq!(info.lang, Lang::Jav);
+        assert_eq!(info.script, Script::Javanese);
+    }
+
+    #[test]
+    fn test_detect_lao_script() {
+        let info = detect("ພາສາລາວເປັນພາສາລາດຊະການຂອງສາທາລະນະລັດ ປະຊາທິປະໄຕ ປະຊາຊົນລາວ").unwrap();
+        assert_eq!(info.lang, Lang::Lao);
+        assert_eq!(info.script, Script::Lao);
+    }
+
     #[test]
     fn test_detect_with_options_with_blacklist() {
         let text = "I am begging pardon";
         // without blacklist
         let output = detect_with_options(text, &Options::default());
-        assert_eq!(output.is_some(), true);
+        assert!(output.is_some());
         let info = output.unwrap();
         assert_eq!(info.lang, Lang::Tgl);
 
         // with blacklist
-        let blacklist = vec![Lang::Tgl, Lang::Jav, Lang::Nld, Lang::Uzb, Lang::Swe, Lang::Nob, Lang::Ceb, Lang::Ilo];
+        let blacklist = vec![Lang::Tgl, Lang::Jav, Lang::Nld, Lang::Uzb, Lang::Swe, Lang::Nob, Lang::Ceb, Lang::Ilo, Lang::Zlm,
+                             Lang::Cym, Lang::Gla];
         let options = Options::new().set_blacklist(blacklist);
         let output = detect_with_options(text, &options);
-        assert_eq!(output.is_some(), true);
+        assert!(output.is_some());
         let info = output.unwrap();
         assert_eq!(info.lang, Lang::Eng);
     }
@@ -203,6 +835,10 @@ mod tests {
         let options = Options::new().set_blacklist(blacklist);
         let output = detect_with_options(text, &options);
         assert_eq!(output, None);
+
+        // The only language of Georgian script is blacklisted
+        let options = Options::new().set_blacklist(vec![Lang::Kat]);
+        assert_eq!(detect_with_options("ქართული ენა", &options), None);
     }
 
     #[test]
@@ -212,11 +848,81 @@ mod tests {
 
         let text = "Mi ne scias!";
         let output = detect_with_options(text, &options);
-        assert_eq!(output.is_some(), true);
+        assert!(output.is_some());
         let info = output.unwrap();
         assert_eq!(info.lang, Lang::Epo);
     }
 
+    #[test]
+    fn test_detect_secondary_script() {
+        let info = detect("Russian word любовь means love.").unwrap();
+        assert_eq!(info.script(), Script::Latin);
+        let (script, share) = info.secondary_script().unwrap();
+        assert_eq!(script, Script::Cyrillic);
+        assert!((share - 6.0 / 26.0).abs() < 1e-9);
+
+        let info = detect("Hello world! How are you?").unwrap();
+        assert_eq!(info.secondary_script(), None);
+    }
+
+    #[test]
+    fn test_detect_with_options_with_min_confidence() {
+        let text = "qwertyuioasdfghjklzxcvbnm";
+        let info = detect(text).unwrap();
+        assert!(info.confidence() < 0.9);
+
+        let options = Options::new().set_min_confidence(0.9);
+        assert_eq!(detect_with_options(text, &options), None);
+        assert_eq!(detect_lang_with_options(text, &options), None);
+
+        let options = Options::new().set_min_confidence(info.confidence());
+        assert_eq!(detect_with_options(text, &options), Some(info));
+    }
+
+    #[test]
+    #[should_panic(expected = "min confidence is NaN")]
+    fn test_min_confidence_is_not_nan() {
+        Options::new().set_min_confidence(::std::f64::NAN);
+    }
+
+    #[test]
+    fn test_try_detect() {
+        assert_eq!(try_detect("Та нічого, все нормально. А в тебе як?").map(|info| info.lang), Ok(Lang::Ukr));
+
+        assert_eq!(try_detect(""), Err(DetectError::EmptyInput));
+        assert_eq!(try_detect(" \n\t"), Err(DetectError::EmptyInput));
+        assert_eq!(try_detect("1, 2, 3 :)"), Err(DetectError::NoScript));
+        assert_eq!(try_detect("ᐃᓄᒃᑎᑐᑦ"), Err(DetectError::NoLanguage(Script::CanadianAboriginal)));
+
+        let options = Options::new().set_blacklist(vec![Lang::Heb, Lang::Ydd]);
+        assert_eq!(try_detect_with_options("האקדמיה ללשון העברית", &options), Err(DetectError::NoLanguage(Script::Hebrew)));
+
+        let text = "qwertyuioasdfghjklzxcvbnm";
+        let info = detect(text).unwrap();
+        let options = Options::new().set_min_confidence(0.9);
+        let error = try_detect_with_options(text, &options).unwrap_err();
+        assert_eq!(error, DetectError::LowConfidence(info));
+        assert_eq!(error.to_string(), format!("confidence {} of {} is below the threshold", info.confidence, info.lang.eng_name()));
+    }
+
+    #[test]
+    fn test_detect_with_min_length() {
+        let options = Options::new().set_min_length(4);
+        assert_eq!(try_detect_with_options("Да!", &options), Err(DetectError::TooShort));
+        assert_eq!(try_detect_with_options("1 2 3 a b c", &options), Err(DetectError::TooShort));
+        assert_eq!(try_detect_with_options("!!!", &options), Err(DetectError::NoScript));
+        assert!(try_detect_with_options("Да, да", &options).is_ok());
+    }
+
+    #[test]
+    fn test_detect_with_emoji() {
+        let text = "🎉🎉🎉 Поздравляю с днём рождения 🎂🎁 Желаю счастья и здоровья ❤️❤️👍🏽 ✨✨✨";
+        let info = detect(text).unwrap();
+        assert_eq!(info.script(), Script::Cyrillic);
+        assert_eq!(info.lang(), Lang::Rus);
+        assert_eq!(detect("😀😀 👍🏽 ❤️ ─━═ ★☆"), None);
+    }
+
     #[test]
     fn test_detect_with_random_text() {
         assert_eq!(detect("fdf"), None);
@@ -241,4 +947,30 @@ mod tests {
         let info = detect(text).unwrap();
         assert!(!info.is_reliable());
     }
+
+    #[test]
+    fn test_detect_with_max_duration() {
+        use std::time::Duration;
+
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(1000);
+        let info = detect_with_options(&text, &Options::new().set_max_duration(Duration::from_millis(0))).unwrap();
+        assert_eq!(info.lang(), Lang::Deu);
+        assert!(info.is_partial());
+        assert!(!info.is_reliable());
+
+        let info = detect_with_options(&text, &Options::new().set_max_duration(Duration::from_secs(60))).unwrap();
+        assert_eq!(Some(info), detect(&text));
+        assert!(!info.is_partial());
+
+        // Short texts are detected as a whole
+        let text = "Ĉu vi ne volas eklerni Esperanton? Bonvolu!";
+        let info = detect_with_options(text, &Options::new().set_max_duration(Duration::from_millis(0))).unwrap();
+        assert_eq!(Some(info), detect(text));
+
+        let options = Options::new().set_max_duration(Duration::from_millis(0)).set_min_confidence(1.1);
+        match try_detect_with_options(&"Esperanto estas facila lingvo. ".repeat(1000), &options) {
+            Err(DetectError::LowConfidence(info)) => assert!(info.is_partial()),
+            result => panic!("Unexpected result: {:?}", result)
+        }
+    }
 }
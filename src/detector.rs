@@ -1,9 +1,14 @@
 use lang::Lang;
 use script::Script;
-use script::detect_script;
+use script::detect_script_with_options;
 use info::Info;
 use options::Options;
+use model::{LanguageModel, TrigramModel};
+use trigrams::Scratch;
+#[cfg(feature = "cache")]
+use cache::DetectionCache;
 use detect;
+use detect::DetectError;
 
 /// Configurable structure that holds detection options and provides functions
 /// to detect language and script.
@@ -28,9 +33,17 @@ use detect;
 /// let lang = detector.detect_lang("Jen la trinkejo fermitis, ni iras tra mallumo kaj pluvo.");
 /// assert_eq!(lang, Some(Lang::Epo));
 /// ```
+///
+/// By default languages are recognized by [TrigramModel](struct.TrigramModel.html),
+/// a custom [LanguageModel](trait.LanguageModel.html) can be plugged in with `with_model`,
+/// e.g. [ProfileModel](struct.ProfileModel.html) with profiles loaded at runtime.
 #[derive(Debug, Clone, Default)]
-pub struct Detector {
+pub struct Detector<M = TrigramModel> {
     options: Options,
+    model: M,
+    scratch: Scratch,
+    #[cfg(feature = "cache")]
+    cache: DetectionCache,
 }
 
 impl Detector {
@@ -49,19 +62,101 @@ impl Detector {
     }
 
     pub fn with_options(options: Options) -> Self {
-        Detector { options }
+        Self::with_model(TrigramModel, options)
+    }
+}
+
+impl<M: LanguageModel> Detector<M> {
+    pub fn with_model(model: M, options: Options) -> Self {
+        Detector {
+            options,
+            model,
+            scratch: Scratch::new(),
+            #[cfg(feature = "cache")]
+            cache: DetectionCache::default()
+        }
+    }
+
+    /// Cache results of detection of the `size` most recently used texts (requires `cache` feature),
+    /// so detection of a repeated text (e.g. from a template or a retry) returns at once.
+    /// Cached texts are kept in memory with their results. Partial results of detection,
+    /// which ran out of time (see `Options::set_max_duration`), are not cached.
+    /// The cache is disabled by default (size 0), a clone of a detector starts with an empty cache.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Detector, Lang};
+    ///
+    /// let detector = Detector::new().set_cache_size(1000);
+    /// let text = "Ĉu vi ne volas eklerni Esperanton? Bonvolu!";
+    /// assert_eq!(detector.detect_lang(text), Some(Lang::Epo));
+    /// // Returned from the cache
+    /// assert_eq!(detector.detect_lang(text), Some(Lang::Epo));
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn set_cache_size(mut self, size: usize) -> Self {
+        self.cache = DetectionCache::new(size);
+        self
+    }
+
+    /// Size of the cache of results, see `set_cache_size`.
+    #[cfg(feature = "cache")]
+    pub fn cache_size(&self) -> usize {
+        self.cache.capacity()
+    }
+
+    pub fn model(&self) -> &M {
+        &self.model
     }
 
     pub fn detect(&self, text: &str) -> Option<Info> {
-        detect::detect_with_options(text, &self.options)
+        self.try_detect(text).ok()
+    }
+
+    /// Detect a language and a script, reporting why detection failed,
+    /// see [try_detect](fn.try_detect.html).
+    pub fn try_detect(&self, text: &str) -> Result<Info, DetectError> {
+        #[cfg(feature = "cache")]
+        let result = self.cache.get_or_detect(text, || detect::try_detect_with_model(text, &self.options, &self.model));
+        #[cfg(not(feature = "cache"))]
+        let result = detect::try_detect_with_model(text, &self.options, &self.model);
+        result
+    }
+
+    /// The same as `detect`, but n-grams of the text are counted in buffers of the detector,
+    /// which are reused by the next calls instead of allocating them for every text.
+    /// A detector is `Send`, so a thread can keep its own one for a hot loop.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Detector, Lang};
+    ///
+    /// let mut detector = Detector::new();
+    /// let texts = ["Ĉu vi ne volas eklerni Esperanton? Bonvolu!", "Введите запрос, чтобы найти нужную информацию"];
+    /// let langs: Vec<Lang> = texts.iter().filter_map(|text| detector.detect_in(text)).map(|info| info.lang()).collect();
+    /// assert_eq!(langs, vec![Lang::Epo, Lang::Rus]);
+    /// ```
+    pub fn detect_in(&mut self, text: &str) -> Option<Info> {
+        let (options, model, scratch) = (&self.options, &self.model, &mut self.scratch);
+        #[cfg(feature = "cache")]
+        let result = self.cache.get_or_detect(text, || detect::try_detect_with_model_in(text, options, model, scratch));
+        #[cfg(not(feature = "cache"))]
+        let result = detect::try_detect_with_model_in(text, options, model, scratch);
+        result.ok()
     }
 
     pub fn detect_lang(&self, text: &str) -> Option<Lang> {
-        detect::detect_lang_with_options(text, &self.options)
+        self.detect(text).map(|info| info.lang)
+    }
+
+    /// Detect only a language by a text in a known script,
+    /// see [detect_lang_with_script](fn.detect_lang_with_script.html).
+    pub fn detect_lang_with_script(&self, text: &str, script: Script) -> Option<Lang> {
+        detect::detect_lang_with_script_and_model(text, script, &self.options, &self.model)
     }
 
     pub fn detect_script(&self, text: &str) -> Option<Script> {
-        detect_script(text)
+        detect_script_with_options(text, &self.options)
     }
 }
 
@@ -75,6 +170,12 @@ mod tests {
         assert_eq!(Detector::new().detect_script("Кириллица"), Some(Script::Cyrillic));
     }
 
+    #[test]
+    fn test_detect_lang_with_script() {
+        let text = "Введите запрос, чтобы найти нужную информацию";
+        assert_eq!(Detector::new().detect_lang_with_script(text, Script::Cyrillic), Some(Lang::Rus));
+    }
+
     #[test]
     fn test_detect_lang() {
         // Esperanto
@@ -87,10 +188,74 @@ mod tests {
         // Esperanto
         let text = "Ĉiuj redaktantoj de Esperanta Vikipedio estas volontuloj. Ili partoprenas en la kunlaborema komunumo, sen estro, kie la anoj kunordigas siajn strebojn kadre de temaj projektoj kaj pluraj diskutejoj. Ili sekvas la bazajn regulojn establitaj de la komunumo, ekzemple kontrolebleco de la informo aŭ la menciindeco de la temo.";
         let output = Detector::new().detect(text);
-        assert_eq!(output.is_some(), true);
+        assert!(output.is_some());
 
         let info = output.unwrap();
         assert_eq!(info.lang, Lang::Epo);
         assert_eq!(info.script, Script::Latin);
     }
+
+    #[test]
+    fn test_detect_in_matches_detect() {
+        let texts = [
+            "Ĉu vi ne volas eklerni Esperanton? Bonvolu! Estas unu de la plej bonaj aferoj!",
+            "Введите запрос, чтобы найти нужную информацию",
+            "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.",
+            "",
+            "Hund und Katze",
+        ];
+        let mut detector = Detector::with_options(Options::new().set_blacklist(vec![Lang::Ita]));
+        for &text in texts.iter() {
+            assert_eq!(detector.detect_in(text), detector.detect(text), "{}", text);
+        }
+
+        let mut detector = Detector::with_model(LastCode, Options::new());
+        assert_eq!(detector.detect_in("Привет, как дела?"), detector.detect("Привет, как дела?"));
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_detector_with_cache() {
+        let mut detector = Detector::with_model(LastCode, Options::new().set_whitelist(vec![Lang::Rus, Lang::Ukr])).set_cache_size(2);
+        assert_eq!(detector.cache_size(), 2);
+        let text = "Привет, как дела?";
+        let expected = detector.detect(text);
+        assert_eq!(detector.detect(text), expected);
+        assert_eq!(detector.detect_in(text), expected);
+        assert_eq!(detector.try_detect(""), Err(DetectError::EmptyInput));
+        assert_eq!(Detector::new().cache_size(), 0);
+        assert_eq!(Detector::new().set_cache_size(5).clone().cache_size(), 5);
+    }
+
+    #[test]
+    fn test_detector_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Detector>();
+    }
+
+    // Picks the candidate with the alphabetically last code.
+    struct LastCode;
+
+    impl LanguageModel for LastCode {
+        fn detect_lang(&self, _text: &str, _script: Script, candidates: &[Lang], _options: &Options) -> Option<(Lang, f64)> {
+            candidates.iter().max_by_key(|lang| lang.code()).map(|&lang| (lang, 0.3))
+        }
+    }
+
+    #[test]
+    fn test_with_model() {
+        let whitelist = vec![Lang::Eng, Lang::Rus, Lang::Ukr, Lang::Kat];
+        let detector = Detector::with_model(LastCode, Options::new().set_whitelist(whitelist));
+
+        let info = detector.detect("Привет, как дела?").unwrap();
+        assert_eq!(info.script(), Script::Cyrillic);
+        assert_eq!(info.lang(), Lang::Ukr);
+        assert_eq!(info.confidence(), 0.3);
+
+        // The model is not consulted when only one candidate is left for the script
+        assert_eq!(detector.detect_lang("Hello, how are you?"), Some(Lang::Eng));
+
+        // Scripts used by a single language do not need a model as well
+        assert_eq!(detector.detect_lang("ქართული ენა"), Some(Lang::Kat));
+    }
 }
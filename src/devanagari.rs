@@ -0,0 +1,129 @@
+use lang::Lang;
+
+// Hindi, Marathi, Nepali, Bhojpuri and Maithili are written in Devanagari and share much of their
+// vocabulary, so short texts of them are often detected as Hindi or Bhojpuri by trigrams.
+// But their most common function words differ: the copula ("है", "आहे", "छ", "बा", "अछि"),
+// pronouns ("मैं", "मी", "म", "हमनी", "अहाँ") and conjunctions ("और", "आणि", "र").
+//
+// Words of every language are sorted, to be found with binary search.
+const DEVANAGARI_WORDS: [(Lang, &[&str]); 5] = [
+    (Lang::Hin, &[
+        "आप", "और", "क्या", "गया", "था", "थी", "थे", "नहीं", "मुझे", "मैं", "यह", "रहा", "रही", "वह",
+        "है", "हैं",
+    ]),
+    (Lang::Mar, &[
+        "आणि", "आम्ही", "आहे", "आहेत", "काय", "केले", "खूप", "तुम्ही", "तो", "नाही", "मला", "मी",
+        "म्हणून", "होता", "होती", "होतो",
+    ]),
+    (Lang::Nep, &[
+        "एउटा", "छ", "छन्", "छु", "तपाईं", "त्यसैले", "थिएँ", "थियो", "धेरै", "पनि", "भएको", "म",
+        "मलाई", "मेरो", "यो", "र", "हामी", "हाम्रो", "हुन्छ", "हो",
+    ]),
+    (Lang::Bho, &[
+        "ऊ", "एगो", "कइल", "गइल", "गइलें", "बा", "बाटे", "बाड़ी", "बाड़े", "बानी", "रउआ", "रहनी",
+        "रहल", "हमनी", "हमार", "होला",
+    ]),
+    (Lang::Mai, &[
+        "अछि", "अपन", "अहाँ", "एकटा", "ओ", "कऽ", "गेल", "छथि", "छल", "छलाह", "छी", "नहि", "मे",
+        "सँ", "सभ", "सेहो", "हमर",
+    ]),
+];
+
+// Choose a Devanagari language by the words, which are used only in one of the languages.
+// Returns None if `lang` is not one of them, or if no language has more words in the text
+// than every other one.
+pub(crate) fn resolve_devanagari(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_devanagari(lang) {
+        return None;
+    }
+    resolve_devanagari_by_counts(count_devanagari_words(text), lang)
+}
+
+// Same as resolve_devanagari, but with the words already counted by count_devanagari_words.
+pub(crate) fn resolve_devanagari_by_counts(words: DevanagariWords, lang: Lang) -> Option<Lang> {
+    if !is_devanagari(lang) {
+        return None;
+    }
+
+    let max = *words.counts.iter().max().unwrap();
+    let mut best = DEVANAGARI_WORDS.iter().zip(words.counts.iter()).filter(|&(_, &count)| count == max);
+    match (best.next(), best.next()) {
+        (Some((&(lang, _), _)), None) if max > 0 => Some(lang),
+        _ => None
+    }
+}
+
+// Numbers of words, which are used only in one of the Devanagari languages,
+// in the same order as DEVANAGARI_WORDS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DevanagariWords {
+    pub counts: [usize; 5]
+}
+
+impl DevanagariWords {
+    pub fn add(&mut self, other: DevanagariWords) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other_count;
+        }
+    }
+}
+
+// Vowel signs and virama are not alphabetic, so words are split by whitespace and punctuation.
+pub(crate) fn count_devanagari_words(text: &str) -> DevanagariWords {
+    let mut result = DevanagariWords::default();
+    let is_separator = |ch: char| ch.is_whitespace() || ch.is_ascii_punctuation() || ch == '\u{0964}' || ch == '\u{0965}';
+    for word in text.split(is_separator).filter(|word| !word.is_empty()) {
+        if let Some(index) = DEVANAGARI_WORDS.iter().position(|&(_, words)| contains(words, word)) {
+            result.counts[index] += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_devanagari(lang: Lang) -> bool {
+    DEVANAGARI_WORDS.iter().any(|&(devanagari_lang, _)| devanagari_lang == lang)
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for (i, &(_, words)) in DEVANAGARI_WORDS.iter().enumerate() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+            for &(_, other_words) in DEVANAGARI_WORDS[i + 1..].iter() {
+                for word in words.iter() {
+                    assert!(!contains(other_words, word), "{}", word);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_devanagari() {
+        assert_eq!(resolve_devanagari("यह किताब बहुत अच्छी है।", Lang::Bho), Some(Lang::Hin));
+        assert_eq!(resolve_devanagari("हे पुस्तक खूप चांगले आहे.", Lang::Hin), Some(Lang::Mar));
+        assert_eq!(resolve_devanagari("यो किताब धेरै राम्रो छ।", Lang::Hin), Some(Lang::Nep));
+        assert_eq!(resolve_devanagari("हमनी के गाँव में एगो मंदिर बा।", Lang::Hin), Some(Lang::Bho));
+        assert_eq!(resolve_devanagari("ई पोथी बहुत नीक अछि।", Lang::Bho), Some(Lang::Mai));
+
+        // Without evidence or with as much evidence of two languages the language is not resolved
+        assert_eq!(resolve_devanagari("बहुत अच्छा", Lang::Bho), None);
+        assert_eq!(resolve_devanagari("मैं मी", Lang::Bho), None);
+
+        assert_eq!(resolve_devanagari("यह किताब बहुत अच्छी है।", Lang::Eng), None);
+    }
+
+    #[test]
+    fn test_count_devanagari_words() {
+        let text = "मलाई चिया मन पर्छ, र हामी घरमै छौं। मुझे चाय पसंद है।";
+        assert_eq!(count_devanagari_words(text), DevanagariWords { counts: [2, 0, 3, 0, 0] });
+    }
+}
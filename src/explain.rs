@@ -0,0 +1,183 @@
+use lang::Lang;
+use script::{Script, detect_script_with_options};
+use options::Options;
+use model::{candidate_profiles, calculate_distance};
+use trigrams::{find_ngram, get_ngrams_with_positions};
+use preprocess::preprocess;
+
+// Number of the best languages, which are explained.
+const EXPLAINED_LANGS: usize = 3;
+
+/// Explain how trigrams of a text are compared with profiles of languages:
+/// for the closest languages tells which trigrams matched, their ranks and
+/// their contributions to the distance. Meant for debugging misclassified texts.
+///
+/// The explanation is based on the out-of-place distance (the default scoring metric),
+/// the closest language is not necessarily the detected one, when other options
+/// (e.g. priors) affect detection. Returns `None` if the text contains no letters
+/// of supported scripts.
+///
+/// # Example
+/// ```
+/// use whatlang::{explain, Lang, Script};
+///
+/// let explanation = explain("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.").unwrap();
+/// assert_eq!(explanation.script(), Script::Latin);
+///
+/// let best = &explanation.langs()[0];
+/// assert_eq!(best.lang(), Lang::Deu);
+/// for m in best.matches().iter().take(3) {
+///     println!("{:?}: text rank {}, profile rank {}, distance {}", m.trigram(), m.text_rank(), m.profile_rank(), m.distance());
+/// }
+/// ```
+pub fn explain(text: &str) -> Option<Explanation> {
+    explain_with_options(text, &Options::default())
+}
+
+/// Explain how trigrams of a text are compared with profiles of languages, using the given options.
+///
+/// See [`explain`](fn.explain.html) for details.
+pub fn explain_with_options(text: &str, options: &Options) -> Option<Explanation> {
+    let text = preprocess(text, options);
+    let script = detect_script_with_options(&text, options)?;
+    let trigrams = get_ngrams_with_positions(&text, options.ngram_order.size());
+
+    let mut langs: Vec<LangExplanation> = candidate_profiles(script, options)
+        .filter(|&&(lang, _)| options.is_allowed(lang))
+        .map(|&(lang, profile)| {
+            let matches = profile.iter()
+                .enumerate()
+                .filter_map(|(i, &trigram)| {
+                    find_ngram(&trigrams, trigram).map(|(pos, _)| TrigramMatch {
+                        trigram: trigram.to_string(),
+                        text_rank: pos,
+                        profile_rank: i as u32,
+                        distance: (pos as i32 - i as i32).abs() as u32
+                    })
+                })
+                .collect();
+            LangExplanation {
+                lang,
+                distance: calculate_distance(profile, &trigrams),
+                matches
+            }
+        })
+        .collect();
+    langs.sort_by_key(|explanation| explanation.distance);
+    langs.truncate(EXPLAINED_LANGS);
+
+    Some(Explanation { script, langs })
+}
+
+/// Explanation of detection of a text, created by [explain](fn.explain.html).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    script: Script,
+    langs: Vec<LangExplanation>
+}
+
+impl Explanation {
+    pub fn script(&self) -> Script {
+        self.script
+    }
+
+    /// The closest languages, sorted by distance. Empty for scripts, which are used
+    /// only by one language (they have no profiles).
+    pub fn langs(&self) -> &[LangExplanation] {
+        &self.langs
+    }
+}
+
+/// Comparison of a text with the profile of one language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LangExplanation {
+    lang: Lang,
+    distance: u32,
+    matches: Vec<TrigramMatch>
+}
+
+impl LangExplanation {
+    pub fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    /// Total distance between the text and the profile, the lower the closer.
+    /// Every trigram of the profile, which is not found in the text, adds the maximum
+    /// distance of 300, so the distance is the sum of distances of the matches and
+    /// 300 for every missing trigram.
+    pub fn distance(&self) -> u32 {
+        self.distance
+    }
+
+    /// Trigrams of the profile, which are found in the text, in the order of the profile.
+    pub fn matches(&self) -> &[TrigramMatch] {
+        &self.matches
+    }
+}
+
+/// A trigram found both in a text and in a profile of a language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrigramMatch {
+    trigram: String,
+    text_rank: u32,
+    profile_rank: u32,
+    distance: u32
+}
+
+impl TrigramMatch {
+    pub fn trigram(&self) -> &str {
+        &self.trigram
+    }
+
+    /// Position of the trigram among the most common trigrams of the text, starting from 0.
+    pub fn text_rank(&self) -> u32 {
+        self.text_rank
+    }
+
+    /// Position of the trigram in the profile of the language, starting from 0.
+    pub fn profile_rank(&self) -> u32 {
+        self.profile_rank
+    }
+
+    /// Contribution of the trigram to the distance: the difference of its ranks.
+    pub fn distance(&self) -> u32 {
+        self.distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use constants::MAX_TRIGRAM_DISTANCE;
+
+    #[test]
+    fn test_explain() {
+        assert_eq!(explain("42!"), None);
+
+        let explanation = explain("Та нічого, все нормально. А в тебе як?").unwrap();
+        assert_eq!(explanation.script(), Script::Cyrillic);
+        let langs = explanation.langs();
+        assert_eq!(langs.len(), EXPLAINED_LANGS);
+        assert_eq!(langs[0].lang(), Lang::Ukr);
+        assert!(langs.windows(2).all(|pair| pair[0].distance() <= pair[1].distance()));
+
+        for lang in langs {
+            let matched: u32 = lang.matches().iter().map(TrigramMatch::distance).sum();
+            let missing = 300 - lang.matches().len() as u32;
+            assert_eq!(lang.distance(), matched + missing * MAX_TRIGRAM_DISTANCE);
+        }
+    }
+
+    #[test]
+    fn test_explain_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Rus, Lang::Bel]);
+        let explanation = explain_with_options("Та нічого, все нормально. А в тебе як?", &options).unwrap();
+        let langs: Vec<Lang> = explanation.langs().iter().map(LangExplanation::lang).collect();
+        assert_eq!(langs.len(), 2);
+        assert!(!langs.contains(&Lang::Ukr));
+
+        let explanation = explain("Γεια σου κόσμε").unwrap();
+        assert_eq!(explanation.script(), Script::Greek);
+        assert!(explanation.langs().is_empty());
+    }
+}
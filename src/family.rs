@@ -0,0 +1,110 @@
+use std::fmt;
+
+/// Represents a language family or, for big families like Indo-European and Afro-Asiatic,
+/// a branch of it (e.g. Slavic or Semitic).
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub enum Family {
+    // Keep this in alphabetic order
+    Armenian,
+    Austroasiatic,
+    Austronesian,
+    Baltic,
+    Bantu,
+    /// An isolate, Basque is not related to any other language
+    Basque,
+    Berber,
+    Celtic,
+    Chadic,
+    /// Constructed languages, e.g. Esperanto
+    Constructed,
+    Creole,
+    Cushitic,
+    Dravidian,
+    Germanic,
+    Hellenic,
+    IndoAryan,
+    Iranian,
+    /// Latin, the Romance languages descend from it
+    Italic,
+    Iroquoian,
+    Japonic,
+    Kartvelian,
+    Koreanic,
+    Kwa,
+    Mande,
+    Mongolic,
+    Romance,
+    Semitic,
+    Sinitic,
+    Slavic,
+    TaiKadai,
+    TibetoBurman,
+    Turkic,
+    Uralic,
+    VoltaNiger,
+}
+
+impl Family {
+    /// Get a human readable name of the family in English.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Family;
+    /// assert_eq!(Family::IndoAryan.name(), "Indo-Aryan");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Family::Armenian      => "Armenian",
+            Family::Austroasiatic => "Austroasiatic",
+            Family::Austronesian  => "Austronesian",
+            Family::Baltic        => "Baltic",
+            Family::Bantu         => "Bantu",
+            Family::Basque        => "Basque",
+            Family::Berber        => "Berber",
+            Family::Celtic        => "Celtic",
+            Family::Chadic        => "Chadic",
+            Family::Constructed   => "Constructed",
+            Family::Creole        => "Creole",
+            Family::Cushitic      => "Cushitic",
+            Family::Dravidian     => "Dravidian",
+            Family::Germanic      => "Germanic",
+            Family::Hellenic      => "Hellenic",
+            Family::IndoAryan     => "Indo-Aryan",
+            Family::Iranian       => "Iranian",
+            Family::Italic        => "Italic",
+            Family::Iroquoian     => "Iroquoian",
+            Family::Japonic       => "Japonic",
+            Family::Kartvelian    => "Kartvelian",
+            Family::Koreanic      => "Koreanic",
+            Family::Kwa           => "Kwa",
+            Family::Mande         => "Mande",
+            Family::Mongolic      => "Mongolic",
+            Family::Romance       => "Romance",
+            Family::Semitic       => "Semitic",
+            Family::Sinitic       => "Sinitic",
+            Family::Slavic        => "Slavic",
+            Family::TaiKadai      => "Tai-Kadai",
+            Family::TibetoBurman  => "Tibeto-Burman",
+            Family::Turkic        => "Turkic",
+            Family::Uralic        => "Uralic",
+            Family::VoltaNiger    => "Volta-Niger",
+        }
+    }
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_family_name() {
+        assert_eq!(Family::Slavic.name(), "Slavic");
+        assert_eq!(Family::TibetoBurman.to_string(), "Tibeto-Burman");
+    }
+}
@@ -0,0 +1,165 @@
+// Elements, which contents are not text of the page.
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style"];
+
+// Named character references, which are common in texts. Names with the first letter
+// in uppercase (e.g. `&Eacute;`) are decoded to the uppercase chars.
+// Sorted, to be found with binary search.
+const ENTITIES: &[(&str, char)] = &[
+    ("aacute", 'á'), ("acirc", 'â'), ("aelig", 'æ'), ("agrave", 'à'), ("amp", '&'), ("apos", '\''),
+    ("aring", 'å'), ("atilde", 'ã'), ("auml", 'ä'), ("ccedil", 'ç'), ("copy", '©'), ("eacute", 'é'),
+    ("ecirc", 'ê'), ("egrave", 'è'), ("eth", 'ð'), ("euml", 'ë'), ("gt", '>'), ("hellip", '…'),
+    ("iacute", 'í'), ("icirc", 'î'), ("igrave", 'ì'), ("iuml", 'ï'), ("laquo", '«'), ("ldquo", '“'),
+    ("lsquo", '‘'), ("lt", '<'), ("mdash", '—'), ("nbsp", '\u{A0}'), ("ndash", '–'), ("ntilde", 'ñ'),
+    ("oacute", 'ó'), ("ocirc", 'ô'), ("oelig", 'œ'), ("ograve", 'ò'), ("oslash", 'ø'), ("otilde", 'õ'),
+    ("ouml", 'ö'), ("quot", '"'), ("raquo", '»'), ("rdquo", '”'), ("reg", '®'), ("rsquo", '’'),
+    ("scaron", 'š'), ("szlig", 'ß'), ("thorn", 'þ'), ("uacute", 'ú'), ("ucirc", 'û'), ("ugrave", 'ù'),
+    ("uuml", 'ü'), ("yacute", 'ý'), ("yuml", 'ÿ'), ("zcaron", 'ž'),
+];
+
+// Character references longer than this are not looked up.
+const MAX_ENTITY_LENGTH: usize = 10;
+
+/// Strip HTML markup from a text: tags (with their attributes), comments and contents of
+/// `<script>` and `<style>` elements are replaced with spaces, character references
+/// (e.g. `&amp;`, `&eacute;`, `&#233;`) are decoded. Enabled by `html` feature.
+///
+/// # Example
+/// ```
+/// use whatlang::strip_html;
+///
+/// let html = r#"<p class="intro">Caf&eacute; <b>au</b> lait<script>var x = 1;</script></p>"#;
+/// assert_eq!(strip_html(html).split_whitespace().collect::<Vec<_>>(), vec!["Café", "au", "lait"]);
+/// ```
+pub fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut pos = 0;
+
+    while let Some(offset) = html[pos..].find(|ch: char| ch == '<' || ch == '&') {
+        text.push_str(&html[pos..pos + offset]);
+        pos += offset;
+        let rest = &html[pos..];
+
+        if rest.starts_with("<!--") {
+            pos += rest.find("-->").map_or(rest.len(), |end| end + 3);
+            text.push(' ');
+        } else if rest.starts_with('<') && is_tag_start(rest) {
+            let (end, name) = parse_tag(rest);
+            pos += end;
+            if SKIPPED_ELEMENTS.contains(&name.as_str()) && !rest[..end].ends_with("/>") {
+                pos += find_closing_tag(&html[pos..], &name);
+            }
+            text.push(' ');
+        } else if let Some((end, ch)) = parse_entity(rest) {
+            pos += end;
+            text.push(ch);
+        } else {
+            // A single `<` or `&`, which does not start markup
+            pos += 1;
+            text.push_str(&rest[..1]);
+        }
+    }
+    text.push_str(&html[pos..]);
+    text
+}
+
+// Tags start with a letter (`<p>`), a slash (`</p>`), `!` (`<!DOCTYPE>`) or `?` (`<?xml?>`).
+fn is_tag_start(rest: &str) -> bool {
+    rest[1..].chars().next().map_or(false, |ch| ch.is_ascii_alphabetic() || ch == '/' || ch == '!' || ch == '?')
+}
+
+// Length of the tag at the start of `rest` and its lowercase name (empty for closing tags).
+// Quoted attribute values may contain `>`.
+fn parse_tag(rest: &str) -> (usize, String) {
+    let name: String = rest[1..].chars()
+        .take_while(|ch| ch.is_ascii_alphanumeric())
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect();
+
+    let mut quote = None;
+    for (i, ch) in rest.char_indices().skip(1) {
+        match (quote, ch) {
+            (None, '"') | (None, '\'') => quote = Some(ch),
+            (Some(q), _) if q == ch => quote = None,
+            (None, '>') => return (i + 1, name),
+            _ => {}
+        }
+    }
+    (rest.len(), name)
+}
+
+// Length of the contents of the element till the end of its closing tag.
+fn find_closing_tag(rest: &str, name: &str) -> usize {
+    let mut pos = 0;
+    while let Some(offset) = rest[pos..].find("</") {
+        let start = pos + offset;
+        let tag_name = &rest[start + 2..];
+        if tag_name.len() >= name.len() && tag_name.as_bytes()[..name.len()].eq_ignore_ascii_case(name.as_bytes()) {
+            return start + parse_tag(&rest[start..]).0;
+        }
+        pos = start + 2;
+    }
+    rest.len()
+}
+
+// Decode a character reference at the start of `rest`, returns its length and the char.
+fn parse_entity(rest: &str) -> Option<(usize, char)> {
+    let end = rest.bytes().take(MAX_ENTITY_LENGTH + 2).position(|byte| byte == b';')?;
+    let name = &rest[1..end];
+
+    let ch = if name.starts_with("#x") || name.starts_with("#X") {
+        u32::from_str_radix(&name[2..], 16).ok().and_then(::std::char::from_u32)?
+    } else if name.starts_with('#') {
+        name[1..].parse().ok().and_then(::std::char::from_u32)?
+    } else {
+        let lowercase = name.to_ascii_lowercase();
+        let index = ENTITIES.binary_search_by(|&(entity, _)| entity.cmp(lowercase.as_str())).ok()?;
+        let ch = ENTITIES[index].1;
+        if name.starts_with(|ch: char| ch.is_ascii_uppercase()) {
+            ch.to_uppercase().next().unwrap_or(ch)
+        } else {
+            ch
+        }
+    };
+    Some((end + 1, ch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(html: &str) -> Vec<String> {
+        strip_html(html).split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_strip_html() {
+        assert_eq!(strip_html(""), "");
+        assert_eq!(strip_html("plain text"), "plain text");
+        assert_eq!(words("<p>Hello,<br/>world</p>"), vec!["Hello,", "world"]);
+        assert_eq!(words(r#"<a href="/en" title="English > all">Deutsch</a>"#), vec!["Deutsch"]);
+        assert_eq!(words("a < b & c <3"), vec!["a", "<", "b", "&", "c", "<3"]);
+        assert_eq!(words("<!-- comment --><!DOCTYPE html>Text"), vec!["Text"]);
+    }
+
+    #[test]
+    fn test_strip_html_skipped_elements() {
+        let html = "<style>p { color: red; }</style>Hallo<SCRIPT type=\"text/javascript\">if (a </b) {}</Script> Welt";
+        assert_eq!(words(html), vec!["Hallo", "Welt"]);
+        assert_eq!(words("<script src=\"a.js\"/>Text"), vec!["Text"]);
+        assert_eq!(words("Text<script>unclosed"), vec!["Text"]);
+    }
+
+    #[test]
+    fn test_strip_html_entities() {
+        assert_eq!(strip_html("caf&eacute; &Eacute;t&eacute; &amp; &lt;b&gt;"), "café Été & <b>");
+        assert_eq!(strip_html("&#233;&#xE9;&#XE9;&nbsp;"), "ééé\u{A0}");
+        assert_eq!(strip_html("&unknown; &#xZZ; &amp"), "&unknown; &#xZZ; &amp");
+    }
+
+    #[test]
+    fn test_entities_are_sorted() {
+        for pair in ENTITIES.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{} must go before {}", pair[1].0, pair[0].0);
+        }
+    }
+}
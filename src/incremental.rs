@@ -0,0 +1,320 @@
+use lang::Lang;
+use script::{Script, count_scripts_after, retain_allowed_scripts, scripts_by_appearance, sorted_script_counts};
+use info::Info;
+use options::Options;
+use detect::detect_by_script_counts;
+use model::{Ranking, builtin_script_langs, detect_lang_by_trigrams};
+use trigrams::{TrigramCounter, trigram_positions};
+use cjk::{HanChars, count_han_chars, detect_han_lang_by_counts};
+use arabic::{ArabicLetters, count_arabic_letters, filter_arabic_langs};
+use cyrillic::{CyrillicLetters, count_cyrillic_letters, filter_cyrillic_langs};
+use latin::{LatinLetters, count_latin_letters, filter_latin_langs};
+use serbo_croatian::{SerboCroatianWords, count_serbo_croatian_words, resolve_serbo_croatian_by_counts};
+use bulgarian_macedonian::{BulgarianMacedonianEvidence, count_bulgarian_macedonian_letters, count_bulgarian_macedonian_words,
+                           resolve_bulgarian_macedonian_by_counts};
+use norwegian::{NorwegianWords, count_norwegian_words, resolve_norwegian_by_counts};
+use devanagari::{DevanagariWords, count_devanagari_words, resolve_devanagari_by_counts};
+use danish_norwegian::{DanishNorwegianWords, count_danish_norwegian_words, resolve_danish_norwegian_by_counts};
+use malay_indonesian::{MalayIndonesianWords, count_malay_indonesian_words, resolve_malay_indonesian_by_counts};
+use czech_slovak::{CzechSlovakEvidence, count_czech_slovak_letters, count_czech_slovak_words, resolve_czech_slovak_by_counts};
+use spanish_portuguese::{SpanishPortugueseEvidence, count_spanish_portuguese_letters, count_spanish_portuguese_words,
+                         resolve_spanish_portuguese_by_counts};
+use turkish_azerbaijani::{TurkishAzerbaijaniEvidence, count_turkish_azerbaijani_letters, count_turkish_azerbaijani_words,
+                          resolve_turkish_azerbaijani_by_counts};
+use markers::{MarkerWords, count_marker_words};
+
+// A word longer than this (in bytes) is not in the lists of Serbian and Croatian words (nor a marker word),
+// so only its beginning is kept until the word ends.
+const MAX_WORD_SIZE: usize = 32;
+
+/// Detects a language of a text, which comes in chunks, e.g. from a network stream.
+///
+/// Chunks are pushed with `feed` and are not kept in memory: only counters of scripts
+/// and trigrams are accumulated, so the current best guess can be requested at any point
+/// with `current`. The result is the same as if the whole text was passed to
+/// [detect_with_options](fn.detect_with_options.html)
+/// (except for `Options::set_sample_size`, which is ignored).
+///
+/// # Example
+/// ```
+/// use whatlang::{IncrementalDetector, Lang};
+///
+/// let mut detector = IncrementalDetector::new();
+/// detector.feed("Ĉu vi ne volas ");
+/// detector.feed("eklerni Esperanton? Bonvolu!");
+/// assert_eq!(detector.current().unwrap().lang(), Lang::Epo);
+///
+/// detector.feed(" Estas unu de la plej bonaj aferoj!");
+/// let info = detector.finalize().unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalDetector {
+    options: Options,
+    is_empty: bool,
+    script_counts: Vec<usize>,
+    // Scripts in the order of their first chars, which resolves ties between them
+    scripts: Vec<Script>,
+    // The script, which combining marks at the beginning of the next chunk take
+    script_base: Option<Script>,
+    trigrams: TrigramCounter,
+    han_chars: HanChars,
+    arabic_letters: ArabicLetters,
+    cyrillic_letters: CyrillicLetters,
+    latin_letters: LatinLetters,
+    words: SerboCroatianWords,
+    bulgarian_macedonian: BulgarianMacedonianEvidence,
+    norwegian: NorwegianWords,
+    danish_norwegian: DanishNorwegianWords,
+    czech_slovak_letters: CzechSlovakEvidence,
+    czech_slovak_words: CzechSlovakEvidence,
+    spanish_portuguese: SpanishPortugueseEvidence,
+    devanagari: DevanagariWords,
+    turkish_azerbaijani: TurkishAzerbaijaniEvidence,
+    malay_indonesian: MalayIndonesianWords,
+    markers: MarkerWords,
+    // The last word of the fed text, which may continue in the next chunk
+    last_word: String
+}
+
+impl Default for IncrementalDetector {
+    fn default() -> Self {
+        Self::with_options(Options::default())
+    }
+}
+
+impl IncrementalDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: Options) -> Self {
+        IncrementalDetector {
+            trigrams: TrigramCounter::with_size(options.ngram_order.size(), 0),
+            options,
+            is_empty: true,
+            script_counts: vec![0; Script::COUNT],
+            scripts: vec![],
+            script_base: None,
+            han_chars: HanChars::default(),
+            arabic_letters: ArabicLetters::default(),
+            cyrillic_letters: CyrillicLetters::default(),
+            latin_letters: LatinLetters::default(),
+            words: SerboCroatianWords::default(),
+            bulgarian_macedonian: BulgarianMacedonianEvidence::default(),
+            norwegian: NorwegianWords::default(),
+            danish_norwegian: DanishNorwegianWords::default(),
+            czech_slovak_letters: CzechSlovakEvidence::default(),
+            czech_slovak_words: CzechSlovakEvidence::default(),
+            spanish_portuguese: SpanishPortugueseEvidence::default(),
+            devanagari: DevanagariWords::default(),
+            turkish_azerbaijani: TurkishAzerbaijaniEvidence::default(),
+            malay_indonesian: MalayIndonesianWords::default(),
+            markers: MarkerWords::default(),
+            last_word: String::new()
+        }
+    }
+
+    /// Push the next chunk of the text.
+    pub fn feed(&mut self, chunk: &str) {
+        if !chunk.trim().is_empty() {
+            self.is_empty = false;
+        }
+
+        let (mut counts, script_base) = count_scripts_after(chunk, self.script_base);
+        retain_allowed_scripts(&mut counts, &self.options);
+        if counts.iter().zip(self.script_counts.iter()).any(|(&count, &total)| count > 0 && total == 0) {
+            for script in scripts_by_appearance(chunk, self.script_base, &counts) {
+                if !self.scripts.contains(&script) {
+                    self.scripts.push(script);
+                }
+            }
+        }
+        self.script_base = script_base;
+        for (total, count) in self.script_counts.iter_mut().zip(counts.iter()) {
+            *total += count;
+        }
+        self.trigrams.push_str(chunk);
+        self.han_chars.add(count_han_chars(chunk));
+        self.arabic_letters.add(count_arabic_letters(chunk));
+        self.cyrillic_letters.add(count_cyrillic_letters(chunk));
+        self.latin_letters.add(count_latin_letters(chunk));
+        self.bulgarian_macedonian.add(count_bulgarian_macedonian_letters(chunk));
+        self.czech_slovak_letters.add(count_czech_slovak_letters(chunk));
+        self.spanish_portuguese.add(count_spanish_portuguese_letters(chunk));
+        self.turkish_azerbaijani.add(count_turkish_azerbaijani_letters(chunk));
+
+        // Words are counted once they end, since they can be split between chunks
+        self.last_word.push_str(chunk);
+        if let Some((pos, ch)) = self.last_word.char_indices().rev().find(|&(_, ch)| !ch.is_alphabetic()) {
+            let end = pos + ch.len_utf8();
+            self.words.add(count_serbo_croatian_words(&self.last_word[..end]));
+            self.bulgarian_macedonian.add(count_bulgarian_macedonian_words(&self.last_word[..end]));
+            self.norwegian.add(count_norwegian_words(&self.last_word[..end]));
+            self.danish_norwegian.add(count_danish_norwegian_words(&self.last_word[..end]));
+            self.czech_slovak_words.add(count_czech_slovak_words(&self.last_word[..end]));
+            self.spanish_portuguese.add(count_spanish_portuguese_words(&self.last_word[..end]));
+            self.devanagari.add(count_devanagari_words(&self.last_word[..end]));
+            self.turkish_azerbaijani.add(count_turkish_azerbaijani_words(&self.last_word[..end]));
+            self.malay_indonesian.add(count_malay_indonesian_words(&self.last_word[..end]));
+            self.markers.add(&count_marker_words(&self.last_word[..end], &self.options));
+            self.last_word.drain(..end);
+        }
+        if self.last_word.len() > MAX_WORD_SIZE {
+            let mut size = MAX_WORD_SIZE;
+            while !self.last_word.is_char_boundary(size) {
+                size -= 1;
+            }
+            self.last_word.truncate(size);
+        }
+    }
+
+    /// The best guess for the text fed so far.
+    pub fn current(&self) -> Option<Info> {
+        if self.is_empty {
+            return None;
+        }
+
+        let counts = sorted_script_counts(&self.script_counts, || self.scripts.clone());
+        let mut words = self.words;
+        words.add(count_serbo_croatian_words(&self.last_word));
+        let mut bulgarian_macedonian = self.bulgarian_macedonian;
+        bulgarian_macedonian.add(count_bulgarian_macedonian_words(&self.last_word));
+        let mut norwegian = self.norwegian;
+        norwegian.add(count_norwegian_words(&self.last_word));
+        let mut danish_norwegian = self.danish_norwegian;
+        danish_norwegian.add(count_danish_norwegian_words(&self.last_word));
+        let mut czech_slovak_words = self.czech_slovak_words;
+        czech_slovak_words.add(count_czech_slovak_words(&self.last_word));
+        let mut spanish_portuguese = self.spanish_portuguese;
+        spanish_portuguese.add(count_spanish_portuguese_words(&self.last_word));
+        let mut devanagari = self.devanagari;
+        devanagari.add(count_devanagari_words(&self.last_word));
+        let mut turkish_azerbaijani = self.turkish_azerbaijani;
+        turkish_azerbaijani.add(count_turkish_azerbaijani_words(&self.last_word));
+        let mut malay_indonesian = self.malay_indonesian;
+        malay_indonesian.add(count_malay_indonesian_words(&self.last_word));
+        let mut markers = self.markers.clone();
+        markers.add(&count_marker_words(&self.last_word, &self.options));
+
+        detect_by_script_counts(&counts, &self.options,
+                                |counts| detect_han_lang_by_counts(counts, self.han_chars, &self.options),
+                                |script| match script {
+                                    Script::Arabic => filter_arabic_langs(builtin_script_langs(script, &self.options), &counts,
+                                                                          self.arabic_letters),
+                                    Script::Cyrillic => filter_cyrillic_langs(builtin_script_langs(script, &self.options), &counts,
+                                                                              self.cyrillic_letters),
+                                    Script::Latin => filter_latin_langs(builtin_script_langs(script, &self.options), &counts,
+                                                                        self.latin_letters),
+                                    _ => builtin_script_langs(script, &self.options)
+                                },
+                                |script, candidates| self.detect_lang(script, candidates, &markers, |lang, rivals| {
+                                    if let Some(lang) = resolve_czech_slovak_by_counts(self.czech_slovak_letters,
+                                                                                       czech_slovak_words, lang, rivals) {
+                                        return Some(lang);
+                                    }
+                                    let resolved = resolve_danish_norwegian_by_counts(danish_norwegian, lang);
+                                    let lang = resolved.unwrap_or(lang);
+                                    resolve_serbo_croatian_by_counts(words, lang)
+                                        .or_else(|| resolve_norwegian_by_counts(norwegian, lang))
+                                        .or_else(|| resolve_spanish_portuguese_by_counts(spanish_portuguese, lang))
+                                        .or_else(|| resolve_bulgarian_macedonian_by_counts(bulgarian_macedonian, lang))
+                                        .or_else(|| resolve_devanagari_by_counts(devanagari, lang))
+                                        .or_else(|| resolve_turkish_azerbaijani_by_counts(turkish_azerbaijani, lang))
+                                        .or_else(|| resolve_malay_indonesian_by_counts(malay_indonesian, lang))
+                                        .or(resolved)
+                                }),
+                                // Only built-in languages are detected incrementally
+                                |_, _| None).ok()
+    }
+
+    /// Finish the text and return the result of detection.
+    pub fn finalize(self) -> Option<Info> {
+        self.current()
+    }
+
+    // `resolve` tells apart close languages by the words counted so far, see `resolve_close_langs`.
+    fn detect_lang<F>(&self, script: Script, candidates: &[Lang], markers: &MarkerWords,
+                      resolve: F) -> Option<Ranking>
+        where F: FnOnce(Lang, &[Lang]) -> Option<Lang>
+    {
+        let trigrams = trigram_positions(self.trigrams.clone().finish());
+        detect_lang_by_trigrams(&trigrams, markers, script, candidates, &self.options, resolve)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use detect::detect_with_options;
+
+    fn feed_in_chunks(text: &str, chunk_chars: usize, options: &Options) -> Option<Info> {
+        let mut detector = IncrementalDetector::with_options(options.clone());
+        let chars: Vec<char> = text.chars().collect();
+        for chunk in chars.chunks(chunk_chars) {
+            detector.feed(&chunk.iter().collect::<String>());
+        }
+        detector.finalize()
+    }
+
+    #[test]
+    fn test_incremental_detector_matches_detect() {
+        let texts = [
+            "Ĉu vi ne volas eklerni Esperanton? Bonvolu! Estas unu de la plej bonaj aferoj!",
+            "Введите запрос, чтобы найти нужную информацию",
+            "Gdje je rijeka? Ovdje, uvijek ovdje, djeca se igraju.",
+            "Gde je reka? Ovde, uvek ovde, deca se igraju.",
+            "Тој рече дека ќе дојде утре наутро.",
+            "Времето днес е студено и вали сняг.",
+            "Han fikk et brev fra moren sin i går.",
+            "Eg likar å lese bøker om kvelden.",
+            "Pigen gik ud efter skole for at lege.",
+            "यो किताब धेरै राम्रो छ र मलाई मन पर्छ।",
+            "میں نے کل بازار سے کتابیں خریدیں۔",
+            "Біз ертең кездесеміз.",
+            "Študenti sa pripravujú na skúšku celý týždeň. Uvidíme sa v sobotu večer?",
+            "Não sei por que ele não me ligou.",
+            "Biz yay tətilində dənizə getdik.",
+            "Ме\u{301}сто, где мы впервы\u{301}е встре\u{301}тились.",
+            "Hund und Katze",
+            "東京都知事選挙の結果発表",
+            "我们今天去北京大学参观了图书馆和博物馆",
+            "佢唔喺屋企，我哋聽日再嚟。",
+            "삼성전자는 Galaxy S24 Ultra를 서울에서 공개했다",
+            "где abc",
+            "abc где",
+            "",
+            "42!",
+        ];
+        let options = Options::new().set_blacklist(vec![Lang::Ita]).set_marker_words(Lang::Deu, vec!["und"])
+            .set_scripts(vec![Script::Latin, Script::Cyrillic, Script::Mandarin]);
+        for &text in texts.iter() {
+            for &chunk_chars in [1, 2, 3, 7, 1000].iter() {
+                assert_eq!(feed_in_chunks(text, chunk_chars, &Options::default()), detect_with_options(text, &Options::default()), "{}", text);
+                assert_eq!(feed_in_chunks(text, chunk_chars, &options), detect_with_options(text, &options), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_detector_current() {
+        let mut detector = IncrementalDetector::new();
+        assert_eq!(detector.current(), None);
+
+        detector.feed("   ");
+        assert_eq!(detector.current(), None);
+
+        detector.feed("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.");
+        assert_eq!(detector.current().unwrap().lang(), Lang::Deu);
+    }
+
+    #[test]
+    fn test_incremental_detector_with_long_words() {
+        let mut detector = IncrementalDetector::new();
+        for _ in 0..100 {
+            detector.feed("我们今天去北京大学参观了图书馆");
+        }
+        assert!(detector.last_word.len() <= MAX_WORD_SIZE);
+        assert_eq!(detector.finalize().unwrap().lang(), Lang::Cmn);
+    }
+}
@@ -1,14 +1,29 @@
 use lang::Lang;
+use custom_lang::CustomLang;
+use version::ModelVersion;
 use script::Script;
 
 const RELIABLE_CONFIDENCE_THRESHOLD: f64 = 0.8;
 
 /// Represents a full outcome of language detection.
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Info {
     pub(crate) lang: Lang,
     pub(crate) script: Script,
-    pub(crate) confidence: f64
+    pub(crate) confidence: f64,
+    pub(crate) secondary_script: Option<(Script, f64)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) second_best: Option<(Lang, f64)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) custom_lang: Option<CustomLang>,
+    // Results stored before the version was added are deserialized with the default one,
+    // which is never the version of a model
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) model_version: ModelVersion,
+    // Detected by a sample of the text, because the whole one did not fit in Options::set_max_duration
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) partial: bool
 }
 
 impl Info {
@@ -20,8 +35,81 @@ impl Info {
         self.script
     }
 
+    /// The runner-up script of a mixed text with its share of chars (from 0 to 1),
+    /// or `None` if the text contains only one script.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect, Script};
+    ///
+    /// let info = detect("Привет! Текст на русском with some English.").unwrap();
+    /// assert_eq!(info.script(), Script::Cyrillic);
+    /// assert_eq!(info.secondary_script().map(|(script, _)| script), Some(Script::Latin));
+    /// ```
+    pub fn secondary_script(&self) -> Option<(Script, f64)> {
+        self.secondary_script
+    }
+
+    /// The runner-up language with the margin of the best score over its score (from 0 for a tie
+    /// to 1), or `None` if the language was not chosen by scores, e.g. it is the only language
+    /// of the script or the only allowed one. Latin Serbian and Croatian are scored as one language
+    /// and told apart by their specific words, so one of them is never the runner-up of the other.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect, Lang};
+    ///
+    /// let info = detect("Además de todo lo anteriormente dicho, también encontramos...").unwrap();
+    /// assert_eq!(info.lang(), Lang::Spa);
+    /// let (lang, margin) = info.second_best().unwrap();
+    /// assert_ne!(lang, Lang::Spa);
+    /// assert!(margin > 0.0 && margin < 1.0);
+    /// ```
+    pub fn second_best(&self) -> Option<(Lang, f64)> {
+        self.second_best
+    }
+
+    /// The custom language, which matches the text better than [lang](#method.lang),
+    /// or `None` if the model has no such language, see
+    /// [ProfileModel::add_custom_profile](struct.ProfileModel.html#method.add_custom_profile).
+    /// When it is set, `lang` is the best built-in language, and confidence is of the custom one.
+    pub fn custom_lang(&self) -> Option<CustomLang> {
+        self.custom_lang
+    }
+
+    /// BCP-47 language tag of the detected language and script, see
+    /// [Lang::to_bcp47](enum.Lang.html#method.to_bcp47). The code of a custom language is returned as is.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::detect;
+    ///
+    /// let info = detect("Ĉu vi ne volas eklerni Esperanton? Bonvolu!").unwrap();
+    /// assert_eq!(info.to_bcp47(), "eo");
+    /// ```
+    pub fn to_bcp47(&self) -> String {
+        if let Some(custom_lang) = self.custom_lang {
+            return custom_lang.code().to_string();
+        }
+        self.lang.to_bcp47(self.script)
+    }
+
+    /// Version of the model, which detected the language, see [model_version](fn.model_version.html).
+    /// It's serialized with the rest of the result, so stored results of another version
+    /// can be detected again.
+    pub fn model_version(&self) -> ModelVersion {
+        self.model_version
+    }
+
+    /// Whether the language is detected by a part of the text only, because detection of
+    /// the whole text did not fit in [Options::set_max_duration](struct.Options.html#method.set_max_duration).
+    /// Partial results are never reliable.
+    pub fn is_partial(&self) -> bool {
+        self.partial
+    }
+
     pub fn is_reliable(&self) -> bool {
-        self.confidence > RELIABLE_CONFIDENCE_THRESHOLD
+        !self.partial && self.confidence > RELIABLE_CONFIDENCE_THRESHOLD
     }
 
     pub fn confidence(&self) -> f64 {
@@ -0,0 +1,47 @@
+use std::convert::TryFrom;
+
+use isolang::Language;
+use lang::{Lang, ParseLangError};
+
+impl From<Lang> for Language {
+    fn from(lang: Lang) -> Language {
+        // Every supported language has a valid ISO 639-3 code
+        Language::from_639_3(lang.code()).unwrap()
+    }
+}
+
+/// Macrolanguages are converted into the languages which represent them in whatlang,
+/// e.g. `Language::Zho` into `Lang::Cmn` and `Language::Ara` into `Lang::Arb`.
+impl TryFrom<Language> for Lang {
+    type Error = ParseLangError;
+
+    fn try_from(language: Language) -> Result<Self, Self::Error> {
+        let code = language.to_639_3();
+        Lang::from_code(code)
+            .or_else(|| language.to_639_1().and_then(Lang::from_code))
+            .ok_or_else(|| ParseLangError(code.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_into_language() {
+        for &lang in Lang::all() {
+            let language = Language::from(lang);
+            assert_eq!(language.to_639_3(), lang.code());
+            assert_eq!(Lang::try_from(language), Ok(lang));
+        }
+    }
+
+    #[test]
+    fn test_language_try_into_lang() {
+        assert_eq!(Lang::try_from(Language::Zho), Ok(Lang::Cmn));
+        assert_eq!(Lang::try_from(Language::Ara), Ok(Lang::Arb));
+        assert_eq!(Lang::try_from(Language::Nor), Ok(Lang::Nob));
+        assert_eq!(Lang::try_from(Language::Lat), Ok(Lang::Lat));
+        assert!(Lang::try_from(Language::Bre).is_err());
+    }
+}
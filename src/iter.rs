@@ -0,0 +1,75 @@
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+/// Extends iterators over texts with language detection.
+///
+/// # Example
+/// ```
+/// use whatlang::{DetectExt, Lang};
+///
+/// let texts = vec!["Ĉu vi ne volas eklerni Esperanton? Bonvolu!", "42!"];
+/// let langs: Vec<_> = texts.into_iter()
+///     .detect_langs()
+///     .map(|(text, info)| (text, info.map(|info| info.lang())))
+///     .collect();
+/// assert_eq!(langs, vec![
+///     ("Ĉu vi ne volas eklerni Esperanton? Bonvolu!", Some(Lang::Epo)),
+///     ("42!", None)
+/// ]);
+/// ```
+pub trait DetectExt: Iterator + Sized where Self::Item: AsRef<str> {
+    /// Pair every text with the result of its detection.
+    fn detect_langs(self) -> DetectLangs<Self> {
+        self.detect_langs_with_options(&Options::default())
+    }
+
+    /// Pair every text with the result of its detection, using the given options.
+    fn detect_langs_with_options(self, options: &Options) -> DetectLangs<Self> {
+        DetectLangs { iter: self, options: options.clone() }
+    }
+}
+
+impl<I> DetectExt for I where I: Iterator, I::Item: AsRef<str> {}
+
+/// Iterator over texts paired with results of their detection,
+/// created by [DetectExt::detect_langs](trait.DetectExt.html#method.detect_langs).
+#[derive(Debug, Clone)]
+pub struct DetectLangs<I> {
+    iter: I,
+    options: Options
+}
+
+impl<I> Iterator for DetectLangs<I> where I: Iterator, I::Item: AsRef<str> {
+    type Item = (I::Item, Option<Info>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.iter.next()?;
+        let info = detect_with_options(text.as_ref(), &self.options);
+        Some((text, info))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lang::Lang;
+
+    #[test]
+    fn test_detect_langs_with_options() {
+        let texts = vec![
+            String::from("Der Sommer war sehr heiß"),
+            String::from("Введите запрос, чтобы найти нужную информацию"),
+        ];
+        let options = Options::new().set_whitelist(vec![Lang::Eng, Lang::Rus]);
+        let langs: Vec<Option<Lang>> = texts.into_iter()
+            .detect_langs_with_options(&options)
+            .map(|(_, info)| info.map(|info| info.lang()))
+            .collect();
+        assert_eq!(langs, vec![Some(Lang::Eng), Some(Lang::Rus)]);
+    }
+}
@@ -1,20 +1,54 @@
+use std::error::Error;
 use std::fmt;
+use std::str::FromStr;
+
+use script::Script;
+use family::Family;
+use locale::lang_from_locale;
+use lazy::Lazy;
+use ngram_table::NgramTable;
 
 // Definition of Lang and Script lists are generated by build.rs
 include!(concat!(env!("OUT_DIR"), "/lang.rs"));
 
 impl Lang {
-    /// Get enum by ISO 639-3 code as a string.
+    /// Number of supported languages.
+    pub const COUNT: usize = LANG_COUNT;
+
+    /// Get all supported languages, in alphabetic order of their ISO 639-3 codes.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Lang;
+    /// assert_eq!(Lang::all().len(), Lang::COUNT);
+    /// assert_eq!(Lang::all()[0], Lang::Aka);
+    /// ```
+    pub fn all() -> &'static [Lang] {
+        &ALL_LANGS
+    }
+
+    /// Get enum by ISO 639-3 code, ISO 639-1 code or BCP-47 language tag as a string.
+    /// Only the primary language subtag of a tag is taken into account, the case is ignored.
     ///
     /// # Example
     /// ```
     /// use whatlang::Lang;
     /// assert_eq!(Lang::from_code("ukr"), Some(Lang::Ukr));
+    /// assert_eq!(Lang::from_code("uk"), Some(Lang::Ukr));
+    /// assert_eq!(Lang::from_code("en-US"), Some(Lang::Eng));
     /// ```
     pub fn from_code<S: Into<String>>(code: S) -> Option<Lang> {
+        lang_from_locale(&code.into())
+    }
+
+    pub(crate) fn from_iso639_3(code: &str) -> Option<Lang> {
         lang_from_code(code)
     }
 
+    pub(crate) fn from_iso639_1(code: &str) -> Option<Lang> {
+        lang_from_iso639_1(code)
+    }
+
     /// Convert enum into ISO 639-3 code as a string.
     ///
     /// # Example
@@ -26,7 +60,20 @@ impl Lang {
         lang_to_code(*self)
     }
 
-    /// Get a language name in the language itself.
+    /// Convert enum into ISO 639-1 two-letter code, or `None` if the language
+    /// has no such code (e.g. Cebuano).
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Lang;
+    /// assert_eq!(Lang::Ukr.code_iso639_1(), Some("uk"));
+    /// assert_eq!(Lang::Ceb.code_iso639_1(), None);
+    /// ```
+    pub fn code_iso639_1(&self) -> Option<&'static str> {
+        lang_to_iso639_1(*self)
+    }
+
+    /// Get a language name in the language itself, the same as [native_name](#method.native_name).
     ///
     /// # Example
     /// ```
@@ -37,6 +84,17 @@ impl Lang {
         lang_to_name(*self)
     }
 
+    /// Get a language name in the language itself.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Lang;
+    /// assert_eq!(Lang::Ukr.native_name(), "Українська");
+    /// ```
+    pub fn native_name(&self) -> &'static str {
+        lang_to_name(*self)
+    }
+
     /// Get a human readable name of the language in English.
     ///
     /// # Example
@@ -47,6 +105,73 @@ impl Lang {
     pub fn eng_name(&self) -> &'static str {
         lang_to_eng_name(*self)
     }
+
+    /// Get a BCP-47 language tag of the language written in the given script, e.g. `"sr-Latn"`.
+    /// The tag consists of ISO 639-1 code (or ISO 639-3 code, if the language has no two-letter one)
+    /// and ISO 15924 code of the script. The script subtag is omitted, if the language is written
+    /// only in this script, or if the language mixes its scripts in one text (Japanese and Korean).
+    /// Chinese is tagged as `"zh"`, because Simplified and Traditional Chinese are not told apart.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Lang, Script};
+    /// assert_eq!(Lang::Eng.to_bcp47(Script::Latin), "en");
+    /// assert_eq!(Lang::Srp.to_bcp47(Script::Cyrillic), "sr-Cyrl");
+    /// assert_eq!(Lang::Hin.to_bcp47(Script::Latin), "hi-Latn");
+    /// ```
+    pub fn to_bcp47(&self, script: Script) -> String {
+        let code = self.code_iso639_1().unwrap_or_else(|| self.code());
+        let scripts = self.scripts();
+        let mixes_scripts = *self == Lang::Jpn || *self == Lang::Kor;
+        if (scripts.len() == 1 && scripts[0] == script) || (mixes_scripts && scripts.contains(&script)) {
+            code.to_string()
+        } else {
+            format!("{}-{}", code, script.code())
+        }
+    }
+
+    /// Get a family of the language, for Indo-European and Afro-Asiatic languages a branch
+    /// of the family, e.g. Slavic or Semitic.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Family, Lang};
+    /// assert_eq!(Lang::Ukr.family(), Family::Slavic);
+    /// assert_eq!(Lang::Tur.family(), Family::Turkic);
+    /// ```
+    pub fn family(&self) -> Family {
+        lang_to_family(*self)
+    }
+
+    /// Get scripts, in which the language is detected, in alphabetic order.
+    /// Romanized profiles (see [Options::set_romanized](struct.Options.html#method.set_romanized))
+    /// are not taken into account.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Lang, Script};
+    /// assert_eq!(Lang::Srp.scripts(), vec![Script::Cyrillic, Script::Latin]);
+    /// assert_eq!(Lang::Jpn.scripts(), vec![Script::Hiragana, Script::Katakana, Script::Mandarin]);
+    /// ```
+    pub fn scripts(&self) -> Vec<Script> {
+        Script::all().iter()
+            .filter(|script| script.langs().contains(self))
+            .cloned()
+            .collect()
+    }
+
+    /// Check whether the language is written in a right-to-left script.
+    /// Languages written in several scripts (e.g. Uyghur) are right-to-left if one of the scripts is.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Lang;
+    /// assert!(Lang::Heb.is_rtl());
+    /// assert!(!Lang::Eng.is_rtl());
+    /// ```
+    pub fn is_rtl(&self) -> bool {
+        self.scripts().iter().any(Script::is_rtl)
+    }
 }
 
 impl fmt::Display for Lang {
@@ -55,12 +180,102 @@ impl fmt::Display for Lang {
     }
 }
 
+/// Parses a language from a code or a language tag, see [Lang::from_code](enum.Lang.html#method.from_code).
+///
+/// # Example
+/// ```
+/// use whatlang::Lang;
+/// let lang: Lang = "pt-BR".parse().unwrap();
+/// assert_eq!(lang, Lang::Por);
+/// assert!("xx".parse::<Lang>().is_err());
+/// ```
+impl FromStr for Lang {
+    type Err = ParseLangError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Lang::from_code(code).ok_or_else(|| ParseLangError(code.to_string()))
+    }
+}
+
+/// An error returned when a string is not a code of a supported language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLangError(pub(crate) String);
+
+impl fmt::Display for ParseLangError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown language code: {:?}", self.0)
+    }
+}
+
+impl Error for ParseLangError {}
+
 pub type LangProfile = &'static [&'static str];
 pub type LangProfileList = &'static [(Lang, LangProfile)];
 
+// A list of built-in profiles, which is decoded at first use and then kept for the lifetime of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProfileListId(usize);
+
+impl ProfileListId {
+    pub(crate) fn profiles(self) -> LangProfileList {
+        DECODED_PROFILE_LISTS[self.0].get_or_init(|| decode_profile_list(ENCODED_PROFILE_LISTS[self.0]))
+    }
+
+    pub(crate) fn table(self) -> &'static NgramTable {
+        &NGRAM_TABLES[self.0]
+    }
+}
+
+fn decode_profile_list(data: &[u16]) -> Vec<(Lang, LangProfile)> {
+    static DECODED_NGRAMS: Lazy<Vec<&'static str>> = Lazy::new();
+    let ngrams = DECODED_NGRAMS.get_or_init(|| NGRAMS.split('|').collect());
+
+    let mut profiles = Vec::new();
+    let mut data = data.iter().map(|&n| n as usize);
+    while let Some(lang_index) = data.next() {
+        let size = data.next().unwrap();
+        let profile: Vec<&'static str> = data.by_ref().take(size).map(|index| ngrams[index]).collect();
+        profiles.push((ALL_LANGS[lang_index], &*Box::leak(profile.into_boxed_slice())));
+    }
+    profiles
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Lang;
+    use super::*;
+    use script::Script;
+    use family::Family;
+
+    #[test]
+    fn test_decode_profile_lists() {
+        let profiles = LATIN_LANGS.profiles();
+        assert!(profiles.iter().all(|&(_, profile)| profile.len() == 300));
+        let &(_, english) = profiles.iter().find(|&&(lang, _)| lang == Lang::Eng).unwrap();
+        assert_eq!(&english[..5], &[" th", "the", " an", "he ", "nd "]);
+
+        let &(_, ukrainian) = CYRILLIC_LANGS.profiles().iter().find(|&&(lang, _)| lang == Lang::Ukr).unwrap();
+        assert_eq!(ukrainian[299], "і о");
+
+        // Decoded once
+        assert!(::std::ptr::eq(LATIN_LANGS.profiles(), profiles));
+    }
+
+    #[test]
+    fn test_generated_lists_are_sorted() {
+        // build.rs must generate languages in the same order in every build,
+        // otherwise ties between their scores are resolved differently
+        for &script in Script::all() {
+            let codes: Vec<&str> = profile_langs(script).iter().map(|lang| lang.code()).collect();
+            assert!(codes.windows(2).all(|pair| pair[0] < pair[1]), "{:?}: {:?}", script, codes);
+        }
+        for index in 0..PROFILE_LIST_COUNT {
+            let list = ProfileListId(index);
+            let codes: Vec<&str> = list.profiles().iter().map(|(lang, _)| lang.code()).collect();
+            assert!(codes.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", codes);
+            let table_codes: Vec<&str> = list.table().langs.iter().map(|lang| lang.code()).collect();
+            assert_eq!(table_codes, codes);
+        }
+    }
 
     #[test]
     fn test_from_code() {
@@ -68,6 +283,77 @@ mod tests {
         assert_eq!(Lang::from_code("ukr"), Some(Lang::Ukr));
         assert_eq!(Lang::from_code("ENG"), Some(Lang::Eng));
         assert_eq!(Lang::from_code("oops"), None);
+
+        assert_eq!(Lang::from_code("de"), Some(Lang::Deu));
+        assert_eq!(Lang::from_code("sr-Latn-RS"), Some(Lang::Srp));
+        assert_eq!(Lang::from_code("zh_CN"), Some(Lang::Cmn));
+        assert_eq!(Lang::from_code("no"), Some(Lang::Nob));
+        assert_eq!(Lang::from_code("xx-US"), None);
+        assert_eq!(Lang::from_code(""), None);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("eng".parse::<Lang>(), Ok(Lang::Eng));
+        assert_eq!("EN-gb".parse::<Lang>(), Ok(Lang::Eng));
+        assert_eq!(
+            "english".parse::<Lang>().unwrap_err().to_string(),
+            "unknown language code: \"english\""
+        );
+    }
+
+    #[test]
+    fn test_all() {
+        let all = Lang::all();
+        assert_eq!(all.len(), Lang::COUNT);
+        for pair in all.windows(2) {
+            assert!(pair[0].code() < pair[1].code());
+        }
+        for &lang in all {
+            assert_eq!(Lang::from_code(lang.code()), Some(lang));
+        }
+    }
+
+    #[test]
+    fn test_to_bcp47() {
+        assert_eq!(Lang::Deu.to_bcp47(Script::Latin), "de");
+        assert_eq!(Lang::Ceb.to_bcp47(Script::Latin), "ceb");
+        assert_eq!(Lang::Cmn.to_bcp47(Script::Mandarin), "zh");
+        assert_eq!(Lang::Jpn.to_bcp47(Script::Katakana), "ja");
+        assert_eq!(Lang::Kor.to_bcp47(Script::Mandarin), "ko");
+        assert_eq!(Lang::Srp.to_bcp47(Script::Latin), "sr-Latn");
+        assert_eq!(Lang::Khk.to_bcp47(Script::Mongolian), "mn-Mong");
+        assert_eq!(Lang::Rus.to_bcp47(Script::Latin), "ru-Latn");
+    }
+
+    #[test]
+    fn test_family() {
+        assert_eq!(Lang::Deu.family(), Family::Germanic);
+        assert_eq!(Lang::Hin.family(), Family::IndoAryan);
+        assert_eq!(Lang::Epo.family(), Family::Constructed);
+    }
+
+    #[test]
+    fn test_scripts() {
+        assert_eq!(Lang::Eng.scripts(), vec![Script::Latin]);
+        assert_eq!(Lang::Khk.scripts(), vec![Script::Cyrillic, Script::Mongolian]);
+        assert_eq!(Lang::Kor.scripts(), vec![Script::Hangul, Script::Mandarin]);
+        assert_eq!(Lang::Cmn.scripts(), vec![Script::Mandarin]);
+        for &lang in Lang::all() {
+            assert!(!lang.scripts().is_empty(), "{:?}", lang);
+        }
+    }
+
+    #[test]
+    fn test_is_rtl() {
+        assert!(Lang::Arb.is_rtl());
+        assert!(Lang::Pes.is_rtl());
+        assert!(Lang::Ydd.is_rtl());
+        assert!(Lang::Uig.is_rtl());
+        assert!(Lang::Div.is_rtl());
+        assert!(Lang::Nqo.is_rtl());
+        assert!(!Lang::Rus.is_rtl());
+        assert!(!Lang::Jpn.is_rtl());
     }
 
     #[test]
@@ -75,6 +361,14 @@ mod tests {
         assert_eq!(Lang::Spa.code(), "spa");
     }
 
+    #[test]
+    fn test_code_iso639_1() {
+        assert_eq!(Lang::Eng.code_iso639_1(), Some("en"));
+        assert_eq!(Lang::Rus.code_iso639_1(), Some("ru"));
+        assert_eq!(Lang::Ilo.code_iso639_1(), None);
+        assert_eq!(Lang::Zgh.code_iso639_1(), None);
+    }
+
     #[test]
     fn test_name() {
         assert_eq!(Lang::Rus.name(), "Русский");
@@ -82,6 +376,13 @@ mod tests {
         assert_eq!(Lang::Epo.name(), "Esperanto");
     }
 
+    #[test]
+    fn test_native_name() {
+        assert_eq!(Lang::Ukr.native_name(), "Українська");
+        assert_eq!(Lang::Deu.native_name(), "Deutsch");
+        assert_eq!(Lang::Eng.native_name(), Lang::Eng.name());
+    }
+
     #[test]
     fn test_eng_name() {
         assert_eq!(Lang::Spa.eng_name(), "Spanish");
@@ -0,0 +1,116 @@
+use lang::Lang;
+use script::Script;
+
+// Letters, which only some languages of the Latin script use: thorn and eth of the West Nordic
+// languages, the letters of Esperanto with circumflexes and breve, and letters of the Scandinavian,
+// German and Turkic languages.
+const SPECIFIC_LETTERS: [char; 18] = [
+    'þ', 'ð', 'ĉ', 'ĝ', 'ĥ', 'ĵ', 'ŝ', 'ŭ', 'å', 'ä', 'ö', 'ø', 'ü', 'ß', 'ç', 'ğ', 'ı', 'ş',
+];
+
+// Specific letters used by some languages of the Latin script, the other ones are never used
+// by the language, so a text with them is not written in it. Latin is written without any of them.
+const LANG_LETTERS: &[(Lang, &[char])] = &[
+    (Lang::Epo, &['ĉ', 'ĝ', 'ĥ', 'ĵ', 'ŝ', 'ŭ']),
+    (Lang::Fao, &['ð', 'ø']),
+    (Lang::Isl, &['þ', 'ð', 'ö']),
+    (Lang::Lat, &[]),
+];
+
+// Specific letters, which other languages may use: all of them, except those of the West Nordic
+// languages and of Esperanto.
+const OTHER_LANG_LETTERS: &[char] = &['å', 'ä', 'ö', 'ø', 'ü', 'ß', 'ç', 'ğ', 'ı', 'ş'];
+
+// A language is not a candidate, when a larger share of the Latin chars of a text are
+// specific letters which it does not use. A few of them are allowed in longer texts, e.g. in names.
+const MAX_FOREIGN_SHARE: f64 = 0.01;
+
+// Remove the candidates of a text of the Latin script, which do not use some of its letters,
+// so they are not scored by trigrams at all. If every candidate would be removed, none is.
+pub(crate) fn filter_latin_langs(langs: Vec<Lang>, counts: &[(Script, usize)], letters: LatinLetters) -> Vec<Lang> {
+    if letters.counts.iter().all(|&count| count == 0) {
+        return langs;
+    }
+    let latin = counts.iter().find(|&&(script, _)| script == Script::Latin).map_or(0, |&(_, count)| count);
+    let is_foreign = |lang: Lang| {
+        let used = LANG_LETTERS.iter().find(|&&(letters_lang, _)| letters_lang == lang)
+            .map_or(OTHER_LANG_LETTERS, |&(_, used)| used);
+        let foreign: usize = SPECIFIC_LETTERS.iter().zip(letters.counts.iter())
+            .filter(|&(letter, _)| !used.contains(letter))
+            .map(|(_, &count)| count)
+            .sum();
+        foreign as f64 > latin as f64 * MAX_FOREIGN_SHARE
+    };
+
+    if langs.iter().all(|&lang| is_foreign(lang)) {
+        return langs;
+    }
+    langs.into_iter().filter(|&lang| !is_foreign(lang)).collect()
+}
+
+// Numbers of every specific letter in a text, in the same order as SPECIFIC_LETTERS.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LatinLetters {
+    pub counts: [usize; 18]
+}
+
+impl LatinLetters {
+    pub fn add(&mut self, other: LatinLetters) {
+        for (count, other_count) in self.counts.iter_mut().zip(other.counts.iter()) {
+            *count += other_count;
+        }
+    }
+}
+
+// Most chars of Latin texts are ASCII, so only the other ones are lowercased and looked up.
+pub(crate) fn count_latin_letters(text: &str) -> LatinLetters {
+    let mut result = LatinLetters::default();
+    for ch in text.chars().filter(|ch| !ch.is_ascii()).flat_map(char::to_lowercase) {
+        if let Some(index) = SPECIFIC_LETTERS.iter().position(|&letter| letter == ch) {
+            result.counts[index] += 1;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use script::script_counts;
+
+    fn filter(text: &str) -> Vec<Lang> {
+        let langs = vec![Lang::Dan, Lang::Fao, Lang::Isl, Lang::Nob];
+        filter_latin_langs(langs, &script_counts(text), count_latin_letters(text))
+    }
+
+    #[test]
+    fn test_filter_latin_langs() {
+        assert_eq!(filter("Það er gott veður í dag."), vec![Lang::Isl]);
+        assert_eq!(filter("Tað er gott veður í dag."), vec![Lang::Fao, Lang::Isl]);
+        assert_eq!(filter("Børnini spæla fótbolt í garðinum."), vec![Lang::Fao]);
+        assert_eq!(filter("Ho kjem heim frå jobben klokka fem."), vec![Lang::Dan, Lang::Nob]);
+        assert_eq!(filter("Det er godt vejr i dag."), vec![Lang::Dan, Lang::Fao, Lang::Isl, Lang::Nob]);
+
+        let langs = vec![Lang::Epo, Lang::Ita, Lang::Lat];
+        let text = "Ĉu vi ne volas eklerni Esperanton?";
+        assert_eq!(filter_latin_langs(langs.clone(), &script_counts(text), count_latin_letters(text)), vec![Lang::Epo]);
+        let text = "Ich möchte eine Fahrkarte kaufen.";
+        assert_eq!(filter_latin_langs(langs.clone(), &script_counts(text), count_latin_letters(text)), vec![Lang::Ita]);
+        let text = "Roma caput mundi est.";
+        assert_eq!(filter_latin_langs(langs.clone(), &script_counts(text), count_latin_letters(text)), langs);
+    }
+
+    #[test]
+    fn test_filter_latin_langs_allows_few_foreign_letters() {
+        // An Icelandic name in a longer English text
+        let text = "The delegations met in Copenhagen yesterday to discuss trade, transport and energy. \
+                    They agreed to continue the negotiations next month and to prepare a new agreement \
+                    on cooperation. Iceland was represented by Þórdís";
+        let langs = vec![Lang::Eng, Lang::Isl];
+        assert_eq!(filter_latin_langs(langs.clone(), &script_counts(text), count_latin_letters(text)), langs);
+
+        // Letters foreign to all candidates do not remove them all
+        assert_eq!(filter_latin_langs(vec![Lang::Eng, Lang::Deu], &script_counts("þ"), count_latin_letters("þ")),
+                   vec![Lang::Eng, Lang::Deu]);
+    }
+}
@@ -0,0 +1,56 @@
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::Once;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+// A value, which is initialized on the first access. It can be shared between threads,
+// and is usable in statics (where it's never dropped).
+pub(crate) struct Lazy<T> {
+    once: Once,
+    value: AtomicPtr<T>,
+    marker: PhantomData<Box<T>>
+}
+
+impl<T> Lazy<T> {
+    pub const fn new() -> Self {
+        Lazy { once: Once::new(), value: AtomicPtr::new(ptr::null_mut()), marker: PhantomData }
+    }
+
+    pub fn get_or_init<F: FnOnce() -> T>(&self, init: F) -> &T {
+        self.once.call_once(|| self.value.store(Box::into_raw(Box::new(init())), Ordering::Release));
+        // The pointer is set only once by `call_once`, which has completed at this point,
+        // and is freed only when the value is dropped
+        unsafe { &*self.value.load(Ordering::Acquire) }
+    }
+}
+
+impl<T> Drop for Lazy<T> {
+    fn drop(&mut self) {
+        let value = *self.value.get_mut();
+        if !value.is_null() {
+            drop(unsafe { Box::from_raw(value) });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::thread;
+
+    #[test]
+    fn test_lazy_is_initialized_once() {
+        let lazy = Arc::new(Lazy::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let threads: Vec<_> = (0..4).map(|_| {
+            let (lazy, calls) = (lazy.clone(), calls.clone());
+            thread::spawn(move || *lazy.get_or_init(|| calls.fetch_add(1, Ordering::SeqCst) + 42))
+        }).collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
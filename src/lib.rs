@@ -30,25 +30,160 @@
 //! let detector = Detector::with_whitelist(whitelist);
 //! let lang = detector.detect_lang("There is no reason not to learn Esperanto.");
 //! assert_eq!(lang, Some(Lang::Eng));
+//! ```
+//!
+//! # Features
+//!
+//! * `parallel` - count characters of scripts in parallel using [rayon](https://crates.io/crates/rayon).
+//!   It's disabled by default, so whatlang never spawns threads on its own.
+//!   The feature has no effect on WebAssembly (`wasm32`) targets.
+//! * `isolang` - conversions between `Lang` and `isolang::Language`
+//!   from [isolang](https://crates.io/crates/isolang) crate.
+//! * `serde` - serialization of `Lang`, `Script` and `Info` with [serde](https://crates.io/crates/serde).
+//!   Languages and scripts are represented by their enum names, see [serde_code](serde_code/index.html)
+//!   to represent them by codes.
+//! * `mmap` - memory-map files of [ProfileBundle](struct.ProfileBundle.html) on Unix instead of reading them.
+//! * `cache` - cache results of detection of recently used texts in a [Detector](struct.Detector.html),
+//!   see `Detector::set_cache_size`.
+//! * `tracing` - emit spans and events of detection (chosen script, candidates, scores of languages)
+//!   with [tracing](https://crates.io/crates/tracing) at debug level.
+//! * `arbitrary` - implementations of `Arbitrary` for `Lang`, `Script` and `Options` from
+//!   [arbitrary](https://crates.io/crates/arbitrary) crate, to fuzz and property-test code using them.
+//!   A fuzz target of `detect_with_options` is in `fuzz` directory.
+//! * `unicode-script` - find scripts of chars by the script property from
+//!   [unicode-script](https://crates.io/crates/unicode-script) crate instead of the built-in ranges,
+//!   to follow its (possibly newer) version of Unicode. Results are mapped to `Script`,
+//!   chars of other scripts are not counted, as without the feature.
+//! * `historic-scripts` - detect historic scripts of manuscripts and inscriptions: Coptic, Glagolitic,
+//!   Gothic, Old Italic, Phoenician and Runic. They are used by no supported language, so only
+//!   the script is detected, e.g. with `detect_script`. The scripts follow the others in `Script`.
 extern crate fnv;
+#[cfg(parallel)]
+extern crate rayon;
+#[cfg(feature = "isolang")]
+extern crate isolang;
+#[cfg(feature = "encoding")]
+extern crate encoding_rs;
+#[cfg(feature = "encoding")]
+extern crate chardetng;
+#[cfg(feature = "normalization")]
+extern crate unicode_normalization;
+#[cfg(feature = "segmentation")]
+extern crate unicode_segmentation;
+#[cfg(all(feature = "mmap", unix))]
+extern crate libc;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary;
+#[cfg(feature = "unicode-script")]
+extern crate unicode_script;
 
+#[macro_use]
+mod trace;
 mod lang;
+mod family;
 mod script;
 mod info;
 mod utils;
 mod trigrams;
 mod detect;
 mod detector;
+mod incremental;
+mod tokens;
+mod bytes;
+mod batch;
+mod iter;
+mod reader;
 mod options;
+mod model;
+mod locale;
+mod sample;
+mod runs;
+mod lines;
+mod sentences;
+mod multilingual;
+mod explain;
+mod train;
+mod profile;
+mod ngram_table;
+mod bundle;
+mod cjk;
+mod arabic;
+mod cyrillic;
+mod latin;
+mod serbo_croatian;
+mod bulgarian_macedonian;
+mod norwegian;
+mod danish_norwegian;
+mod czech_slovak;
+mod spanish_portuguese;
+mod devanagari;
+mod turkish_azerbaijani;
+mod malay_indonesian;
+mod markers;
 mod constants;
+mod lazy;
+mod custom_lang;
+mod version;
+mod preprocess;
+mod code;
+#[cfg(feature = "html")]
+mod html;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "isolang")]
+mod isolang_interop;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_interop;
+#[cfg(feature = "unicode-script")]
+mod unicode_script_interop;
+#[cfg(feature = "serde")]
+pub mod serde_code;
+#[cfg(feature = "raw-profiles")]
+pub mod raw_profiles;
 
-pub use lang::Lang;
-pub use script::Script;
+pub use lang::{Lang, ParseLangError};
+pub use custom_lang::CustomLang;
+pub use family::Family;
+pub use script::{Script, ParseScriptError};
 pub use info::Info;
+pub use version::{model_version, ModelVersion};
 pub use detector::Detector;
-pub use options::Options;
+pub use incremental::IncrementalDetector;
+pub use options::{Options, NgramOrder, Preset, ScoringMetric};
+#[cfg(feature = "normalization")]
+pub use options::Normalization;
+pub use model::{LanguageModel, Ranking, TrigramModel};
+
+pub use locale::{parse_accept_language, priors_from_locales};
 
 pub use detect::detect;
 pub use detect::detect_lang;
+pub use detect::detect_lang_with_options;
+pub use detect::{detect_lang_with_script, detect_lang_with_script_and_options};
 pub use detect::detect_with_options;
-pub use script::detect_script;
+pub use detect::{try_detect, try_detect_with_options, DetectError};
+pub use code::is_code;
+pub use bytes::{detect_bytes, detect_bytes_with_options};
+pub use batch::{detect_many, detect_many_with_options};
+pub use iter::{DetectExt, DetectLangs};
+pub use tokens::{detect_tokens, detect_tokens_with_options};
+pub use trigrams::{extract_trigrams, Scratch};
+pub use train::{train_profile, train_word_list};
+pub use profile::{LanguageProfile, ProfileModel};
+pub use bundle::ProfileBundle;
+pub use explain::{explain, explain_with_options, Explanation, LangExplanation, TrigramMatch};
+pub use reader::{detect_from_reader, detect_from_reader_with_options};
+pub use script::{detect_script, detect_scripts, script_counts};
+pub use runs::{script_runs, ScriptRuns};
+#[cfg(feature = "html")]
+pub use html::strip_html;
+pub use lines::{detect_lines, detect_lines_with_options, DetectLines};
+pub use sentences::{sentences, Sentences, detect_sentences, detect_sentences_with_options, DetectSentences};
+pub use multilingual::{detect_proportions, detect_proportions_with_options};
+pub use multilingual::{detect_spans, detect_spans_with_options};
+pub use multilingual::{extract_lang_spans, extract_lang_spans_with_options, extract_lang_text, extract_lang_text_with_options};
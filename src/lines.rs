@@ -0,0 +1,105 @@
+use std::ops::Range;
+
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+/// Detect a language and a script of every line of a text.
+/// Lines are split by `\n` or `\r\n`, byte ranges of lines do not include the line endings.
+/// Empty lines are reported as well, so the n-th item belongs to the n-th line.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_lines, Lang};
+///
+/// let text = "Введите запрос, чтобы найти нужную информацию\r\nThere is no reason not to learn Esperanto.\n";
+/// let lines: Vec<_> = detect_lines(text)
+///     .map(|(range, info)| (&text[range], info.map(|info| info.lang())))
+///     .collect();
+/// assert_eq!(lines, vec![
+///     ("Введите запрос, чтобы найти нужную информацию", Some(Lang::Rus)),
+///     ("There is no reason not to learn Esperanto.", Some(Lang::Eng)),
+/// ]);
+/// ```
+pub fn detect_lines(text: &str) -> DetectLines<'_> {
+    detect_lines_with_options(text, &Options::default())
+}
+
+/// Detect a language and a script of every line of a text, using the given options.
+///
+/// See [`detect_lines`](fn.detect_lines.html) for details.
+pub fn detect_lines_with_options<'a>(text: &'a str, options: &Options) -> DetectLines<'a> {
+    DetectLines { text, pos: 0, options: options.clone() }
+}
+
+/// An iterator over byte ranges of lines in a text with results of their detection,
+/// created by [detect_lines](fn.detect_lines.html).
+#[derive(Debug, Clone)]
+pub struct DetectLines<'a> {
+    text: &'a str,
+    pos: usize,
+    options: Options
+}
+
+impl<'a> Iterator for DetectLines<'a> {
+    type Item = (Range<usize>, Option<Info>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.text.len() {
+            return None;
+        }
+
+        let start = self.pos;
+        let mut end = match self.text[start..].find('\n') {
+            Some(offset) => {
+                self.pos = start + offset + 1;
+                start + offset
+            }
+            None => {
+                self.pos = self.text.len();
+                self.text.len()
+            }
+        };
+        if self.text[start..end].ends_with('\r') {
+            end -= 1;
+        }
+
+        let info = detect_with_options(&self.text[start..end], &self.options);
+        Some((start..end, info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lang::Lang;
+
+    fn lines(text: &str) -> Vec<(&str, Option<Lang>)> {
+        detect_lines(text).map(|(range, info)| (&text[range], info.map(|info| info.lang()))).collect()
+    }
+
+    #[test]
+    fn test_detect_lines() {
+        assert_eq!(lines(""), vec![]);
+        assert_eq!(lines("\n"), vec![("", None)]);
+        assert_eq!(
+            lines("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.\n\n42\r\nВведите запрос, чтобы найти нужную информацию"),
+            vec![
+                ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", Some(Lang::Deu)),
+                ("", None),
+                ("42", None),
+                ("Введите запрос, чтобы найти нужную информацию", Some(Lang::Rus))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_lines_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Eng]);
+        let text = "Der Sommer war sehr heiß\nHello";
+        let langs: Vec<_> = detect_lines_with_options(text, &options)
+            .map(|(_, info)| info.map(|info| info.lang()))
+            .collect();
+        assert_eq!(langs, vec![Some(Lang::Eng), Some(Lang::Eng)]);
+    }
+}
@@ -0,0 +1,126 @@
+use lang::Lang;
+
+/// Parse a value of HTTP `Accept-Language` header into a list of languages with
+/// their weights (quality values), which can be used as priors with
+/// [Options::set_priors](struct.Options.html#method.set_priors).
+///
+/// Unknown and unsupported language tags are skipped, as well as `*` and tags with `q=0`.
+/// The result is sorted by weight in descending order.
+///
+/// # Example
+/// ```
+/// use whatlang::{parse_accept_language, Lang};
+///
+/// let priors = parse_accept_language("uk-UA,uk;q=0.9,en-US;q=0.8,*;q=0.5");
+/// assert_eq!(priors, vec![(Lang::Ukr, 1.0), (Lang::Eng, 0.8)]);
+/// ```
+pub fn parse_accept_language(header: &str) -> Vec<(Lang, f64)> {
+    let mut priors: Vec<(Lang, f64)> = Vec::new();
+
+    for item in header.split(',') {
+        let mut parts = item.split(';');
+        let tag = parts.next().unwrap_or("").trim();
+
+        let mut weight = 1.0;
+        for param in parts {
+            let param = param.trim();
+            if param.starts_with("q=") || param.starts_with("Q=") {
+                weight = match param[2..].trim().parse::<f64>() {
+                    Ok(q) if (0.0..=1.0).contains(&q) => q,
+                    _ => 0.0
+                };
+            }
+        }
+
+        if weight > 0.0 {
+            if let Some(lang) = lang_from_locale(tag) {
+                add_prior(&mut priors, lang, weight);
+            }
+        }
+    }
+
+    priors.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    priors
+}
+
+/// Convert a list of locale strings (e.g. `"en_US"`, `"de-AT"`, `"fr"`) into a list of
+/// priors, where every recognized language gets weight 1.0.
+///
+/// # Example
+/// ```
+/// use whatlang::{priors_from_locales, Lang};
+///
+/// let priors = priors_from_locales(&["de_AT", "en-GB", "xx", "de"]);
+/// assert_eq!(priors, vec![(Lang::Deu, 1.0), (Lang::Eng, 1.0)]);
+/// ```
+pub fn priors_from_locales<I, S>(locales: I) -> Vec<(Lang, f64)>
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let mut priors: Vec<(Lang, f64)> = Vec::new();
+    for locale in locales {
+        if let Some(lang) = lang_from_locale(locale.as_ref()) {
+            add_prior(&mut priors, lang, 1.0);
+        }
+    }
+    priors
+}
+
+// Keep only the highest weight of a language, e.g. for "en-US,en;q=0.5".
+fn add_prior(priors: &mut Vec<(Lang, f64)>, lang: Lang, weight: f64) {
+    match priors.iter_mut().find(|prior| prior.0 == lang) {
+        Some(prior) => if prior.1 < weight { prior.1 = weight },
+        None => priors.push((lang, weight))
+    }
+}
+
+// Take the primary language subtag of a locale (e.g. "pt" from "pt-BR" or "pt_BR.UTF-8")
+// and convert it into a language.
+pub(crate) fn lang_from_locale(locale: &str) -> Option<Lang> {
+    let primary = locale.trim()
+        .split(&['-', '_', '.', '@'][..])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+
+    match primary.len() {
+        2 => match primary.as_ref() {
+            // Norwegian macrolanguage, Bokmål is the most common written standard
+            "no" => Some(Lang::Nob),
+            code => Lang::from_iso639_1(code)
+        },
+        3 => Lang::from_iso639_3(&primary),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accept_language() {
+        assert_eq!(parse_accept_language(""), vec![]);
+        assert_eq!(parse_accept_language("*"), vec![]);
+        assert_eq!(parse_accept_language("ru"), vec![(Lang::Rus, 1.0)]);
+        assert_eq!(
+            parse_accept_language("en;q=0.3, de-CH ; q=0.7, fr-CH, xx;q=0.9"),
+            vec![(Lang::Fra, 1.0), (Lang::Deu, 0.7), (Lang::Eng, 0.3)]
+        );
+
+        // Invalid or zero weights
+        assert_eq!(parse_accept_language("es;q=0, it;q=2, pt;q=abc, no;q=0.1"), vec![(Lang::Nob, 0.1)]);
+
+        // Duplicates
+        assert_eq!(parse_accept_language("en;q=0.2, en-GB;q=0.6"), vec![(Lang::Eng, 0.6)]);
+    }
+
+    #[test]
+    fn test_lang_from_locale() {
+        assert_eq!(lang_from_locale("uk"), Some(Lang::Ukr));
+        assert_eq!(lang_from_locale("pt-BR"), Some(Lang::Por));
+        assert_eq!(lang_from_locale("pt_BR.UTF-8"), Some(Lang::Por));
+        assert_eq!(lang_from_locale("EN"), Some(Lang::Eng));
+        assert_eq!(lang_from_locale("epo"), Some(Lang::Epo));
+        assert_eq!(lang_from_locale("C"), None);
+        assert_eq!(lang_from_locale(""), None);
+    }
+}
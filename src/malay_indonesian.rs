@@ -0,0 +1,121 @@
+use lang::Lang;
+
+// Malay and Indonesian are two standards of the same language, so their trigram profiles are very
+// close and texts of one language are often detected as the other one. They differ mostly in the
+// spelling of common words ("kerana" and "karena", "bahawa" and "bahwa", "wang" and "uang"),
+// in the borrowings of English and Dutch ("polis" and "polisi", "teksi" and "taksi") and in some
+// of the words themselves ("percuma" and "gratis", "kedai" and "toko").
+//
+// Words are sorted, to be found with binary search.
+const MALAY_WORDS: &[&str] = &[
+    "ahad", "akaun", "aktiviti", "awak", "baharu", "bahawa", "bas", "doktor", "faham", "fikir",
+    "fikiran", "filem", "hospital", "iaitu", "isnin", "jiran", "jumaat", "jururawat", "kanak",
+    "kedai", "kempen", "kenderaan", "kerana", "khamis", "kualiti", "lelaki", "mahu", "maklumat",
+    "memandu", "mesyuarat", "pelancong", "peperiksaan", "percuma", "polis", "projek", "rasmi",
+    "sahaja", "selepas", "sesiapa", "sukan", "syarikat", "teksi", "telefon", "ubat", "universiti",
+    "wang",
+];
+
+const INDONESIAN_WORDS: &[&str] = &[
+    "aktivitas", "akun", "bahwa", "bandara", "banget", "bisa", "bus", "dokter", "enggak", "film",
+    "gimana", "gratis", "informasi", "jumat", "kamar", "kamis", "kampanye", "kantor", "karena",
+    "karyawan", "kemarin", "kendaraan", "kualitas", "laki", "mau", "mobil", "nggak", "obat",
+    "paham", "pemerintah", "pikir", "pikiran", "polisi", "proyek", "resmi", "senin", "sesudah",
+    "taksi", "telepon", "tetangga", "toko", "uang", "universitas", "wisatawan", "yaitu",
+];
+
+// Choose between Malay and Indonesian by the words, which are used only in one of the languages.
+// Most sentences are written equally in both, so Malay is chosen only when the text has more
+// Malay words. Returns None if `lang` is neither Malay nor Indonesian.
+pub(crate) fn resolve_malay_indonesian(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_malay_indonesian(lang) {
+        return None;
+    }
+    resolve_malay_indonesian_by_counts(count_malay_indonesian_words(text), lang)
+}
+
+// Same as resolve_malay_indonesian, but with the words already counted by count_malay_indonesian_words.
+pub(crate) fn resolve_malay_indonesian_by_counts(words: MalayIndonesianWords, lang: Lang) -> Option<Lang> {
+    if !is_malay_indonesian(lang) {
+        return None;
+    }
+
+    if words.malay > words.indonesian {
+        Some(Lang::Zlm)
+    } else {
+        Some(Lang::Ind)
+    }
+}
+
+// Numbers of words, which are used only in Malay or only in Indonesian.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MalayIndonesianWords {
+    pub malay: usize,
+    pub indonesian: usize
+}
+
+impl MalayIndonesianWords {
+    pub fn add(&mut self, other: MalayIndonesianWords) {
+        self.malay += other.malay;
+        self.indonesian += other.indonesian;
+    }
+}
+
+pub(crate) fn count_malay_indonesian_words(text: &str) -> MalayIndonesianWords {
+    let mut result = MalayIndonesianWords::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(MALAY_WORDS, &word) {
+            result.malay += 1;
+        } else if contains(INDONESIAN_WORDS, &word) {
+            result.indonesian += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_malay_indonesian(lang: Lang) -> bool {
+    lang == Lang::Zlm || lang == Lang::Ind
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [MALAY_WORDS, INDONESIAN_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in MALAY_WORDS.iter() {
+            assert!(!contains(INDONESIAN_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_malay_indonesian() {
+        let text = "Saya tidak mahu pergi kerana hujan.";
+        assert_eq!(resolve_malay_indonesian(text, Lang::Ind), Some(Lang::Zlm));
+
+        let text = "Saya tidak mau pergi karena hujan.";
+        assert_eq!(resolve_malay_indonesian(text, Lang::Zlm), Some(Lang::Ind));
+
+        // Without evidence the text is taken for Indonesian
+        let text = "Saya suka makan nasi.";
+        assert_eq!(resolve_malay_indonesian(text, Lang::Zlm), Some(Lang::Ind));
+
+        assert_eq!(resolve_malay_indonesian(text, Lang::Tgl), None);
+    }
+
+    #[test]
+    fn test_count_malay_indonesian_words() {
+        let text = "Dia naik teksi ke pejabat. Dia naik taksi ke kantor, karena hujan.";
+        assert_eq!(count_malay_indonesian_words(text), MalayIndonesianWords { malay: 1, indonesian: 3 });
+    }
+}
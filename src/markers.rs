@@ -0,0 +1,75 @@
+use lang::Lang;
+use options::Options;
+
+// Numbers of words of a text, which are marker words of languages set by `Options::set_marker_words`.
+// `matched` has a counter for every language of the options, in the same order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MarkerWords {
+    pub total: usize,
+    pub matched: Vec<usize>
+}
+
+impl MarkerWords {
+    pub fn add(&mut self, other: &MarkerWords) {
+        self.total += other.total;
+        if self.matched.len() < other.matched.len() {
+            self.matched.resize(other.matched.len(), 0);
+        }
+        for (count, other_count) in self.matched.iter_mut().zip(other.matched.iter()) {
+            *count += other_count;
+        }
+    }
+
+    // Share of words of the text, which are marker words of the language, from 0 to 1.
+    pub fn share(&self, lang: Lang, options: &Options) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        options.marker_words.iter()
+            .zip(self.matched.iter())
+            .find(|&(&(marker_lang, _), _)| marker_lang == lang)
+            .map_or(0.0, |(_, &count)| count as f64 / self.total as f64)
+    }
+}
+
+pub(crate) fn count_marker_words(text: &str, options: &Options) -> MarkerWords {
+    let mut result = MarkerWords::default();
+    if options.marker_words.is_empty() {
+        return result;
+    }
+    result.matched = vec![0; options.marker_words.len()];
+    for word in text.split(|ch: char| !ch.is_alphabetic()).filter(|word| !word.is_empty()) {
+        let word = word.to_lowercase();
+        result.total += 1;
+        for (count, (_, words)) in result.matched.iter_mut().zip(options.marker_words.iter()) {
+            if words.binary_search(&word).is_ok() {
+                *count += 1;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_marker_words() {
+        let options = Options::new()
+            .set_marker_words(Lang::Eng, vec!["the", "and"])
+            .set_marker_words(Lang::Deu, vec!["und", "Die"]);
+        let words = count_marker_words("The cat and the dog, die Katze und der Hund", &options);
+        assert_eq!(words, MarkerWords { total: 10, matched: vec![3, 2] });
+        assert_eq!(words.share(Lang::Eng, &options), 0.3);
+        assert_eq!(words.share(Lang::Deu, &options), 0.2);
+        assert_eq!(words.share(Lang::Fra, &options), 0.0);
+
+        let mut total = MarkerWords::default();
+        total.add(&words);
+        total.add(&words);
+        assert_eq!(total, MarkerWords { total: 20, matched: vec![6, 4] });
+
+        assert_eq!(count_marker_words("The cat", &Options::new()), MarkerWords::default());
+    }
+}
@@ -0,0 +1,846 @@
+use std::cmp::Ordering;
+
+use fnv::{FnvHashMap, FnvHashSet};
+
+use lang::*;
+use custom_lang::CustomLang;
+use script::Script;
+use options::{NgramOrder, Options, ScoringMetric};
+use trigrams::{Ngram, Scratch, find_ngram, get_ngrams_with_positions};
+use ngram_table::NgramTable;
+use serbo_croatian::{is_serbo_croatian, resolve_serbo_croatian};
+use bulgarian_macedonian::resolve_bulgarian_macedonian;
+use czech_slovak::resolve_czech_slovak;
+use spanish_portuguese::resolve_spanish_portuguese;
+use norwegian::resolve_norwegian;
+use danish_norwegian::resolve_danish_norwegian;
+use malay_indonesian::{is_malay_indonesian, resolve_malay_indonesian};
+use devanagari::resolve_devanagari;
+use turkish_azerbaijani::resolve_turkish_azerbaijani;
+use markers::{MarkerWords, count_marker_words};
+use constants::{LANG_PROFILE_SIZE, MAX_TRIGRAM_DISTANCE, MAX_TOTAL_DISTANCE, TEXT_TRIGRAMS_SIZE};
+
+/// The best language with confidence and the runner-up language with the margin of the best
+/// score over its score, see [LanguageModel::detect_lang_with_second_best_in](trait.LanguageModel.html#method.detect_lang_with_second_best_in).
+pub type Ranking = (Lang, f64, Option<(Lang, f64)>);
+
+/// A backend that chooses a language for a text among given candidates.
+///
+/// Whatlang detects a script of the text and narrows the list of candidate
+/// languages (applying whitelist or blacklist) before a model is invoked,
+/// so an implementation only needs to score the text against `candidates`.
+/// A model is expected to honour other [Options](struct.Options.html) where it makes
+/// sense for it, e.g. priors.
+/// The built-in implementation is [TrigramModel](struct.TrigramModel.html).
+///
+/// # Example
+/// ```
+/// use whatlang::{Detector, LanguageModel, Lang, Options, Script};
+///
+/// // A model which always picks the first candidate.
+/// struct FirstCandidate;
+///
+/// impl LanguageModel for FirstCandidate {
+///     fn detect_lang(&self, _text: &str, _script: Script, candidates: &[Lang], _options: &Options) -> Option<(Lang, f64)> {
+///         candidates.first().map(|&lang| (lang, 0.5))
+///     }
+/// }
+///
+/// let detector = Detector::with_model(FirstCandidate, Options::new());
+/// let info = detector.detect("Привет").unwrap();
+/// assert_eq!(info.script(), Script::Cyrillic);
+/// ```
+pub trait LanguageModel {
+    /// Returns the most probable language among `candidates` with confidence
+    /// in the range from 0 to 1, or `None` if none of the candidates matches the text.
+    ///
+    /// `candidates` always contains at least 2 languages of the given `script`.
+    fn detect_lang(&self, text: &str, script: Script, candidates: &[Lang], options: &Options) -> Option<(Lang, f64)>;
+
+    /// The same as `detect_lang`, but buffers of `scratch` can be reused instead of allocating them,
+    /// see [Detector::detect_in](struct.Detector.html#method.detect_in). By default the scratch is not used.
+    fn detect_lang_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options, scratch: &mut Scratch) -> Option<(Lang, f64)> {
+        let _ = scratch;
+        self.detect_lang(text, script, candidates, options)
+    }
+
+    /// The same as `detect_lang_in`, but also returns the runner-up language with the margin
+    /// of the best score over its score, see [Info::second_best](struct.Info.html#method.second_best).
+    /// By default there is no runner-up.
+    fn detect_lang_with_second_best_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options,
+                                       scratch: &mut Scratch) -> Option<Ranking> {
+        self.detect_lang_in(text, script, candidates, options, scratch).map(|(lang, confidence)| (lang, confidence, None))
+    }
+
+    /// Languages of the script, which the model can choose from. Candidates are taken from them.
+    /// By default these are the languages, which have built-in profiles for the script.
+    fn script_langs(&self, script: Script, options: &Options) -> Vec<Lang> {
+        builtin_script_langs(script, options)
+    }
+
+    /// A custom language, which matches the text better than `lang` detected among the candidates,
+    /// with confidence, see [Info::custom_lang](struct.Info.html#method.custom_lang).
+    /// By default a model has no custom languages.
+    fn detect_custom_lang_in(&self, _text: &str, _script: Script, _lang: Lang, _options: &Options,
+                             _scratch: &mut Scratch) -> Option<(CustomLang, f64)> {
+        None
+    }
+}
+
+// A score of a language is increased by at most this fraction, when the language has prior weight 1.0.
+const PRIOR_WEIGHT: f64 = 0.05;
+
+// A score of a language is multiplied by 1 + MARKER_WEIGHT * (share of its marker words in the text).
+const MARKER_WEIGHT: f64 = 1.0;
+
+// Close languages, which trigrams tell apart well in longer texts (e.g. Spanish and Portuguese),
+// are resolved by their specific letters and words, when the best score exceeds the score
+// of the other one by less than this fraction.
+const CLOSE_SCORE_MARGIN: f64 = 0.2;
+
+// Number of the runners-up, which are passed to `resolve` of choose_lang with the best language.
+const RIVALS_NEAR_TOP: usize = 3;
+
+/// The default model, that compares trigrams of a text with the built-in
+/// trigram profiles of languages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrigramModel;
+
+impl LanguageModel for TrigramModel {
+    fn detect_lang(&self, text: &str, script: Script, candidates: &[Lang], options: &Options) -> Option<(Lang, f64)> {
+        self.detect_lang_in(text, script, candidates, options, &mut Scratch::new())
+    }
+
+    fn detect_lang_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options, scratch: &mut Scratch) -> Option<(Lang, f64)> {
+        self.detect_lang_with_second_best_in(text, script, candidates, options, scratch)
+            .map(|(lang, confidence, _)| (lang, confidence))
+    }
+
+    fn detect_lang_with_second_best_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options,
+                                       scratch: &mut Scratch) -> Option<Ranking> {
+        let trigrams = scratch.ngrams_with_positions(text, options.ngram_order.size());
+        let markers = count_marker_words(text, options);
+        detect_lang_by_trigrams(trigrams, &markers, script, candidates, options,
+                                |lang, rivals| resolve_close_langs(text, lang, rivals))
+    }
+}
+
+// Tells apart languages, which are too close for trigrams, by the letters and words of the text.
+// `rivals` are the runners-up near the top of trigram scores. Czech and Slovak are resolved first,
+// since other languages win them too, then Danish is told apart from Norwegian, so the standard
+// of Norwegian is resolved afterwards.
+pub(crate) fn resolve_close_langs(text: &str, lang: Lang, rivals: &[Lang]) -> Option<Lang> {
+    if let Some(lang) = resolve_czech_slovak(text, lang, rivals) {
+        return Some(lang);
+    }
+    let danish_norwegian = resolve_danish_norwegian(text, lang);
+    let lang = danish_norwegian.unwrap_or(lang);
+    resolve_serbo_croatian(text, lang)
+        .or_else(|| resolve_norwegian(text, lang))
+        .or_else(|| resolve_spanish_portuguese(text, lang))
+        .or_else(|| resolve_bulgarian_macedonian(text, lang))
+        .or_else(|| resolve_devanagari(text, lang))
+        .or_else(|| resolve_turkish_azerbaijani(text, lang))
+        .or_else(|| resolve_malay_indonesian(text, lang))
+        .or(danish_norwegian)
+}
+
+// Detect a language by positions of the most common trigrams of a text, boosted by marker words of the text.
+// Close languages (e.g. Serbian and Croatian) are told apart by `resolve`, see is_resolvable.
+pub(crate) fn detect_lang_by_trigrams<F>(trigrams: &FnvHashMap<Ngram, (u32, u32)>, markers: &MarkerWords, script: Script,
+                                         candidates: &[Lang], options: &Options, resolve: F) -> Option<Ranking>
+    where F: FnOnce(Lang, &[Lang]) -> Option<Lang>
+{
+    let tables: Vec<&NgramTable> = candidate_profile_lists(script, options).into_iter().map(ProfileListId::table).collect();
+    let langs: Vec<Lang> = tables.iter()
+        .flat_map(|table| table.langs.iter().cloned())
+        .filter(|lang| candidates.contains(lang))
+        .collect();
+    let is_known = |ngram| tables.iter().any(|table| table.get(ngram).any(|(index, _)| candidates.contains(&table.langs[index])));
+    detect_lang_by_scores(trigrams, &langs, script, options, is_known,
+                          |trigrams| score_by_tables(trigrams, markers, &tables, candidates, options), resolve)
+}
+
+// The same as `detect_lang_by_trigrams`, but compares trigrams with the given profiles
+// instead of the built-in ones.
+pub(crate) fn detect_lang_by_profiles<F>(trigrams: &FnvHashMap<Ngram, (u32, u32)>, markers: &MarkerWords, profiles: &[(Lang, &[&str])],
+                                         script: Script, candidates: &[Lang], options: &Options, resolve: F) -> Option<Ranking>
+    where F: FnOnce(Lang, &[Lang]) -> Option<Lang>
+{
+    let profiles: Vec<(Lang, &[&str])> = profiles.iter().filter(|&&(lang, _)| candidates.contains(&lang)).cloned().collect();
+    let langs: Vec<Lang> = profiles.iter().map(|&(lang, _)| lang).collect();
+    let vocabulary: FnvHashSet<Ngram> = profiles.iter()
+        .flat_map(|&(_, profile)| profile.iter().filter_map(|&ngram| Ngram::new(ngram)))
+        .collect();
+    detect_lang_by_scores(trigrams, &langs, script, options, |ngram| vocabulary.contains(&ngram),
+                          |trigrams| score_by_profiles(trigrams, markers, &profiles, options), resolve)
+}
+
+// `langs` are the candidates, which have profiles, `is_known` tells if an n-gram is in any of their profiles
+// and `score` gives their scores for n-grams of a text. Returns the best language with confidence
+// and the runner-up with the margin of the best score over its score. Close languages are resolved
+// only with Options::set_resolve_close_langs.
+fn detect_lang_by_scores<K, S, F>(trigrams: &FnvHashMap<Ngram, (u32, u32)>, langs: &[Lang], script: Script, options: &Options,
+                                  is_known: K, score: S, resolve: F) -> Option<Ranking>
+    where K: Fn(Ngram) -> bool,
+          S: Fn(&FnvHashMap<Ngram, (u32, u32)>) -> Vec<(Lang, f64)>,
+          F: FnOnce(Lang, &[Lang]) -> Option<Lang>
+{
+    enter_span!("score", ?script, trigrams = trigrams.len());
+    let resolve = |lang, rivals: &[Lang]| if options.resolve_close_langs { resolve(lang, rivals) } else { None };
+    let result = match options.unseen_trigram_penalty {
+        Some(penalty) => {
+            let (known, unseen_share) = remove_unseen_trigrams(trigrams, is_known);
+            choose_lang(score(&known), trigrams.len(), langs, script, resolve)
+                .map(|(lang, confidence, second_best)| (lang, confidence * (1.0 - penalty * unseen_share), second_best))
+        }
+        None => choose_lang(score(trigrams), trigrams.len(), langs, script, resolve)
+    };
+    if options.calibrated_confidence {
+        result.map(|(lang, confidence, second_best)| (lang, calibrate_confidence(confidence, trigrams.len()), second_best))
+    } else {
+        result
+    }
+}
+
+// Coefficients of the logistic regression, which maps raw confidence and the number of unique
+// trigrams of a text to the probability that the detected language is right. They are fitted
+// on all the prefixes (by words) of the texts in tests/examples.json, which are in scripts
+// with more than one language.
+const CALIBRATION_INTERCEPT: f64 = -1.225;
+const CALIBRATION_CONFIDENCE_WEIGHT: f64 = 4.392;
+const CALIBRATION_TRIGRAMS_WEIGHT: f64 = 0.105;
+
+fn calibrate_confidence(confidence: f64, trigram_count: usize) -> f64 {
+    let trigram_count = trigram_count.max(1) as f64;
+    let z = CALIBRATION_INTERCEPT
+        + CALIBRATION_CONFIDENCE_WEIGHT * confidence
+        + CALIBRATION_TRIGRAMS_WEIGHT * trigram_count.ln();
+    1.0 / (1.0 + (-z).exp())
+}
+
+// Remove trigrams, which are not in any of the profiles (e.g. from names, numbers or code),
+// so they do not shift positions of the known ones. Returns the known trigrams with new positions
+// and the share of occurrences of the unseen ones.
+fn remove_unseen_trigrams<K>(trigrams: &FnvHashMap<Ngram, (u32, u32)>, is_known: K) -> (FnvHashMap<Ngram, (u32, u32)>, f64)
+    where K: Fn(Ngram) -> bool
+{
+    let mut known: Vec<(Ngram, u32, u32)> = vec![];
+    let mut total_count = 0;
+    let mut unseen_count = 0;
+    for (&trigram, &(pos, count)) in trigrams {
+        total_count += count;
+        if is_known(trigram) {
+            known.push((trigram, pos, count));
+        } else {
+            unseen_count += count;
+        }
+    }
+    known.sort_by_key(|&(_, pos, _)| pos);
+
+    let known = known.into_iter()
+        .enumerate()
+        .map(|(i, (trigram, _, count))| (trigram, (i as u32, count)))
+        .collect();
+    let unseen_share = if total_count == 0 { 0.0 } else { unseen_count as f64 / total_count as f64 };
+    (known, unseen_share)
+}
+
+fn lang_weight(lang: Lang, markers: &MarkerWords, options: &Options) -> f64 {
+    (1.0 + PRIOR_WEIGHT * options.prior(lang)) * (1.0 + MARKER_WEIGHT * markers.share(lang, options))
+}
+
+// Scores of the profiles, which must be already filtered by candidates. Every profile is scored
+// exactly, the same as by score_by_tables.
+fn score_by_profiles(trigrams: &FnvHashMap<Ngram, (u32, u32)>, markers: &MarkerWords,
+                     profiles: &[(Lang, &[&str])], options: &Options) -> Vec<(Lang, f64)> {
+    profiles.iter()
+        .map(|&(lang, profile)| (lang, profile_similarity(trigrams, profile, options) * lang_weight(lang, markers, options)))
+        .collect()
+}
+
+// Scores of the candidates with built-in profiles. Instead of looking up every n-gram of every profile
+// among n-grams of the text, every n-gram of the text is looked up once in the tables of the profiles.
+fn score_by_tables(trigrams: &FnvHashMap<Ngram, (u32, u32)>, markers: &MarkerWords,
+                   tables: &[&NgramTable], candidates: &[Lang], options: &Options) -> Vec<(Lang, f64)> {
+    let metric = options.scoring_metric;
+    let text_total = text_total(metric, trigrams);
+    let mut lang_scores : Vec<(Lang, f64)> = vec![];
+    for table in tables {
+        // Numbers of the matched n-grams and sums of their terms for every profile
+        let mut matches = vec![(0, 0.0); table.langs.len()];
+        for (&trigram, &(pos, count)) in trigrams {
+            for (index, rank) in table.get(trigram) {
+                let (ref mut matched, ref mut sum) = matches[index];
+                *matched += 1;
+                *sum += match_term(metric, rank, table.sizes[index] as usize, pos, count);
+            }
+        }
+
+        for (index, &lang) in table.langs.iter().enumerate() {
+            if !candidates.contains(&lang) {
+                continue;
+            }
+            let (matched, sum) = matches[index];
+            let profile_size = table.sizes[index] as usize;
+            let similarity = match metric {
+                ScoringMetric::OutOfPlace => {
+                    // The same as calculate_distance, where every missing n-gram is at the max distance
+                    let missing = LANG_PROFILE_SIZE.max(profile_size) - matched;
+                    let dist = missing as u32 * MAX_TRIGRAM_DISTANCE + sum as u32;
+                    MAX_TOTAL_DISTANCE.saturating_sub(dist) as f64
+                }
+                metric => similarity_by_sum(metric, sum, profile_size, text_total) * MAX_TOTAL_DISTANCE as f64
+            };
+            lang_scores.push((lang, similarity * lang_weight(lang, markers, options)));
+        }
+    }
+    lang_scores
+}
+
+// `trigram_count` is the number of unique trigrams of the text, including the removed ones.
+// `langs` are the candidates, which have profiles.
+fn choose_lang<F>(mut lang_scores: Vec<(Lang, f64)>, trigram_count: usize, langs: &[Lang], script: Script,
+                  resolve: F) -> Option<Ranking>
+    where F: FnOnce(Lang, &[Lang]) -> Option<Lang>
+{
+    // Sort languages by score in descending order
+    lang_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    // Latin Serbian and Croatian (as well as Malay and Indonesian) are too close to be told apart
+    // by trigrams, so they compete with other languages as one, and then are resolved by the words
+    // specific for each of them. Other close languages are resolved by `resolve` in the same way,
+    // see is_resolvable.
+    if script == Script::Latin {
+        merge_close_langs(&mut lang_scores, is_serbo_croatian);
+        merge_close_langs(&mut lang_scores, is_malay_indonesian);
+    }
+    if let Some(first) = lang_scores.first().map(|pair| pair.0) {
+        if is_resolvable(first, script, &lang_scores) {
+            let rivals: Vec<Lang> = lang_scores.iter().skip(1).take(RIVALS_NEAR_TOP).map(|pair| pair.0).collect();
+            match resolve(first, &rivals) {
+                Some(lang) if langs.contains(&lang) => promote_lang(&mut lang_scores, lang),
+                _ => {}
+            }
+        }
+    }
+
+    // Return None if lang_scores is empty
+    // Return the only language with is_reliable=true if there is only 1 item
+    if lang_scores.len() < 2 {
+        return lang_scores.first().map(|pair| (pair.0, 1.0, None));
+    }
+
+    // Calculate is_reliable based on:
+    // - number of unique trigrams in the text
+    // - rate (diff between score of the first and second languages)
+    //
+    let (lang1, score1) = lang_scores[0];
+    let (lang2, score2) = lang_scores[1];
+    // A resolved language takes the score of the best one, so the scores stay sorted
+    let second_best = if score1 > 0.0 { Some((lang2, (score1 - score2) / score1)) } else { None };
+    debug_event!(lang = ?lang1, score = score1, runner_up = ?lang_scores[1].0, runner_up_score = score2, "chose language");
+
+    let confidence = scores_confidence(score1, score2, trigram_count)?;
+    // Kazakh, Kyrgyz and Tatar share most letters with Russian, so random Cyrillic text matches
+    // a few trigrams of one of them and the runner-up is not at 0, see the score2 == 0 case below.
+    // Their best score is still low, so it limits confidence in the same way.
+    let confidence = if script == Script::Cyrillic { confidence.min(score1 / 500.0) } else { confidence };
+    Some((lang1, confidence, second_best))
+}
+
+// Confidence of the language with the best score by the score of the runner-up,
+// or None if the best score is 0.
+fn scores_confidence(score1: f64, score2: f64, trigram_count: usize) -> Option<f64> {
+    if score1 == 0.0 {
+        // If score1 is 0, score2 is 0 as well, because array is sorted.
+        // Therefore there is no language to return.
+        return None;
+    } else if score2 == 0.0 {
+        // If score2 is 0, return first language, to prevent division by zero in the rate formula.
+        // In this case confidence is calculated by another formula.
+        // At this point there are two options:
+        // * Text contains random characters that accidentally match trigrams of one of the languages
+        // * Text really matches one of the languages.
+        //
+        // Number 500.0 is based on experiments and common sense expectations.
+        let mut confidence = score1 / 500.0;
+        if confidence > 1.0 {
+            confidence = 1.0;
+        }
+        return Some(confidence);
+    }
+
+    let rate = (score1 - score2) / score2;
+
+    // Hyperbola function. Everything that is above the function has confidence = 1.0
+    // If rate is below, confidence is calculated proportionally.
+    // Numbers 12.0 and 0.05 are obtained experimentally, so the function represents common sense.
+    //
+    let confident_rate = (12.0 / trigram_count as f64) + 0.05;
+    let confidence =
+        if rate > confident_rate {
+            1.0
+        } else {
+            rate / confident_rate
+        };
+
+    Some(confidence)
+}
+
+// Choose the custom language with the best profile, if it matches n-grams of a text better than
+// the profile of the language detected among the built-in ones (if it has one). Confidence is
+// calculated by the best of the other scores. Priors and marker words are set only for built-in
+// languages, so neither score is weighted by them.
+pub(crate) fn detect_custom_lang(trigrams: &FnvHashMap<Ngram, (u32, u32)>, lang_profile: Option<&[&str]>,
+                                 custom_profiles: &[(CustomLang, &[&str])], options: &Options) -> Option<(CustomLang, f64)> {
+    let mut scores: Vec<(CustomLang, f64)> = custom_profiles.iter()
+        .map(|&(lang, profile)| (lang, profile_similarity(trigrams, profile, options)))
+        .collect();
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+    let &(custom_lang, score1) = scores.first()?;
+    let lang_score = lang_profile.map_or(0.0, |profile| profile_similarity(trigrams, profile, options));
+    if score1 <= lang_score {
+        return None;
+    }
+    let score2 = scores.get(1).map_or(lang_score, |&(_, score)| score.max(lang_score));
+    debug_event!(?custom_lang, score = score1, runner_up_score = score2, "chose custom language");
+    scores_confidence(score1, score2, trigrams.len()).map(|confidence| (custom_lang, confidence))
+}
+
+// Unweighted score of a profile in the same scale as in score_by_profiles.
+fn profile_similarity(trigrams: &FnvHashMap<Ngram, (u32, u32)>, profile: &[&str], options: &Options) -> f64 {
+    match options.scoring_metric {
+        ScoringMetric::OutOfPlace => MAX_TOTAL_DISTANCE.saturating_sub(calculate_distance(profile, trigrams)) as f64,
+        metric => calculate_similarity(metric, profile, trigrams) * MAX_TOTAL_DISTANCE as f64
+    }
+}
+
+// Trigram (or n-gram of the given order) profiles of the languages, that share the given script.
+// Scripts which are used only by one language have no profiles.
+pub(crate) fn script_profiles(script: Script, order: NgramOrder) -> LangProfileList {
+    script_profile_list(script, order).map_or(&[], ProfileListId::profiles)
+}
+
+fn script_profile_list(script: Script, order: NgramOrder) -> Option<ProfileListId> {
+    let list = match (script, order) {
+        (Script::Latin, NgramOrder::Trigrams)      => LATIN_LANGS,
+        (Script::Latin, NgramOrder::Bigrams)       => LATIN_LANGS_BIGRAMS,
+        (Script::Latin, NgramOrder::Fourgrams)     => LATIN_LANGS_FOURGRAMS,
+        (Script::Cyrillic, NgramOrder::Trigrams)   => CYRILLIC_LANGS,
+        (Script::Cyrillic, NgramOrder::Bigrams)    => CYRILLIC_LANGS_BIGRAMS,
+        (Script::Cyrillic, NgramOrder::Fourgrams)  => CYRILLIC_LANGS_FOURGRAMS,
+        (Script::Devanagari, NgramOrder::Trigrams) => DEVANAGARI_LANGS,
+        (Script::Devanagari, NgramOrder::Bigrams)  => DEVANAGARI_LANGS_BIGRAMS,
+        (Script::Devanagari, NgramOrder::Fourgrams) => DEVANAGARI_LANGS_FOURGRAMS,
+        (Script::Hebrew, NgramOrder::Trigrams)     => HEBREW_LANGS,
+        (Script::Hebrew, NgramOrder::Bigrams)      => HEBREW_LANGS_BIGRAMS,
+        (Script::Hebrew, NgramOrder::Fourgrams)    => HEBREW_LANGS_FOURGRAMS,
+        (Script::Ethiopic, NgramOrder::Trigrams)   => ETHIOPIC_LANGS,
+        (Script::Ethiopic, NgramOrder::Bigrams)    => ETHIOPIC_LANGS_BIGRAMS,
+        (Script::Ethiopic, NgramOrder::Fourgrams)  => ETHIOPIC_LANGS_FOURGRAMS,
+        (Script::Arabic, NgramOrder::Trigrams)     => ARABIC_LANGS,
+        (Script::Arabic, NgramOrder::Bigrams)      => ARABIC_LANGS_BIGRAMS,
+        (Script::Arabic, NgramOrder::Fourgrams)    => ARABIC_LANGS_FOURGRAMS,
+        _ => return None
+    };
+    Some(list)
+}
+
+// Profiles of languages written in Latin script instead of their native one.
+pub(crate) fn romanized_profiles(order: NgramOrder) -> LangProfileList {
+    romanized_profile_list(order).profiles()
+}
+
+fn romanized_profile_list(order: NgramOrder) -> ProfileListId {
+    match order {
+        NgramOrder::Trigrams => ROMANIZED_LANGS,
+        NgramOrder::Bigrams => ROMANIZED_LANGS_BIGRAMS,
+        NgramOrder::Fourgrams => ROMANIZED_LANGS_FOURGRAMS
+    }
+}
+
+// Lists of profiles of the script, followed by romanized profiles if they are enabled for Latin script.
+fn candidate_profile_lists(script: Script, options: &Options) -> Vec<ProfileListId> {
+    let mut lists: Vec<ProfileListId> = script_profile_list(script, options.ngram_order).into_iter().collect();
+    if script == Script::Latin && options.romanized {
+        lists.push(romanized_profile_list(options.ngram_order));
+    }
+    lists
+}
+
+pub(crate) fn candidate_profiles(script: Script, options: &Options) -> impl Iterator<Item = &'static (Lang, LangProfile)> {
+    candidate_profile_lists(script, options).into_iter().flat_map(|list| list.profiles().iter())
+}
+
+pub(crate) fn builtin_script_langs(script: Script, options: &Options) -> Vec<Lang> {
+    candidate_profiles(script, options).map(|&(lang, _)| lang).collect()
+}
+
+// Similarity of a text to the trigram profile of the language, from 0 to 1.
+// Returns None if the language has no profile for the script.
+pub(crate) fn trigram_similarity(text: &str, script: Script, lang: Lang, options: &Options) -> Option<f64> {
+    let &(_, lang_trigrams) = candidate_profiles(script, options).find(|&&(profile_lang, _)| profile_lang == lang)?;
+    let trigrams = get_ngrams_with_positions(text, options.ngram_order.size());
+    match options.scoring_metric {
+        ScoringMetric::OutOfPlace => {
+            let dist = calculate_distance(lang_trigrams, &trigrams);
+            Some(MAX_TOTAL_DISTANCE.saturating_sub(dist) as f64 / MAX_TOTAL_DISTANCE as f64)
+        }
+        metric => Some(calculate_similarity(metric, lang_trigrams, &trigrams))
+    }
+}
+
+// Whether the best language is told apart from a close one by `resolve` of choose_lang.
+// Bokmål and Nynorsk are two standards of the same language, so they are resolved whenever one
+// of them wins (as well as merged Serbian and Croatian, Malay and Indonesian), and then Norwegian
+// may be resolved to Danish too.
+// Danish, Turkish and Azerbaijani, Bulgarian and Macedonian are often confused by trigrams
+// with the close languages, so they are resolved when both of them are near the top.
+// Short Czech and Slovak texts are often won by other Slavic languages too, so any language
+// is resolved, when one of them is near the top (by their letters only, see resolve_czech_slovak).
+// Spanish and Portuguese are resolved only when their scores are close, since longer texts of them
+// are told apart by trigrams well. Devanagari languages are resolved whenever one of them wins,
+// since trigrams tend to choose Hindi or Bhojpuri for all of them.
+fn is_resolvable(first: Lang, script: Script, lang_scores: &[(Lang, f64)]) -> bool {
+    let is_near_top = |rival: Lang, rank: usize| lang_scores.iter().take(rank).any(|pair| pair.0 == rival);
+    let is_close = |rival: Lang| lang_scores.iter().find(|pair| pair.0 == rival)
+        .map_or(false, |pair| lang_scores[0].1 - pair.1 < CLOSE_SCORE_MARGIN * pair.1);
+    let is_czech_slovak_near_top = is_near_top(Lang::Ces, RIVALS_NEAR_TOP + 1) || is_near_top(Lang::Slk, RIVALS_NEAR_TOP + 1);
+    match (script, first) {
+        (Script::Latin, _) if is_czech_slovak_near_top => true,
+        (Script::Latin, Lang::Srp) | (Script::Latin, Lang::Hrv) |
+        (Script::Latin, Lang::Nob) | (Script::Latin, Lang::Nno) |
+        (Script::Latin, Lang::Zlm) | (Script::Latin, Lang::Ind) => true,
+        (Script::Latin, Lang::Dan) => is_near_top(Lang::Nob, 3) || is_near_top(Lang::Nno, 3),
+        (Script::Latin, Lang::Tur) => is_near_top(Lang::Azj, 3),
+        (Script::Latin, Lang::Azj) => is_near_top(Lang::Tur, 3),
+        (Script::Latin, Lang::Spa) => is_close(Lang::Por),
+        (Script::Latin, Lang::Por) => is_close(Lang::Spa),
+        (Script::Cyrillic, Lang::Bul) => is_near_top(Lang::Mkd, 2),
+        (Script::Cyrillic, Lang::Mkd) => is_near_top(Lang::Bul, 2),
+        (Script::Devanagari, _) => true,
+        _ => false
+    }
+}
+
+// The language gets the best score, and the language which had it takes the former place of the language.
+fn promote_lang(lang_scores: &mut [(Lang, f64)], lang: Lang) {
+    let first = lang_scores[0].0;
+    if let Some(pair) = lang_scores.iter_mut().find(|pair| pair.0 == lang) {
+        pair.0 = first;
+    }
+    lang_scores[0].0 = lang;
+}
+
+// Keep only the best scored of the close languages (e.g. Serbian and Croatian), `lang_scores` must be sorted.
+fn merge_close_langs(lang_scores: &mut Vec<(Lang, f64)>, is_close: fn(Lang) -> bool) {
+    let mut found = false;
+    lang_scores.retain(|&(lang, _)| {
+        if !is_close(lang) {
+            return true;
+        }
+        let keep = !found;
+        found = true;
+        keep
+    });
+}
+
+// Derived profiles may be shorter than trigram ones, their missing n-grams are counted as not found.
+pub(crate) fn calculate_distance(lang_trigrams: &[&str],  text_trigrams: &FnvHashMap<Ngram, (u32, u32)>) -> u32 {
+    let mut total_dist = LANG_PROFILE_SIZE.saturating_sub(lang_trigrams.len()) as u32 * MAX_TRIGRAM_DISTANCE;
+
+    for (i, &trigram) in lang_trigrams.iter().enumerate() {
+        let dist = match find_ngram(text_trigrams, trigram) {
+            Some((n, _)) => (n as i32 - i as i32).abs() as u32,
+            None => MAX_TRIGRAM_DISTANCE
+        };
+        total_dist += dist;
+    }
+    total_dist
+}
+
+// Similarity of trigrams of a text to the profile by the given metric (except OutOfPlace), from 0 to 1.
+// Trigrams weigh by their ranks: the first one of the text weighs TEXT_TRIGRAMS_SIZE,
+// the first one of the profile weighs the size of the profile.
+fn calculate_similarity(metric: ScoringMetric, lang_trigrams: &[&str], text_trigrams: &FnvHashMap<Ngram, (u32, u32)>) -> f64 {
+    let mut sum = 0.0;
+    for (i, &trigram) in lang_trigrams.iter().enumerate() {
+        if let Some((pos, count)) = find_ngram(text_trigrams, trigram) {
+            sum += match_term(metric, i, lang_trigrams.len(), pos, count);
+        }
+    }
+    similarity_by_sum(metric, sum, lang_trigrams.len(), text_total(metric, text_trigrams))
+}
+
+fn text_weight(pos: u32) -> f64 {
+    (TEXT_TRIGRAMS_SIZE as u32 - pos) as f64
+}
+
+// A term of the sum over trigrams of a profile, which are found in a text:
+// `rank` is the position of the trigram in the profile, `pos` and `count` are the ones in the text.
+// For OutOfPlace it's the distance between the positions.
+fn match_term(metric: ScoringMetric, rank: usize, profile_size: usize, pos: u32, count: u32) -> f64 {
+    let profile_weight = profile_size as f64 - rank as f64;
+    match metric {
+        ScoringMetric::OutOfPlace => (pos as i32 - rank as i32).abs() as f64,
+        ScoringMetric::Cosine => text_weight(pos) * profile_weight,
+        ScoringMetric::LogProbability => text_weight(pos) * (2.0 * profile_weight).ln(),
+        ScoringMetric::CountWeighted => count as f64 * profile_weight
+    }
+}
+
+// The part of a similarity, which depends only on the text.
+fn text_total(metric: ScoringMetric, text_trigrams: &FnvHashMap<Ngram, (u32, u32)>) -> f64 {
+    match metric {
+        ScoringMetric::OutOfPlace => 0.0,
+        ScoringMetric::Cosine => text_trigrams.values().map(|&(pos, _)| text_weight(pos).powi(2)).sum::<f64>().sqrt(),
+        ScoringMetric::LogProbability => text_trigrams.values().map(|&(pos, _)| text_weight(pos)).sum(),
+        ScoringMetric::CountWeighted => text_trigrams.values().map(|&(_, count)| count as f64).sum()
+    }
+}
+
+// Similarity (except OutOfPlace) from the sum of terms of the matched trigrams.
+fn similarity_by_sum(metric: ScoringMetric, sum: f64, profile_size: usize, text_total: f64) -> f64 {
+    let size = profile_size as f64;
+    match metric {
+        ScoringMetric::Cosine => {
+            let profile_norm: f64 = (0..profile_size).map(|i| (size - i as f64).powi(2)).sum::<f64>().sqrt();
+            if text_total == 0.0 || profile_norm == 0.0 {
+                0.0
+            } else {
+                sum / (text_total * profile_norm)
+            }
+        }
+        // Probability of a trigram of the profile is proportional to its weight, a missing trigram
+        // is smoothed to half of the weight of the last one. The average log-probability of trigrams
+        // of the text is scaled so the worst one (nothing matched) is 0 and the best one is 1.
+        ScoringMetric::LogProbability => {
+            if text_total == 0.0 || profile_size == 0 {
+                0.0
+            } else {
+                sum / (text_total * (2.0 * size).ln())
+            }
+        }
+        ScoringMetric::CountWeighted => {
+            if text_total == 0.0 || profile_size == 0 {
+                0.0
+            } else {
+                sum / (text_total * size)
+            }
+        }
+        ScoringMetric::OutOfPlace => unreachable!("Out-of-place distance is calculated by calculate_distance")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigram_model_respects_candidates() {
+        let text = "Та нічого, все нормально. А в тебе як?";
+        let model = TrigramModel;
+        let options = Options::new();
+        let (lang, _) = model.detect_lang(text, Script::Cyrillic, &[Lang::Ukr, Lang::Rus], &options).unwrap();
+        assert_eq!(lang, Lang::Ukr);
+
+        let (lang, _) = model.detect_lang(text, Script::Cyrillic, &[Lang::Rus, Lang::Bel], &options).unwrap();
+        assert_ne!(lang, Lang::Ukr);
+    }
+
+    #[test]
+    fn test_choose_lang_limits_confidence_of_cyrillic() {
+        // Scores of random Cyrillic text, only the Cyrillic confidence is limited by the best one
+        let ranking = choose_lang(vec![(Lang::Ukr, 228.0), (Lang::Kir, 25.0)], 300, &[Lang::Ukr, Lang::Kir],
+                                  Script::Cyrillic, |_, _| None);
+        assert_eq!(ranking.map(|(lang, confidence, _)| (lang, confidence)), Some((Lang::Ukr, 228.0 / 500.0)));
+
+        let ranking = choose_lang(vec![(Lang::Deu, 228.0), (Lang::Eng, 25.0)], 300, &[Lang::Deu, Lang::Eng],
+                                  Script::Latin, |_, _| None);
+        assert_eq!(ranking.map(|(lang, confidence, _)| (lang, confidence)), Some((Lang::Deu, 1.0)));
+    }
+
+    #[test]
+    fn test_calculate_distance() {
+        let trigrams = get_ngrams_with_positions("Привет, мир!", 3);
+        let profile = script_profiles(Script::Cyrillic, NgramOrder::Trigrams)[0].1;
+        let dist = calculate_distance(profile, &trigrams);
+        assert!(dist > 0 && dist < MAX_TOTAL_DISTANCE);
+        assert_eq!(calculate_distance(profile, &get_ngrams_with_positions("", 3)), MAX_TOTAL_DISTANCE);
+    }
+
+    #[test]
+    fn test_trigram_model_with_ngram_orders() {
+        let texts = [
+            ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", Lang::Deu),
+            ("Le véritable été arrive à la forêt avec les enfants", Lang::Fra),
+            ("Suomen kieli on uralilainen kieli, jota puhuu äidinkielenään noin viisi miljoonaa ihmistä", Lang::Fin),
+        ];
+        let candidates = profile_langs(Script::Latin);
+        for &order in [NgramOrder::Bigrams, NgramOrder::Trigrams, NgramOrder::Fourgrams].iter() {
+            let options = Options::new().set_ngram_order(order);
+            for &(text, expected) in texts.iter() {
+                let (lang, _) = TrigramModel.detect_lang(text, Script::Latin, candidates, &options).unwrap();
+                assert_eq!(lang, expected, "{:?}: {}", order, text);
+            }
+            assert_eq!(script_profiles(Script::Cyrillic, order).len(), profile_langs(Script::Cyrillic).len());
+        }
+    }
+
+    #[test]
+    fn test_trigram_model_with_scoring_metrics() {
+        let texts = [
+            ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", Script::Latin, Lang::Deu),
+            ("Hayvanlar ve bitkiler dünyanın her yerinde yaşamaktadır", Script::Latin, Lang::Tur),
+            ("Та нічого, все нормально. А в тебе як?", Script::Cyrillic, Lang::Ukr),
+        ];
+        let metrics = [ScoringMetric::OutOfPlace, ScoringMetric::Cosine, ScoringMetric::LogProbability, ScoringMetric::CountWeighted];
+        for &metric in metrics.iter() {
+            let options = Options::new().set_scoring_metric(metric);
+            for &(text, script, expected) in texts.iter() {
+                let (lang, _) = TrigramModel.detect_lang(text, script, profile_langs(script), &options).unwrap();
+                assert_eq!(lang, expected, "{:?}: {}", metric, text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_calculate_similarity() {
+        let profile = script_profiles(Script::Latin, NgramOrder::Trigrams)[0].1;
+        let same: FnvHashMap<Ngram, (u32, u32)> = profile.iter().enumerate()
+            .map(|(i, &trigram)| (Ngram::new(trigram).unwrap(), (i as u32, 1)))
+            .collect();
+        let other = get_ngrams_with_positions("Привет, мир!", 3);
+        for &metric in [ScoringMetric::Cosine, ScoringMetric::LogProbability, ScoringMetric::CountWeighted].iter() {
+            let similarity = calculate_similarity(metric, profile, &same);
+            assert!((0.5..=1.0).contains(&similarity), "{:?}: {}", metric, similarity);
+            assert_eq!(calculate_similarity(metric, profile, &other), 0.0);
+            assert_eq!(calculate_similarity(metric, profile, &FnvHashMap::default()), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_remove_unseen_trigrams() {
+        let trigrams = get_ngrams_with_positions("the qqq the xzx", 3);
+        let table = LATIN_LANGS.table();
+        let (known, unseen_share) = remove_unseen_trigrams(&trigrams, |ngram| table.get(ngram).next().is_some());
+        let contains = |trigram| find_ngram(&known, trigram).is_some();
+        assert!(contains(" th") && contains("the") && contains("he "));
+        assert!(!contains("qqq") && !contains("xzx"));
+
+        let mut positions: Vec<u32> = known.values().map(|&(pos, _)| pos).collect();
+        positions.sort();
+        assert_eq!(positions, (0..known.len() as u32).collect::<Vec<_>>());
+        assert!(unseen_share > 0.0 && unseen_share < 1.0);
+    }
+
+    #[test]
+    fn test_score_by_tables_matches_profiles() {
+        let texts = [
+            ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. XQZ QWXZ", Script::Latin),
+            ("Та нічого, все нормально. А в тебе як?", Script::Cyrillic),
+            ("Privet, kak dela? Vse horosho, spasibo", Script::Latin),
+        ];
+        let metrics = [ScoringMetric::OutOfPlace, ScoringMetric::Cosine, ScoringMetric::LogProbability, ScoringMetric::CountWeighted];
+        for &metric in metrics.iter() {
+            let options = Options::new().set_scoring_metric(metric).set_romanized(true).set_unseen_trigram_penalty(0.5);
+            for &(text, script) in texts.iter() {
+                let trigrams = get_ngrams_with_positions(text, 3);
+                let markers = MarkerWords::default();
+                let candidates = builtin_script_langs(script, &options);
+                let profiles: Vec<(Lang, &[&str])> = candidate_profiles(script, &options).map(|&(lang, profile)| (lang, profile)).collect();
+                let (lang1, confidence1, _) = detect_lang_by_trigrams(&trigrams, &markers, script, &candidates, &options, |_, _| None).unwrap();
+                let (lang2, confidence2, _) = detect_lang_by_profiles(&trigrams, &markers, &profiles, script, &candidates, &options, |_, _| None).unwrap();
+                assert_eq!(lang1, lang2, "{:?}: {}", metric, text);
+                assert!((confidence1 - confidence2).abs() < 1e-9, "{:?}: {}", metric, text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trigram_model_with_unseen_trigram_penalty() {
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. XQZ-8841-KJW QWXZ";
+        let candidates = profile_langs(Script::Latin);
+        let (lang, confidence) = TrigramModel.detect_lang(text, Script::Latin, candidates, &Options::new()).unwrap();
+        assert_eq!(lang, Lang::Deu);
+
+        let options = Options::new().set_unseen_trigram_penalty(1.0);
+        let (lang, penalized) = TrigramModel.detect_lang(text, Script::Latin, candidates, &options).unwrap();
+        assert_eq!(lang, Lang::Deu);
+        assert!(penalized < confidence);
+
+        // The penalty is clamped, so confidence never gets negative
+        let options = Options::new().set_unseen_trigram_penalty(10.0);
+        assert_eq!(TrigramModel.detect_lang(text, Script::Latin, candidates, &options), Some((lang, penalized)));
+    }
+
+    #[test]
+    fn test_calibrate_confidence() {
+        assert!(calibrate_confidence(0.0, 1) > 0.2 && calibrate_confidence(0.0, 1) < 0.3);
+        assert!(calibrate_confidence(1.0, 100) > 0.95 && calibrate_confidence(1.0, 100) < 1.0);
+        assert!(calibrate_confidence(0.5, 10) < calibrate_confidence(0.5, 100));
+        assert!(calibrate_confidence(0.3, 50) < calibrate_confidence(0.6, 50));
+    }
+
+    #[test]
+    fn test_trigram_model_with_calibrated_confidence() {
+        let text = "Jeg har en hund og en katt";
+        let candidates = [Lang::Nob, Lang::Dan];
+        let (lang, raw) = TrigramModel.detect_lang(text, Script::Latin, &candidates, &Options::new()).unwrap();
+
+        let options = Options::new().set_calibrated_confidence(true);
+        let (calibrated_lang, calibrated) = TrigramModel.detect_lang(text, Script::Latin, &candidates, &options).unwrap();
+        assert_eq!(calibrated_lang, lang);
+        assert_eq!(calibrated, calibrate_confidence(raw, get_ngrams_with_positions(text, 3).len()));
+    }
+
+    #[test]
+    fn test_trigram_model_with_serbian_and_croatian() {
+        let model = TrigramModel;
+        let options = Options::new();
+        let candidates = [Lang::Srp, Lang::Hrv, Lang::Slv, Lang::Eng];
+
+        let text = "Juče sam bio u prodavnici i kupio hleb, mleko i malo voća. Posle toga smo otišli kod prijatelja.";
+        let (lang, _) = model.detect_lang(text, Script::Latin, &candidates, &options).unwrap();
+        assert_eq!(lang, Lang::Srp);
+        let (lang, _) = model.detect_lang(text, Script::Latin, &[Lang::Hrv, Lang::Slv], &options).unwrap();
+        assert_eq!(lang, Lang::Hrv);
+
+        let text = "Zagreb je glavni i najveći grad Republike Hrvatske. Smješten je na rijeci Savi.";
+        let (lang, _) = model.detect_lang(text, Script::Latin, &candidates, &options).unwrap();
+        assert_eq!(lang, Lang::Hrv);
+
+        // Serbian and Croatian are merged into one candidate
+        let (lang, confidence) = model.detect_lang(text, Script::Latin, &[Lang::Srp, Lang::Hrv], &options).unwrap();
+        assert_eq!(lang, Lang::Hrv);
+        assert_eq!(confidence, 1.0);
+    }
+
+    #[test]
+    fn test_trigram_model_with_priors() {
+        // Borderline case: Bokmål and Danish are very close
+        let text = "Jeg har en hund og en katt";
+        let model = TrigramModel;
+        let candidates = [Lang::Nob, Lang::Dan];
+
+        let (lang, _) = model.detect_lang(text, Script::Latin, &candidates, &Options::new()).unwrap();
+        let other = if lang == Lang::Nob { Lang::Dan } else { Lang::Nob };
+
+        let options = Options::new().set_priors(vec![(other, 1.0)]);
+        let (lang, _) = model.detect_lang(text, Script::Latin, &candidates, &options).unwrap();
+        assert_eq!(lang, other);
+
+        // Priors do not override a clear result
+        let text = "Та нічого, все нормально. А в тебе як?";
+        let options = Options::new().set_priors(vec![(Lang::Rus, 1.0)]);
+        let (lang, _) = model.detect_lang(text, Script::Cyrillic, &[Lang::Ukr, Lang::Rus], &options).unwrap();
+        assert_eq!(lang, Lang::Ukr);
+
+        // Invalid weights are skipped
+        let options = Options::new().set_priors(vec![(Lang::Rus, ::std::f64::NAN), (Lang::Ukr, 2.0), (Lang::Ukr, -1.0)]);
+        assert_eq!(options, Options::new());
+        assert_eq!(model.detect_lang(text, Script::Cyrillic, &[Lang::Ukr, Lang::Rus], &options).unwrap().0, Lang::Ukr);
+    }
+}
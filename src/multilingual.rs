@@ -0,0 +1,413 @@
+use std::cmp::Reverse;
+use std::ops::Range;
+
+use lang::Lang;
+use script::Script;
+use options::Options;
+use detect::detect_with_options;
+use model::trigram_similarity;
+use runs::script_runs;
+use sentences::detect_sentences_with_options;
+
+// Number of words in a window, which is detected to find languages of the words in it.
+const SPAN_WINDOW_WORDS: usize = 8;
+
+// Windows overlap, so every word gets votes from several windows.
+const SPAN_WINDOW_STEP: usize = 2;
+
+// Shorter runs of words with another language are rather misdetections than code-switching,
+// so they are merged into the neighbouring span.
+const MIN_SPAN_WORDS: usize = 3;
+
+// Vote of a window, detected with zero confidence.
+const MIN_VOTE: f64 = 0.01;
+
+// Languages are rather switched between clauses, so a boundary after punctuation is preferred.
+const PUNCTUATION_BONUS: f64 = 0.1;
+
+/// Detect languages of a multilingual text with their proportions.
+///
+/// The text is split into [sentences](fn.sentences.html), a language of every sentence
+/// is detected, and the languages are weighted by the number of chars in their sentences.
+/// Proportions are in the range from 0 to 1 and add up to 1, the most common language goes first.
+/// Sentences without a detected language are not taken into account.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_proportions, Lang};
+///
+/// let text = "There is no reason not to learn Esperanto. \
+///             Введите запрос, чтобы найти нужную информацию.";
+/// let langs: Vec<Lang> = detect_proportions(text).into_iter().map(|(lang, _)| lang).collect();
+/// assert_eq!(langs, vec![Lang::Rus, Lang::Eng]);
+/// ```
+pub fn detect_proportions(text: &str) -> Vec<(Lang, f64)> {
+    detect_proportions_with_options(text, &Options::default())
+}
+
+/// Detect languages of a multilingual text with their proportions, using the given options.
+///
+/// See [`detect_proportions`](fn.detect_proportions.html) for details.
+pub fn detect_proportions_with_options(text: &str, options: &Options) -> Vec<(Lang, f64)> {
+    let mut sizes: Vec<(Lang, usize)> = vec![];
+    for (range, info) in detect_sentences_with_options(text, options) {
+        let info = match info {
+            Some(info) => info,
+            None => continue
+        };
+        let size = text[range].chars().count();
+        match sizes.iter_mut().find(|&&mut (lang, _)| lang == info.lang()) {
+            Some(pair) => pair.1 += size,
+            None => sizes.push((info.lang(), size))
+        }
+    }
+
+    // Sort is stable, so ties keep the order of the first occurrence in the text
+    sizes.sort_by_key(|&(_, size)| Reverse(size));
+    let total: usize = sizes.iter().map(|&(_, size)| size).sum();
+    sizes.into_iter()
+        .map(|(lang, size)| (lang, size as f64 / total as f64))
+        .collect()
+}
+
+/// Split a text into spans of different languages, e.g. to find where a text switches
+/// from one language to another. Spans are returned as byte ranges in the order of the text.
+///
+/// The text is split into runs of the same script, within a run every word is labeled
+/// by windows of several words, which contain it. Runs of words shorter than 3 words are
+/// merged into the neighbouring spans, and boundaries between spans are moved to the
+/// positions, where the languages are told apart best. Words without a detected language
+/// (e.g. numbers) belong to the preceding span. Short texts are detected less reliably,
+/// so spans of a few words may be labeled wrongly.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_spans, Lang};
+///
+/// let text = "Ayer fuimos al mercado y compramos muchas frutas frescas para la semana, \
+///             but then it started raining and we had to go back home quickly.";
+/// let spans: Vec<_> = detect_spans(text).into_iter().map(|(range, lang)| (&text[range], lang)).collect();
+/// assert_eq!(spans, vec![
+///     ("Ayer fuimos al mercado y compramos muchas frutas frescas para la semana,", Lang::Spa),
+///     ("but then it started raining and we had to go back home quickly.", Lang::Eng),
+/// ]);
+/// ```
+pub fn detect_spans(text: &str) -> Vec<(Range<usize>, Lang)> {
+    detect_spans_with_options(text, &Options::default())
+}
+
+/// Split a text into spans of different languages, using the given options.
+///
+/// See [`detect_spans`](fn.detect_spans.html) for details.
+pub fn detect_spans_with_options(text: &str, options: &Options) -> Vec<(Range<usize>, Lang)> {
+    let mut spans: Vec<(Range<usize>, Lang)> = vec![];
+    let mut last_lang = None;
+    for (run, script) in script_runs(text) {
+        let words = split_words(text, run);
+        let langs = label_words(text, script, &words, options);
+        for (word, lang) in words.into_iter().zip(langs) {
+            let lang = match lang.or(last_lang) {
+                Some(lang) => lang,
+                None => continue
+            };
+            last_lang = Some(lang);
+            match spans.last_mut() {
+                Some(&mut (ref mut range, span_lang)) if span_lang == lang => range.end = word.end,
+                _ => spans.push((word, lang))
+            }
+        }
+    }
+    spans
+}
+
+/// Byte ranges of the parts of a text, which are detected as the given language,
+/// see [detect_spans](fn.detect_spans.html) for how the text is split.
+///
+/// # Example
+/// ```
+/// use whatlang::{extract_lang_spans, Lang};
+///
+/// let text = "Ayer fuimos al mercado y compramos muchas frutas frescas para la semana, \
+///             but then it started raining and we had to go back home quickly.";
+/// let spans: Vec<&str> = extract_lang_spans(text, Lang::Eng).into_iter().map(|range| &text[range]).collect();
+/// assert_eq!(spans, vec!["but then it started raining and we had to go back home quickly."]);
+/// ```
+pub fn extract_lang_spans(text: &str, lang: Lang) -> Vec<Range<usize>> {
+    extract_lang_spans_with_options(text, lang, &Options::default())
+}
+
+/// Byte ranges of the parts of a text, which are detected as the given language,
+/// using the given options.
+pub fn extract_lang_spans_with_options(text: &str, lang: Lang, options: &Options) -> Vec<Range<usize>> {
+    detect_spans_with_options(text, options).into_iter()
+        .filter(|&(_, span_lang)| span_lang == lang)
+        .map(|(range, _)| range)
+        .collect()
+}
+
+/// The parts of a text, which are detected as the given language, joined by line breaks.
+///
+/// # Example
+/// ```
+/// use whatlang::{extract_lang_text, Lang};
+///
+/// let text = "Ayer fuimos al mercado y compramos muchas frutas frescas para la semana, \
+///             but then it started raining and we had to go back home quickly.";
+/// assert_eq!(
+///     extract_lang_text(text, Lang::Spa),
+///     "Ayer fuimos al mercado y compramos muchas frutas frescas para la semana,"
+/// );
+/// ```
+pub fn extract_lang_text(text: &str, lang: Lang) -> String {
+    extract_lang_text_with_options(text, lang, &Options::default())
+}
+
+/// The parts of a text, which are detected as the given language, joined by line breaks,
+/// using the given options.
+pub fn extract_lang_text_with_options(text: &str, lang: Lang, options: &Options) -> String {
+    extract_lang_spans_with_options(text, lang, options).into_iter()
+        .map(|range| &text[range])
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+// Byte ranges of whitespace separated words within the range of the text.
+fn split_words(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut words = vec![];
+    let mut word_start = None;
+    for (offset, ch) in text[range.clone()].char_indices() {
+        let pos = range.start + offset;
+        match (ch.is_whitespace(), word_start) {
+            (true, Some(start)) => {
+                words.push(start..pos);
+                word_start = None;
+            }
+            (false, None) => word_start = Some(pos),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(start..range.end);
+    }
+    words
+}
+
+// Languages of the words, which are voted by the windows of words.
+fn label_words(text: &str, script: Script, words: &[Range<usize>], options: &Options) -> Vec<Option<Lang>> {
+    let mut votes: Vec<Vec<(Lang, f64)>> = vec![vec![]; words.len()];
+    for window in span_windows(words.len()) {
+        let range = words[window.start].start..words[window.end - 1].end;
+        let info = match detect_with_options(&text[range], options) {
+            Some(info) => info,
+            None => continue
+        };
+        let vote = info.confidence().max(MIN_VOTE);
+        for word_votes in votes[window].iter_mut() {
+            match word_votes.iter_mut().find(|&&mut (lang, _)| lang == info.lang()) {
+                Some(pair) => pair.1 += vote,
+                None => word_votes.push((info.lang(), vote))
+            }
+        }
+    }
+
+    let mut langs: Vec<Option<Lang>> = votes.iter()
+        .map(|word_votes| {
+            // The first of the best voted languages
+            word_votes.iter()
+                .fold(None, |best: Option<(Lang, f64)>, &(lang, vote)| match best {
+                    Some((_, best_vote)) if best_vote >= vote => best,
+                    _ => Some((lang, vote))
+                })
+                .map(|(lang, _)| lang)
+        })
+        .collect();
+    merge_short_spans(&mut langs);
+    refine_boundaries(text, script, words, &mut langs, options);
+    langs
+}
+
+// Windows smear the boundary between two languages, so it's moved within a half of the window
+// to the position, where the words before it look most like the first language
+// and the words after it look most like the second one.
+fn refine_boundaries(text: &str, script: Script, words: &[Range<usize>], langs: &mut [Option<Lang>], options: &Options) {
+    let mut boundary = 1;
+    while boundary < langs.len() {
+        let (first, second) = match (langs[boundary - 1], langs[boundary]) {
+            (Some(first), Some(second)) if first != second => (first, second),
+            _ => {
+                boundary += 1;
+                continue;
+            }
+        };
+        let start = langs[..boundary].iter().rposition(|&lang| lang != Some(first)).map_or(0, |pos| pos + 1);
+        let end = langs[boundary..].iter().position(|&lang| lang != Some(second)).map_or(langs.len(), |len| boundary + len);
+
+        let score = |split: usize| {
+            let before = words[start.max(split.saturating_sub(SPAN_WINDOW_WORDS))].start..words[split - 1].end;
+            let after = words[split].start..words[end.min(split + SPAN_WINDOW_WORDS) - 1].end;
+            let bonus = if text[words[split - 1].clone()].ends_with(|ch: char| ch.is_ascii_punctuation()) {
+                PUNCTUATION_BONUS
+            } else {
+                0.0
+            };
+            lang_preference(&text[before], script, first, second, options)
+                + lang_preference(&text[after], script, second, first, options)
+                + bonus
+        };
+
+        let low = (start + 1).max(boundary.saturating_sub(SPAN_WINDOW_WORDS / 2));
+        let high = (end - 1).min(boundary + SPAN_WINDOW_WORDS / 2);
+        let mut best = (boundary, score(boundary));
+        for split in low..=high {
+            let split_score = score(split);
+            if split_score > best.1 {
+                best = (split, split_score);
+            }
+        }
+        for (i, lang) in langs[start..end].iter_mut().enumerate() {
+            *lang = Some(if start + i < best.0 { first } else { second });
+        }
+        boundary = end;
+    }
+}
+
+// How much more the text looks like `lang` than like `other`, from -1 to 1.
+// Trigram profiles are compared, if both languages have them, otherwise the text
+// is detected with only the two languages allowed.
+fn lang_preference(text: &str, script: Script, lang: Lang, other: Lang, options: &Options) -> f64 {
+    let similarities = (trigram_similarity(text, script, lang, options), trigram_similarity(text, script, other, options));
+    if let (Some(similarity), Some(other_similarity)) = similarities {
+        return similarity - other_similarity;
+    }
+    match detect_with_options(text, &options.clone().set_whitelist(vec![lang, other])) {
+        Some(info) if info.lang() == lang => info.confidence(),
+        Some(info) => -info.confidence(),
+        None => 0.0
+    }
+}
+
+// Windows of SPAN_WINDOW_WORDS words, with the last one ending at the last word.
+fn span_windows(words: usize) -> Vec<Range<usize>> {
+    if words <= SPAN_WINDOW_WORDS {
+        return Some(0..words).into_iter().collect();
+    }
+    let last = words - SPAN_WINDOW_WORDS;
+    let mut windows: Vec<Range<usize>> = (0..=last).step_by(SPAN_WINDOW_STEP)
+        .map(|start| start..start + SPAN_WINDOW_WORDS)
+        .collect();
+    if last % SPAN_WINDOW_STEP != 0 {
+        windows.push(last..words);
+    }
+    windows
+}
+
+// Relabel runs of less than MIN_SPAN_WORDS words with the language of the preceding
+// (or the following, for the first one) run.
+fn merge_short_spans(langs: &mut [Option<Lang>]) {
+    let mut start = 0;
+    while start < langs.len() {
+        let lang = langs[start];
+        let end = langs[start..].iter().position(|&other| other != lang).map_or(langs.len(), |len| start + len);
+        if lang.is_some() && end - start < MIN_SPAN_WORDS {
+            let neighbour = if start > 0 { langs[start - 1] } else { langs.get(end).cloned().unwrap_or(lang) };
+            if neighbour.is_some() {
+                for other in langs[start..end].iter_mut() {
+                    *other = neighbour;
+                }
+            }
+        }
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_proportions() {
+        assert_eq!(detect_proportions(""), vec![]);
+        assert_eq!(detect_proportions("42! 13."), vec![]);
+
+        let text = "There is no reason not to learn Esperanto. 1234. Introduce a request to find the information.";
+        assert_eq!(detect_proportions(text), vec![(Lang::Eng, 1.0)]);
+
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. \
+                    There is no reason not to learn Esperanto.";
+        let proportions = detect_proportions(text);
+        assert_eq!(proportions.len(), 2);
+        assert_eq!(proportions[0].0, Lang::Deu);
+        assert_eq!(proportions[1].0, Lang::Eng);
+        assert!((proportions[0].1 - 69.0 / 111.0).abs() < 1e-9);
+        assert!((proportions[0].1 + proportions[1].1 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_detect_proportions_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Eng, Lang::Rus]);
+        let text = "Der Sommer war sehr heiß. Введите запрос, чтобы найти нужную информацию.";
+        let langs: Vec<Lang> = detect_proportions_with_options(text, &options).into_iter().map(|(lang, _)| lang).collect();
+        assert_eq!(langs, vec![Lang::Rus, Lang::Eng]);
+    }
+
+    fn spans(text: &str) -> Vec<(&str, Lang)> {
+        detect_spans(text).into_iter().map(|(range, lang)| (&text[range], lang)).collect()
+    }
+
+    #[test]
+    fn test_detect_spans() {
+        assert_eq!(spans(""), vec![]);
+        assert_eq!(spans("42 13"), vec![]);
+        assert_eq!(
+            spans("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten."),
+            vec![("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", Lang::Deu)]
+        );
+
+        let text = "The weather was really nice yesterday and we went for a long walk in the park with our friends. \
+                    Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten hinter dem Haus.";
+        assert_eq!(spans(text), vec![
+            ("The weather was really nice yesterday and we went for a long walk in the park with our friends.", Lang::Eng),
+            ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten hinter dem Haus.", Lang::Deu)
+        ]);
+
+        let text = "I have been living in London for many years, and I still remember the words: \
+                    я помню чудное мгновенье, передо мной явилась ты.";
+        assert_eq!(spans(text), vec![
+            ("I have been living in London for many years, and I still remember the words:", Lang::Eng),
+            ("я помню чудное мгновенье, передо мной явилась ты.", Lang::Rus)
+        ]);
+    }
+
+    #[test]
+    fn test_extract_lang_text() {
+        let text = "The weather was really nice yesterday and we went for a long walk in the park with our friends. \
+                    Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten hinter dem Haus. \
+                    After that we had dinner together and talked about our plans for the next holidays.";
+        assert_eq!(
+            extract_lang_text(text, Lang::Eng),
+            "The weather was really nice yesterday and we went for a long walk in the park with our friends.\n\
+             After that we had dinner together and talked about our plans for the next holidays."
+        );
+        assert_eq!(extract_lang_spans(text, Lang::Fra), vec![]);
+    }
+
+    #[test]
+    fn test_merge_short_spans() {
+        let (eng, deu) = (Some(Lang::Eng), Some(Lang::Deu));
+        let mut langs = vec![eng, eng, eng, deu, eng, eng, None, deu, deu, deu];
+        merge_short_spans(&mut langs);
+        assert_eq!(langs, vec![eng, eng, eng, eng, eng, eng, None, deu, deu, deu]);
+
+        let mut langs = vec![deu, eng, eng, eng];
+        merge_short_spans(&mut langs);
+        assert_eq!(langs, vec![eng, eng, eng, eng]);
+    }
+
+    #[test]
+    fn test_span_windows() {
+        assert_eq!(span_windows(3).len(), 1);
+        assert_eq!(span_windows(3)[0], 0..3);
+        assert_eq!(span_windows(12), vec![0..8, 2..10, 4..12]);
+        assert_eq!(span_windows(11), vec![0..8, 2..10, 3..11]);
+    }
+}
@@ -0,0 +1,118 @@
+use lang::Lang;
+use trigrams::Ngram;
+
+// A posting keeps the rank of an n-gram in a profile in its lower bits
+// and the index of the profile in the list in the higher ones. The same as in build.rs.
+const RANK_BITS: u32 = 9;
+const RANK_MASK: u16 = (1 << RANK_BITS) - 1;
+
+// N-grams of texts are packed with 21 bits per char (see src/trigrams.rs), but chars of the built-in
+// profiles are in the Basic Multilingual Plane, so keys of the tables take only 16 bits per char.
+const MAX_NGRAM_SIZE: usize = 4;
+const NGRAM_CHAR_BITS: usize = 21;
+const NGRAM_CHAR_MASK: u128 = (1 << NGRAM_CHAR_BITS) - 1;
+const KEY_CHAR_BITS: usize = 16;
+
+// A perfect hash table of all the n-grams of a list of built-in profiles, generated by build.rs.
+// An n-gram of a text is looked up in O(1) and gives the profiles which contain it with its ranks in them,
+// so the profiles don't need to be scanned for every text.
+pub(crate) struct NgramTable {
+    // Languages and sizes of the profiles
+    pub langs: &'static [Lang],
+    pub sizes: &'static [u16],
+    // Seeds of the hash of the slot for every bucket of keys
+    pub displacements: &'static [u16],
+    // Packed n-grams of the slots, zero for an empty slot
+    pub keys: &'static [u64],
+    // Postings of a slot are in postings[offsets[slot]..offsets[slot + 1]]
+    pub offsets: &'static [u16],
+    pub postings: &'static [u16],
+}
+
+impl NgramTable {
+    // Indices of the profiles, which contain the n-gram, with ranks of the n-gram in them.
+    pub fn get(&self, ngram: Ngram) -> impl Iterator<Item = (usize, usize)> {
+        let postings = match table_key(ngram.packed()).and_then(|key| self.find_slot(key)) {
+            Some(slot) => &self.postings[self.offsets[slot] as usize..self.offsets[slot + 1] as usize],
+            None => &[]
+        };
+        postings.iter().map(|&posting| ((posting >> RANK_BITS) as usize, (posting & RANK_MASK) as usize))
+    }
+
+    fn find_slot(&self, key: u64) -> Option<usize> {
+        if self.displacements.is_empty() {
+            return None;
+        }
+        let bucket = ngram_hash(key, 0) % self.displacements.len() as u64;
+        let displacement = self.displacements[bucket as usize];
+        let slot = (ngram_hash(key, u64::from(displacement) + 1) % self.keys.len() as u64) as usize;
+        if self.keys[slot] == key { Some(slot) } else { None }
+    }
+}
+
+// Repacks an n-gram of a text into a key of the tables, None if it has a char out of the BMP,
+// so it is not in any table.
+fn table_key(packed: u128) -> Option<u64> {
+    let mut key = 0;
+    for i in (0..MAX_NGRAM_SIZE).rev() {
+        let code = ((packed >> (NGRAM_CHAR_BITS * i)) & NGRAM_CHAR_MASK) as u64;
+        if code >= 1 << KEY_CHAR_BITS {
+            return None;
+        }
+        key = (key << KEY_CHAR_BITS) | code;
+    }
+    Some(key)
+}
+
+// The same as in build.rs.
+fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn ngram_hash(key: u64, seed: u64) -> u64 {
+    mix(key ^ seed.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::table_key;
+    use lang::*;
+    use trigrams::Ngram;
+
+    #[test]
+    fn test_ngram_table_matches_profiles() {
+        for list in [LATIN_LANGS, CYRILLIC_LANGS_BIGRAMS, ROMANIZED_LANGS_FOURGRAMS].iter() {
+            let table = list.table();
+            for (index, &(lang, profile)) in list.profiles().iter().enumerate() {
+                assert_eq!((table.langs[index], table.sizes[index] as usize), (lang, profile.len()));
+                for (rank, &ngram) in profile.iter().enumerate() {
+                    assert!(table.get(Ngram::new(ngram).unwrap()).any(|posting| posting == (index, rank)), "{:?} {}", lang, ngram);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_table_key() {
+        let key = |ngram| table_key(Ngram::new(ngram).unwrap().packed());
+        assert_eq!(key("ab"), Some(((u64::from(b'a') + 1) << 48) | ((u64::from(b'b') + 1) << 32)));
+        assert_eq!(key("\u{FFFE}"), Some(0xFFFF << 48));
+        assert_eq!(key("a\u{FFFF}"), None);
+        assert_eq!(key("😀"), None);
+    }
+
+    #[test]
+    fn test_ngram_table_unknown_ngrams() {
+        let table = LATIN_LANGS.table();
+        for &ngram in ["qqq", "жжж", "ab", " the", "t😀e", "𝐭he"].iter() {
+            assert_eq!(table.get(Ngram::new(ngram).unwrap()).count(), 0);
+        }
+        assert_eq!(table.get(Ngram::new("the").unwrap()).count(), table.langs.iter().filter(|&&lang| {
+            LATIN_LANGS.profiles().iter().any(|&(profile_lang, profile)| profile_lang == lang && profile.contains(&"the"))
+        }).count());
+    }
+}
@@ -0,0 +1,118 @@
+use lang::Lang;
+
+// Bokmål and Nynorsk are two written standards of Norwegian, so their trigram profiles are
+// very close and short texts are often detected as the wrong one. They differ mostly in
+// the most common words: pronouns ("jeg" and "eg", "hun" and "ho"), question words
+// ("hva" and "kva"), negation ("ikke" and "ikkje") and articles ("en" and "ein").
+//
+// Words are sorted, to be found with binary search.
+const BOKMAL_WORDS: &[&str] = &[
+    "bare", "ble", "de", "dem", "deres", "disse", "en", "et", "fikk", "fra", "gikk", "gjøre",
+    "hennes", "hjem", "hjemme", "hun", "hva", "hvem", "hver", "hvert", "hvis", "hvor", "hvordan",
+    "hvorfor", "ikke", "jeg", "kommer", "mye", "noe", "noen", "nå", "sammen", "selv", "uten",
+    "være", "vært",
+];
+
+const NYNORSK_WORDS: &[&str] = &[
+    "berre", "dei", "deira", "desse", "eg", "ein", "eit", "fekk", "frå", "gjekk", "gjere",
+    "heim", "heime", "hennar", "ho", "ikkje", "kjem", "korleis", "kva", "kvar", "kven", "kvifor",
+    "mykje", "no", "noko", "nokon", "saman", "sjølv", "utan", "vart", "vere", "vore", "òg",
+];
+
+// Choose between Bokmål and Nynorsk by the words, which are used only in one of the standards.
+// Returns None if `lang` is neither Bokmål nor Nynorsk, or if the text has as many words
+// of one standard as of the other one.
+pub(crate) fn resolve_norwegian(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_norwegian(lang) {
+        return None;
+    }
+    resolve_norwegian_by_counts(count_norwegian_words(text), lang)
+}
+
+// Same as resolve_norwegian, but with the words already counted by count_norwegian_words.
+pub(crate) fn resolve_norwegian_by_counts(words: NorwegianWords, lang: Lang) -> Option<Lang> {
+    if !is_norwegian(lang) {
+        return None;
+    }
+
+    if words.bokmal > words.nynorsk {
+        Some(Lang::Nob)
+    } else if words.nynorsk > words.bokmal {
+        Some(Lang::Nno)
+    } else {
+        None
+    }
+}
+
+// Numbers of words, which are used only in Bokmål or only in Nynorsk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct NorwegianWords {
+    pub bokmal: usize,
+    pub nynorsk: usize
+}
+
+impl NorwegianWords {
+    pub fn add(&mut self, other: NorwegianWords) {
+        self.bokmal += other.bokmal;
+        self.nynorsk += other.nynorsk;
+    }
+}
+
+pub(crate) fn count_norwegian_words(text: &str) -> NorwegianWords {
+    let mut result = NorwegianWords::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(BOKMAL_WORDS, &word) {
+            result.bokmal += 1;
+        } else if contains(NYNORSK_WORDS, &word) {
+            result.nynorsk += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_norwegian(lang: Lang) -> bool {
+    lang == Lang::Nob || lang == Lang::Nno
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [BOKMAL_WORDS, NYNORSK_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in BOKMAL_WORDS.iter() {
+            assert!(!contains(NYNORSK_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_norwegian() {
+        let text = "Jeg vet ikke hva han vil gjøre i morgen.";
+        assert_eq!(resolve_norwegian(text, Lang::Nno), Some(Lang::Nob));
+
+        let text = "Eg veit ikkje kva han vil gjere i morgon.";
+        assert_eq!(resolve_norwegian(text, Lang::Nob), Some(Lang::Nno));
+
+        // Without evidence the standard is not resolved
+        let text = "Det er kaldt i dag.";
+        assert_eq!(resolve_norwegian(text, Lang::Nno), None);
+
+        assert_eq!(resolve_norwegian(text, Lang::Dan), None);
+    }
+
+    #[test]
+    fn test_count_norwegian_words() {
+        let text = "Hun kommer hjem. Ho kjem heim frå jobben.";
+        assert_eq!(count_norwegian_words(text), NorwegianWords { bokmal: 3, nynorsk: 4 });
+    }
+}
@@ -1,4 +1,12 @@
+#[cfg(parallel)]
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(parallel)]
+use rayon::ThreadPool;
+
 use lang::Lang;
+use script::Script;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum List {
@@ -6,10 +14,192 @@ pub(crate) enum List {
     Black(Vec<Lang>)
 }
 
-/// Allows to customize behaviour of [Detector](struct.Detector.html).
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Length of n-grams, which are compared with profiles of languages,
+/// see [Options::set_ngram_order](struct.Options.html#method.set_ngram_order).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NgramOrder {
+    Bigrams,
+    Trigrams,
+    Fourgrams
+}
+
+impl NgramOrder {
+    pub(crate) fn size(self) -> usize {
+        match self {
+            NgramOrder::Bigrams => 2,
+            NgramOrder::Trigrams => 3,
+            NgramOrder::Fourgrams => 4
+        }
+    }
+}
+
+/// A way to compare n-grams of a text with profiles of languages,
+/// see [Options::set_scoring_metric](struct.Options.html#method.set_scoring_metric).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScoringMetric {
+    /// Sum of differences between ranks of n-grams in the text and in the profile (default).
+    OutOfPlace,
+    /// Cosine similarity of the text and the profile, where n-grams weigh by their ranks.
+    Cosine,
+    /// Log-probability of the text in the language, where probabilities of n-grams are
+    /// proportional to their ranks in the profile and missing n-grams are smoothed.
+    LogProbability,
+    /// Share of occurrences of n-grams of the text, which are found in the profile,
+    /// weighted by their ranks in the profile. Unlike other metrics, n-grams count as many
+    /// times as they occur in the text, what separates closely related languages better
+    /// in longer documents.
+    CountWeighted
+}
+
+/// Unicode normalization form, to which texts are converted before detection,
+/// see [Options::set_normalization](struct.Options.html#method.set_normalization).
+#[cfg(feature = "normalization")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// Canonical composition: base letters and combining marks are composed, e.g. `e` + `\u{301}` to `é`.
+    Nfc,
+    /// Compatibility composition: in addition to NFC, compatibility chars are replaced,
+    /// e.g. ligatures (`ﬁ` to `fi`) and full-width Latin letters (`Ａ` to `A`).
+    Nfkc
+}
+
+// A filter of chars, which are ignored in detection. Filters are compared by their addresses.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CharFilter(pub(crate) fn(char) -> bool);
+
+impl PartialEq for CharFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 as usize == other.0 as usize
+    }
+}
+
+impl Eq for CharFilter {}
+
+// A rayon thread pool for parallel processing. Pools are compared by their addresses.
+#[cfg(parallel)]
+#[derive(Debug, Clone)]
+pub(crate) struct SharedThreadPool(pub(crate) Arc<ThreadPool>);
+
+#[cfg(parallel)]
+impl PartialEq for SharedThreadPool {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(parallel)]
+impl Eq for SharedThreadPool {}
+
+// Texts shorter than this (in bytes) are faster to process in a single thread.
+const DEFAULT_PARALLEL_THRESHOLD: usize = 64 * 1024;
+
+// Sizes of samples of texts (in bytes) and limits of options of the presets, see Preset.
+const FAST_SAMPLE_SIZE: usize = 2 * 1024;
+const FAST_SCRIPT_EXIT_THRESHOLD: (f64, usize) = (0.8, 100);
+const FAST_MAX_WORD_LENGTH: usize = 64;
+const BALANCED_SAMPLE_SIZE: usize = 8 * 1024;
+
+/// A bundle of options, which trades accuracy of detection for its speed,
+/// see [Options::preset](struct.Options.html#method.preset).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Preset {
+    /// Detect a sample of 2 KB of a text, stop counting chars of scripts as soon as a script has 80%
+    /// of at least 100 chars, keep only the first 64 chars of every word and do not tell apart
+    /// close languages by their specific words (see `Options::set_resolve_close_langs`).
+    /// Time of detection does not depend on the size of a text, but closely related languages
+    /// are told apart worse.
+    Fast,
+    /// Detect a sample of 8 KB of a text, which gives the same result for nearly all real texts.
+    Balanced,
+    /// Detect the whole text, the same as the default options.
+    Accurate
+}
+
+/// Allows to customize behaviour of [Detector](struct.Detector.html) and
+/// [detect_with_options](fn.detect_with_options.html).
+///
+/// # Example
+/// ```
+/// use whatlang::{Options, Lang};
+///
+/// let options = Options::new()
+///     .set_whitelist(vec![Lang::Eng, Lang::Deu, Lang::Fra])
+///     .set_min_confidence(0.3);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
 pub struct Options {
-    pub(crate) list: Option<List>
+    pub(crate) list: Option<List>,
+    pub(crate) min_confidence: f64,
+    pub(crate) priors: Vec<(Lang, f64)>,
+    pub(crate) sample_size: Option<usize>,
+    pub(crate) max_duration: Option<Duration>,
+    pub(crate) max_chars: Option<usize>,
+    pub(crate) max_word_length: Option<usize>,
+    pub(crate) parallel_threshold: usize,
+    // Share of chars and minimal number of chars, see set_script_exit_threshold
+    pub(crate) script_exit_threshold: Option<(f64, usize)>,
+    // Scripts, which are detected at all, see set_scripts
+    pub(crate) scripts: Option<Vec<Script>>,
+    #[cfg(parallel)]
+    pub(crate) thread_pool: Option<SharedThreadPool>,
+    pub(crate) romanized: bool,
+    pub(crate) min_length: usize,
+    pub(crate) filter_social_tokens: bool,
+    pub(crate) skip_code: bool,
+    pub(crate) char_filter: Option<CharFilter>,
+    pub(crate) ngram_order: NgramOrder,
+    pub(crate) scoring_metric: ScoringMetric,
+    pub(crate) unseen_trigram_penalty: Option<f64>,
+    pub(crate) calibrated_confidence: bool,
+    pub(crate) resolve_close_langs: bool,
+    // Sorted lowercase words of every language
+    pub(crate) marker_words: Vec<(Lang, Vec<String>)>,
+    #[cfg(feature = "html")]
+    pub(crate) strip_html: bool,
+    #[cfg(feature = "normalization")]
+    pub(crate) normalization: Option<Normalization>,
+    #[cfg(feature = "segmentation")]
+    pub(crate) segment_words: bool
+}
+
+// Float options are never NaN (their setters do not store it), so equality of options is total.
+impl Eq for Options {}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            list: None,
+            min_confidence: 0.0,
+            priors: vec![],
+            sample_size: None,
+            max_duration: None,
+            max_chars: None,
+            max_word_length: None,
+            parallel_threshold: DEFAULT_PARALLEL_THRESHOLD,
+            script_exit_threshold: None,
+            scripts: None,
+            #[cfg(parallel)]
+            thread_pool: None,
+            romanized: false,
+            min_length: 0,
+            filter_social_tokens: false,
+            skip_code: false,
+            char_filter: None,
+            ngram_order: NgramOrder::Trigrams,
+            scoring_metric: ScoringMetric::OutOfPlace,
+            unseen_trigram_penalty: None,
+            calibrated_confidence: false,
+            resolve_close_langs: true,
+            marker_words: vec![],
+            #[cfg(feature = "html")]
+            strip_html: false,
+            #[cfg(feature = "normalization")]
+            normalization: None,
+            #[cfg(feature = "segmentation")]
+            segment_words: false
+        }
+    }
 }
 
 impl Options {
@@ -17,6 +207,31 @@ impl Options {
         Self::default()
     }
 
+    /// Options of the preset, which can be changed further by the other methods.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options, Preset};
+    ///
+    /// let options = Options::preset(Preset::Fast).set_whitelist(vec![Lang::Deu, Lang::Eng]);
+    /// let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(1000);
+    /// assert_eq!(detect_lang_with_options(&text, &options), Some(Lang::Deu));
+    /// ```
+    pub fn preset(preset: Preset) -> Self {
+        let options = Self::default();
+        match preset {
+            Preset::Fast => {
+                let (share, min_chars) = FAST_SCRIPT_EXIT_THRESHOLD;
+                options.set_sample_size(FAST_SAMPLE_SIZE)
+                    .set_script_exit_threshold(share, min_chars)
+                    .set_max_word_length(FAST_MAX_WORD_LENGTH)
+                    .set_resolve_close_langs(false)
+            }
+            Preset::Balanced => options.set_sample_size(BALANCED_SAMPLE_SIZE),
+            Preset::Accurate => options
+        }
+    }
+
     pub fn set_whitelist(mut self, whitelist: Vec<Lang>) -> Self {
         self.list = Some(List::White(whitelist));
         self
@@ -26,4 +241,499 @@ impl Options {
         self.list = Some(List::Black(blacklist));
         self
     }
+
+    /// Results with confidence below the given value are discarded, so the
+    /// detection functions return `None` for them. Default is 0. Panics if the value is NaN.
+    pub fn set_min_confidence(mut self, min_confidence: f64) -> Self {
+        assert!(!min_confidence.is_nan(), "min confidence is NaN");
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    /// Do not detect a language of texts, which contain less than `length` letters
+    /// (chars of supported scripts, digits, punctuation and spaces are not counted).
+    /// Languages of very short texts are often detected wrongly, but with high confidence.
+    /// Default is 0, so the length is not checked.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_with_options, try_detect_with_options, DetectError, Options};
+    ///
+    /// let options = Options::new().set_min_length(5);
+    /// assert_eq!(detect_with_options("Ok!", &options), None);
+    /// assert_eq!(try_detect_with_options("Ok!", &options), Err(DetectError::TooShort));
+    /// ```
+    pub fn set_min_length(mut self, length: usize) -> Self {
+        self.min_length = length;
+        self
+    }
+
+    /// Bias detection towards the given languages. Every language has a weight
+    /// in the range from 0 to 1, e.g. a quality value from `Accept-Language` header,
+    /// languages with other weights (or NaN) are skipped, like by `parse_accept_language`.
+    /// Priors resolve borderline cases, but do not override a clear result.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Options, parse_accept_language};
+    ///
+    /// let options = Options::new().set_priors(parse_accept_language("nb-NO,da;q=0.5"));
+    /// ```
+    pub fn set_priors(mut self, mut priors: Vec<(Lang, f64)>) -> Self {
+        priors.retain(|&(_, weight)| (0.0..=1.0).contains(&weight));
+        self.priors = priors;
+        self
+    }
+
+    /// Analyze at most `size` bytes of a text, taken in equal parts from the start,
+    /// the middle and the end of it. By default the whole text is analyzed.
+    ///
+    /// The result of detection stabilizes long before the end of a big document,
+    /// so a sample of 8 KB gives the same result for nearly all real texts,
+    /// while the time of detection does not depend on the size of the text anymore.
+    /// Smaller samples are noticeably less accurate for closely related languages
+    /// (e.g. Maithili and Nepali), especially in scripts which take 3 bytes per character.
+    /// Documents, which mix languages or scripts in separate big parts, may be
+    /// detected differently.
+    pub fn set_sample_size(mut self, size: usize) -> Self {
+        self.sample_size = Some(size);
+        self
+    }
+
+    /// Limit the time of detection of long texts. Samples of a text (see `set_sample_size`)
+    /// are detected starting with 4 KB, every next one is 4 times bigger, while it is expected
+    /// to be detected within `duration` since the start of detection. If the whole text does not fit,
+    /// the result of the biggest sample is returned, which is marked as partial and unreliable,
+    /// see [Info::is_partial](struct.Info.html#method.is_partial). The first sample is always detected,
+    /// so the limit may be exceeded by the time of its detection. Samples take at most a third
+    /// of the time of the whole text on top of it. By default the time is not limited.
+    /// The limit is ignored on `wasm32-unknown-unknown` target, which has no clock.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use whatlang::{detect_with_options, Lang, Options};
+    ///
+    /// let options = Options::new().set_max_duration(Duration::from_millis(5));
+    /// let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(100_000);
+    /// let info = detect_with_options(&text, &options).unwrap();
+    /// assert_eq!(info.lang(), Lang::Deu);
+    /// ```
+    pub fn set_max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Analyze only the first `chars` chars of a text, the rest of it is ignored.
+    /// Unlike `set_sample_size`, the text is cut before any other processing (e.g. stripping of HTML),
+    /// so memory and time of detection of untrusted inputs are bounded. By default the whole text is analyzed.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let text = "Der Sommer war sehr heiß und die Kinder spielten. Esperanto estas facila lingvo por ĉiuj homoj.";
+    /// let options = Options::new().set_max_chars(48);
+    /// assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Deu));
+    /// ```
+    pub fn set_max_chars(mut self, chars: usize) -> Self {
+        self.max_chars = Some(chars);
+        self
+    }
+
+    /// Keep only the first `chars` chars of every word (a run of non-whitespace chars), the rest
+    /// of a longer word is dropped. Real words are much shorter, while extremely long "words"
+    /// (e.g. base64 blobs or megabytes of combining marks) produce lots of garbage n-grams.
+    /// Scripts without spaces between words (e.g. Chinese or Thai) have long runs of chars,
+    /// so the limit should not be too low for them. By default words are not limited.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let text = format!("Der Sommer war sehr heiß und die Kinder spielten {}", "a\u{301}".repeat(100_000));
+    /// let options = Options::new().set_max_word_length(64);
+    /// assert_eq!(detect_lang_with_options(&text, &options), Some(Lang::Deu));
+    /// ```
+    pub fn set_max_word_length(mut self, chars: usize) -> Self {
+        self.max_word_length = Some(chars);
+        self
+    }
+
+    /// With `parallel` feature, characters of texts which are at least `size` bytes long
+    /// are counted in parallel. Shorter texts are processed in the calling thread,
+    /// because for them the overhead of rayon outweighs the gain. Default is 64 KB.
+    /// Without `parallel` feature the option has no effect.
+    pub fn set_parallel_threshold(mut self, size: usize) -> Self {
+        self.parallel_threshold = size;
+        self
+    }
+
+    /// Stop detection of a script as soon as one script has at least `share` of the chars
+    /// counted so far, after at least `min_chars` chars of scripts. By default a script is
+    /// detected once it has more chars than all the other scripts can get till the end
+    /// of the text, which needs about a half of a text in one script. An earlier exit
+    /// makes detection of long texts much faster, but a text which starts with a part
+    /// in another script (e.g. a quote or a code snippet) may be detected by this part.
+    /// The exact check still applies, so the result is never worse for single-script texts.
+    /// Texts are always processed in the calling thread then. Language detection counts chars
+    /// of scripts only till the threshold too (but at least `set_min_length` chars),
+    /// so the proportions of scripts are of the beginning of a text. Panics if `share` is NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Detector, Options, Script};
+    ///
+    /// let options = Options::new().set_script_exit_threshold(0.8, 100);
+    /// let detector = Detector::with_options(options);
+    /// let text = "Привет, мир! ".repeat(20) + &"Hello, world! ".repeat(100);
+    /// assert_eq!(detector.detect_script(&text), Some(Script::Cyrillic));
+    /// assert_eq!(Detector::new().detect_script(&text), Some(Script::Latin));
+    /// ```
+    pub fn set_script_exit_threshold(mut self, share: f64, min_chars: usize) -> Self {
+        assert!(!share.is_nan(), "share of script exit threshold is NaN");
+        self.script_exit_threshold = Some((share, min_chars));
+        self
+    }
+
+    /// Detect only the given scripts, chars of the other scripts are not counted at all,
+    /// as if they were punctuation. So a few stray chars (e.g. CJK ones in a European text)
+    /// can not change the detected script, and a text of only other scripts has no script.
+    /// By default every script is detected.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Detector, Options, Script};
+    ///
+    /// let options = Options::new().set_scripts(vec![Script::Latin, Script::Cyrillic]);
+    /// let detector = Detector::with_options(options);
+    /// assert_eq!(detector.detect_script("OK 東京大学"), Some(Script::Latin));
+    /// assert_eq!(detector.detect_script("東京大学"), None);
+    /// assert_eq!(Detector::new().detect_script("OK 東京大学"), Some(Script::Mandarin));
+    /// ```
+    pub fn set_scripts(mut self, scripts: Vec<Script>) -> Self {
+        self.scripts = Some(scripts);
+        self
+    }
+
+    /// With `parallel` feature, run parallel work (counting of scripts of long texts and
+    /// [detect_many_with_options](fn.detect_many_with_options.html)) in the given rayon pool
+    /// instead of the global one. Without the option the work runs in the pool of the calling
+    /// thread, so calls from within `ThreadPool::install` already use that pool.
+    /// Not available on WebAssembly (`wasm32`) targets.
+    ///
+    /// # Example
+    /// ```
+    /// # extern crate rayon;
+    /// # extern crate whatlang;
+    /// use std::sync::Arc;
+    /// use whatlang::{detect_many_with_options, Lang, Options};
+    ///
+    /// # fn main() {
+    /// let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    /// let options = Options::new().set_thread_pool(Arc::new(pool));
+    /// let results = detect_many_with_options(&["Ĉu vi ne volas eklerni Esperanton? Bonvolu!"], &options);
+    /// assert_eq!(results[0].unwrap().lang(), Lang::Epo);
+    /// # }
+    /// ```
+    #[cfg(parallel)]
+    pub fn set_thread_pool(mut self, pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(SharedThreadPool(pool));
+        self
+    }
+
+    /// Also consider languages written in Latin script instead of their native one:
+    /// Hindi, Arabic (Arabizi), Russian (translit) and Mandarin (Pinyin without tones).
+    /// Such texts are common in social media, but without this option they are always
+    /// detected as some language which is natively written in Latin script. Disabled by default,
+    /// because it makes detection of short texts in these languages less reliable.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let options = Options::new().set_romanized(true);
+    /// let lang = detect_lang_with_options("Kya haal hai bhai? Main theek hoon, tum batao.", &options);
+    /// assert_eq!(lang, Some(Lang::Hin));
+    /// ```
+    pub fn set_romanized(mut self, romanized: bool) -> Self {
+        self.romanized = romanized;
+        self
+    }
+
+    /// Ignore URLs, email addresses, hashtags and @mentions. They are common in social media
+    /// posts and are mostly written in English (or are not words at all), so they drag
+    /// results towards English. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let options = Options::new().set_filter_social_tokens(true);
+    /// let post = "Heute waren wir am Strand, das Wetter war wunderbar! @thebestfriendever #summertime \
+    ///             #happy #weekend https://instagram.com/p/sunset_beach_with_friends";
+    /// assert_eq!(detect_lang_with_options(post, &options), Some(Lang::Deu));
+    /// assert_ne!(detect_lang_with_options(post, &Options::new()), Some(Lang::Deu));
+    /// ```
+    pub fn set_filter_social_tokens(mut self, filter: bool) -> Self {
+        self.filter_social_tokens = filter;
+        self
+    }
+
+    /// Do not detect texts, which are predominantly source code, structured data, markup
+    /// or stack traces (see [is_code](fn.is_code.html)), instead of detecting them as English
+    /// or a random language. Such texts are reported by [try_detect_with_options](fn.try_detect_with_options.html)
+    /// as [DetectError::Code](enum.DetectError.html#variant.Code). The check is done after
+    /// the other preprocessing, e.g. after HTML is stripped. Disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{try_detect_with_options, DetectError, Lang, Options};
+    ///
+    /// let options = Options::new().set_skip_code(true);
+    /// let trace = "Exception in thread \"main\" java.lang.IllegalStateException: Connection is closed\n\
+    ///              \tat com.example.db.Pool.acquire(Pool.java:118)\n\
+    ///              \tat com.example.App.main(App.java:17)";
+    /// assert_eq!(try_detect_with_options(trace, &options), Err(DetectError::Code));
+    ///
+    /// let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.";
+    /// assert_eq!(try_detect_with_options(text, &options).unwrap().lang(), Lang::Deu);
+    /// ```
+    pub fn set_skip_code(mut self, skip_code: bool) -> Self {
+        self.skip_code = skip_code;
+        self
+    }
+
+    /// Ignore chars, for which `filter` returns `true`, in addition to the default ones
+    /// (spaces, digits, punctuation and symbols). Useful for texts, which contain a lot of
+    /// noise besides words, e.g. comments in source code or log lines.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// fn is_code_char(ch: char) -> bool {
+    ///     ch.is_ascii_uppercase() || ch == '_'
+    /// }
+    ///
+    /// let options = Options::new().set_char_filter(is_code_char);
+    /// let line = "WARN MAX_RETRY_COUNT TCP_KEEPALIVE SOCKET_TIMEOUT: die Verbindung wurde unterbrochen";
+    /// assert_eq!(detect_lang_with_options(line, &options), Some(Lang::Deu));
+    /// ```
+    pub fn set_char_filter(mut self, filter: fn(char) -> bool) -> Self {
+        self.char_filter = Some(CharFilter(filter));
+        self
+    }
+
+    /// Compare n-grams of the given length with profiles of languages. Default is trigrams.
+    ///
+    /// Only trigram profiles are built from texts of languages; bigram and 4-gram profiles
+    /// are derived from them, so they are meant for experiments rather than for better accuracy.
+    /// Bigrams are less sensitive to the length of a text, 4-grams capture more of
+    /// a morphology of a language (e.g. suffixes of agglutinative languages).
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, NgramOrder, Options};
+    ///
+    /// let options = Options::new().set_ngram_order(NgramOrder::Fourgrams);
+    /// let lang = detect_lang_with_options("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", &options);
+    /// assert_eq!(lang, Some(Lang::Deu));
+    /// ```
+    pub fn set_ngram_order(mut self, order: NgramOrder) -> Self {
+        self.ngram_order = order;
+        self
+    }
+
+    /// Compare n-grams of texts with profiles of languages by the given metric.
+    /// Default is [ScoringMetric::OutOfPlace](enum.ScoringMetric.html#variant.OutOfPlace).
+    /// Confidence is calculated the same way for all the metrics, so it's not comparable between them.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options, ScoringMetric};
+    ///
+    /// let options = Options::new().set_scoring_metric(ScoringMetric::Cosine);
+    /// let lang = detect_lang_with_options("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", &options);
+    /// assert_eq!(lang, Some(Lang::Deu));
+    /// ```
+    pub fn set_scoring_metric(mut self, metric: ScoringMetric) -> Self {
+        self.scoring_metric = metric;
+        self
+    }
+
+    /// Handle trigrams, which are not in any profile of the candidate languages (e.g. from names,
+    /// numbers or code). Such trigrams are removed before the comparison, so they do not shift
+    /// positions of the known ones, and confidence is reduced by `penalty` (from 0 to 1)
+    /// multiplied by the share of the unseen trigrams in the text. Together with
+    /// `set_min_confidence` it makes texts without much natural language undetected,
+    /// instead of detected as some random language. By default unseen trigrams are
+    /// compared as any other ones. Penalties out of the range are clamped to it, panics if NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_with_options, Options};
+    ///
+    /// let code = "const xs = arr.filter(x => x > 0).map(x => x * 2); console.log(xs.length);";
+    /// let options = Options::new().set_min_confidence(0.6);
+    /// assert!(detect_with_options(code, &options).is_some());
+    /// assert_eq!(detect_with_options(code, &options.set_unseen_trigram_penalty(1.0)), None);
+    /// ```
+    pub fn set_unseen_trigram_penalty(mut self, penalty: f64) -> Self {
+        assert!(!penalty.is_nan(), "unseen trigram penalty is NaN");
+        self.unseen_trigram_penalty = Some(penalty.max(0.0).min(1.0));
+        self
+    }
+
+    /// Report confidence as an approximate probability that the detected language is right.
+    /// Raw confidence depends on the length of a text, so the same threshold means different
+    /// things for short and long texts. Calibrated confidence takes the length into account,
+    /// e.g. 0.7 means that about 70% of texts detected with such confidence are detected right.
+    /// The calibration is fitted on the texts used in tests of whatlang, so it's approximate.
+    /// It applies to the built-in model only, disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_with_options, Options};
+    ///
+    /// let options = Options::new().set_calibrated_confidence(true);
+    /// let info = detect_with_options("Jeg har en hund og en katt", &options).unwrap();
+    /// assert!(info.confidence() > 0.2 && info.confidence() < 1.0);
+    /// ```
+    pub fn set_calibrated_confidence(mut self, calibrated: bool) -> Self {
+        self.calibrated_confidence = calibrated;
+        self
+    }
+
+    /// Tell apart close languages, which trigrams confuse (e.g. Serbian and Croatian, Czech and Slovak,
+    /// Bokmål and Nynorsk), by their specific letters and words. It takes another pass over
+    /// the text, so without it detection is a bit faster, but such languages are often confused.
+    /// It applies to the built-in model and custom profiles, enabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let text = "Eg likar å lese bøker om kvelden.";
+    /// assert_eq!(detect_lang_with_options(text, &Options::new()), Some(Lang::Nno));
+    /// ```
+    pub fn set_resolve_close_langs(mut self, resolve: bool) -> Self {
+        self.resolve_close_langs = resolve;
+        self
+    }
+
+    /// Treat the given words as strong evidence of the language: a score of the language is
+    /// increased proportionally to the share of these words in a text. A few of the most common
+    /// words of a language (articles, conjunctions, pronouns) noticeably improve detection of
+    /// short texts. Words are matched case-insensitively. Replaces previously set words of the language.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let text = "Hund und Katze";
+    /// let options = Options::new().set_marker_words(Lang::Deu, vec!["und", "der", "die", "das"]);
+    /// assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Deu));
+    /// assert_ne!(detect_lang_with_options(text, &Options::new()), Some(Lang::Deu));
+    /// ```
+    pub fn set_marker_words<S: AsRef<str>>(mut self, lang: Lang, words: Vec<S>) -> Self {
+        let mut words: Vec<String> = words.iter().map(|word| word.as_ref().to_lowercase()).collect();
+        words.sort();
+        words.dedup();
+        self.marker_words.retain(|&(marker_lang, _)| marker_lang != lang);
+        self.marker_words.push((lang, words));
+        self
+    }
+
+    /// Strip HTML markup from texts before detection, see [strip_html](fn.strip_html.html).
+    /// Tags and attribute values of web pages are mostly in English, so they drag
+    /// results towards English. Available with `html` feature, disabled by default.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let options = Options::new().set_strip_html(true);
+    /// let html = r#"<div class="content main-section" id="article-body" data-tracking="header-link">
+    ///     <a href="https://example.com/news/latest" title="Read the latest news">Die Kinder spielten im Garten</a>
+    /// </div>"#;
+    /// assert_eq!(detect_lang_with_options(html, &options), Some(Lang::Deu));
+    /// assert_eq!(detect_lang_with_options(html, &Options::new()), Some(Lang::Eng));
+    /// ```
+    #[cfg(feature = "html")]
+    pub fn set_strip_html(mut self, strip_html: bool) -> Self {
+        self.strip_html = strip_html;
+        self
+    }
+
+    /// Normalize texts to the given Unicode normalization form before detection.
+    /// Decomposed texts (e.g. file names from macOS, which are in NFD) separate base letters
+    /// from their combining marks, what breaks counting of scripts and trigrams.
+    /// Available with `normalization` feature, by default texts are not normalized.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Normalization, Options};
+    ///
+    /// let options = Options::new().set_normalization(Normalization::Nfc);
+    /// // "Le véritable été arrive à la forêt" in NFD
+    /// let nfd = "Le ve\u{301}ritable e\u{301}te\u{301} arrive\u{300} la fore\u{302}t";
+    /// assert_eq!(detect_lang_with_options(nfd, &options), Some(Lang::Fra));
+    /// ```
+    #[cfg(feature = "normalization")]
+    pub fn set_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = Some(normalization);
+        self
+    }
+
+    /// Split texts into words by Unicode word boundaries before detection: every word is
+    /// separated by spaces, everything between words (e.g. fullwidth or other non-ASCII
+    /// punctuation) is replaced with spaces. By default only ASCII punctuation, digits
+    /// and symbols separate words, so e.g. Latin words, which are glued to CJK text
+    /// or to fullwidth punctuation, give n-grams with chars of the other script.
+    /// Available with `segmentation` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{detect_lang_with_options, Lang, Options};
+    ///
+    /// let options = Options::new().set_segment_words(true);
+    /// let text = "Das ist ein schöner Tag，nicht wahr？Wir gehen heute spazieren。";
+    /// assert_eq!(detect_lang_with_options(text, &options), Some(Lang::Deu));
+    /// ```
+    #[cfg(feature = "segmentation")]
+    pub fn set_segment_words(mut self, segment_words: bool) -> Self {
+        self.segment_words = segment_words;
+        self
+    }
+
+    pub(crate) fn prior(&self, lang: Lang) -> f64 {
+        self.priors.iter()
+            .find(|prior| prior.0 == lang)
+            .map(|prior| prior.1)
+            .unwrap_or(0.0)
+    }
+
+    // Runs the parallel work in the thread pool of the options, if any.
+    #[cfg(parallel)]
+    pub(crate) fn install<F, R>(&self, work: F) -> R
+        where F: FnOnce() -> R + Send, R: Send
+    {
+        match self.thread_pool {
+            Some(ref pool) => pool.0.install(work),
+            None => work()
+        }
+    }
+
+    pub(crate) fn is_script_allowed(&self, script: Script) -> bool {
+        self.scripts.as_ref().map_or(true, |scripts| scripts.contains(&script))
+    }
+
+    pub(crate) fn is_allowed(&self, lang: Lang) -> bool {
+        match self.list {
+            Some(List::White(ref whitelist)) => whitelist.contains(&lang),
+            Some(List::Black(ref blacklist)) => !blacklist.contains(&lang),
+            None => true
+        }
+    }
 }
@@ -0,0 +1,241 @@
+use std::borrow::Cow;
+
+use options::{CharFilter, Options};
+#[cfg(feature = "html")]
+use html::strip_html;
+#[cfg(feature = "normalization")]
+use options::Normalization;
+#[cfg(feature = "normalization")]
+use unicode_normalization::{is_nfc, is_nfkc, UnicodeNormalization};
+#[cfg(feature = "segmentation")]
+use unicode_segmentation::UnicodeSegmentation;
+
+// Prepare a text for detection, as it's requested by the options.
+pub(crate) fn preprocess<'a>(text: &'a str, options: &Options) -> Cow<'a, str> {
+    let text = match options.max_chars {
+        Some(max_chars) => truncate_chars(text, max_chars),
+        None => text
+    };
+    let mut text = Cow::Borrowed(text);
+    #[cfg(feature = "html")]
+    {
+        if options.strip_html {
+            text = Cow::Owned(strip_html(&text));
+        }
+    }
+    if options.filter_social_tokens {
+        text = Cow::Owned(filter_social_tokens(&text));
+    }
+    #[cfg(feature = "normalization")]
+    {
+        if let Some(normalization) = options.normalization {
+            text = normalize(text, normalization);
+        }
+    }
+    #[cfg(feature = "segmentation")]
+    {
+        if options.segment_words {
+            text = Cow::Owned(segment_words(&text));
+        }
+    }
+    if let Some(CharFilter(filter)) = options.char_filter {
+        if text.chars().any(filter) {
+            text = Cow::Owned(text.chars().map(|ch| if filter(ch) { ' ' } else { ch }).collect());
+        }
+    }
+    if let Some(max_length) = options.max_word_length {
+        if let Some(truncated) = truncate_words(&text, max_length) {
+            text = Cow::Owned(truncated);
+        }
+    }
+    text
+}
+
+// The first `max_chars` chars of the text.
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text
+    }
+}
+
+// Drop chars of words (runs of non-whitespace chars) after the first `max_length` ones.
+// Returns None if there are no longer words, so the text is not copied.
+fn truncate_words(text: &str, max_length: usize) -> Option<String> {
+    let mut result: Option<String> = None;
+    let mut length = 0;
+    for (pos, ch) in text.char_indices() {
+        length = if ch.is_whitespace() { 0 } else { length + 1 };
+        if length > max_length {
+            result.get_or_insert_with(|| text[..pos].to_string());
+        } else if let Some(ref mut result) = result {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+// Texts are mostly normalized already, so they are checked first to avoid copying.
+#[cfg(feature = "normalization")]
+fn normalize(text: Cow<'_, str>, normalization: Normalization) -> Cow<'_, str> {
+    match normalization {
+        Normalization::Nfc if !is_nfc(&text) => Cow::Owned(text.nfc().collect()),
+        Normalization::Nfkc if !is_nfkc(&text) => Cow::Owned(text.nfkc().collect()),
+        _ => text
+    }
+}
+
+// Separate words by spaces and replace everything between them with spaces, by Unicode
+// word boundaries. Segments without letters or digits (punctuation, symbols, whitespace)
+// are not words. Every Han ideograph is a word of its own.
+#[cfg(feature = "segmentation")]
+fn segment_words(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut after_word = false;
+    for segment in text.split_word_bounds() {
+        let is_word = segment.chars().any(char::is_alphanumeric);
+        if is_word {
+            if after_word {
+                result.push(' ');
+            }
+            result.push_str(segment);
+        } else {
+            result.push(' ');
+        }
+        after_word = is_word;
+    }
+    result
+}
+
+// Replace URLs, email addresses, hashtags and @mentions with spaces.
+// Such tokens are mostly in English or are not words at all.
+fn filter_social_tokens(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut token_start = None;
+    for (pos, ch) in text.char_indices() {
+        match (ch.is_whitespace(), token_start) {
+            (true, Some(start)) => {
+                push_token(&mut result, &text[start..pos]);
+                token_start = None;
+                result.push(ch);
+            }
+            (true, None) => result.push(ch),
+            (false, None) => token_start = Some(pos),
+            (false, Some(_)) => {}
+        }
+    }
+    if let Some(start) = token_start {
+        push_token(&mut result, &text[start..]);
+    }
+    result
+}
+
+fn push_token(result: &mut String, token: &str) {
+    if is_social_token(token) {
+        result.push(' ');
+    } else {
+        result.push_str(token);
+    }
+}
+
+fn is_social_token(token: &str) -> bool {
+    // Leading punctuation, e.g. "(#tag" or "\"@user"
+    let token = token.trim_start_matches(|ch: char| ch.is_ascii_punctuation() && ch != '#' && ch != '@');
+    let lowercase = token.to_lowercase();
+
+    if token.starts_with('#') || token.starts_with('@') {
+        return token.chars().nth(1).map_or(false, char::is_alphanumeric);
+    }
+    if lowercase.contains("://") || lowercase.starts_with("www.") {
+        return true;
+    }
+    // Email address: a name and a domain with a dot
+    match token.find('@') {
+        Some(at) => at > 0 && token[at + 1..].trim_end_matches(|ch: char| ch.is_ascii_punctuation()).contains('.'),
+        None => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_social_token() {
+        for &token in ["#tbt", "@user_1", "(@user)", "https://example.com/a?b=c", "HTTP://EXAMPLE.COM",
+                       "www.example.com", "john.doe@example.com", "<john@example.org>,", "#café"].iter() {
+            assert!(is_social_token(token), "{}", token);
+        }
+        for &token in ["#", "@", "# 1", "word", "C#", "e.g.", "user@", "a@b", "10:30", "café"].iter() {
+            assert!(!is_social_token(token), "{}", token);
+        }
+    }
+
+    #[test]
+    fn test_filter_social_tokens() {
+        assert_eq!(filter_social_tokens(""), "");
+        assert_eq!(
+            filter_social_tokens("Merci @marie ! Voir https://t.co/x #vacances\ncontact@example.fr"),
+            "Merci   ! Voir    \n "
+        );
+    }
+
+    #[test]
+    fn test_preprocess_with_char_filter() {
+        let options = Options::new().set_char_filter(|ch| ch == '_' || ch.is_ascii_uppercase());
+        assert_eq!(preprocess("MAX_SIZE exceeded", &options), "         exceeded");
+        assert!(match preprocess("nothing to filter", &options) {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false
+        });
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("", 3), "");
+        assert_eq!(truncate_chars("été là", 3), "été");
+        assert_eq!(truncate_chars("été là", 0), "");
+        assert_eq!(truncate_chars("été", 10), "été");
+    }
+
+    #[test]
+    fn test_truncate_words() {
+        assert_eq!(truncate_words("short words only", 5), None);
+        assert_eq!(truncate_words("a looooong\nword\ttoo", 4), Some("a looo\nword\ttoo".to_string()));
+        assert_eq!(truncate_words("été\u{301}\u{301}\u{301}", 4), Some("été\u{301}".to_string()));
+        assert_eq!(truncate_words("abc", 0), Some("".to_string()));
+    }
+
+    #[test]
+    fn test_preprocess_with_limits() {
+        let options = Options::new().set_max_chars(20).set_max_word_length(5);
+        assert_eq!(preprocess("Donaudampfschifffahrt auf der Donau", &options), "Donau");
+        assert_eq!(preprocess("ab cdefghij klmnopqrstuvwxyz", &options), "ab cdefg klmno");
+        assert!(match preprocess("Die Donau", &options) {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false
+        });
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_normalize() {
+        let nfd = "e\u{301}te\u{301} \u{FB01}n";
+        assert_eq!(normalize(Cow::Borrowed(nfd), Normalization::Nfc), "été \u{FB01}n");
+        assert_eq!(normalize(Cow::Borrowed(nfd), Normalization::Nfkc), "été fin");
+        assert!(match normalize(Cow::Borrowed("été"), Normalization::Nfc) {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false
+        });
+    }
+
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn test_segment_words() {
+        assert_eq!(segment_words(""), "");
+        assert_eq!(segment_words("Hello，world！"), "Hello world ");
+        assert_eq!(segment_words("iPhone手机"), "iPhone 手 机");
+        assert_eq!(segment_words("don't stop"), "don't stop");
+        assert_eq!(segment_words("«Ça va?»"), " Ça va  ");
+    }
+}
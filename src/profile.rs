@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+
+use lang::{Lang, LangProfile, LangProfileList};
+use custom_lang::CustomLang;
+use script::Script;
+use options::{NgramOrder, Options};
+use model::{LanguageModel, Ranking, candidate_profiles, detect_custom_lang, detect_lang_by_profiles, resolve_close_langs, romanized_profiles,
+            script_profiles};
+use trigrams::Scratch;
+use markers::count_marker_words;
+use train::train_profile;
+
+// The first bytes of the binary format, followed by the version of the format.
+const MAGIC: &[u8] = b"WLPF";
+const FORMAT_VERSION: u8 = 1;
+
+// Metadata entry, which marks romanized profiles.
+const ROMANIZED_KEY: &str = "romanized";
+
+// Strings longer than this are rejected while reading, so a broken file does not
+// make the reader allocate gigabytes.
+const MAX_STRING_SIZE: u32 = 1 << 20;
+
+/// N-gram profile of a language: the most common n-grams of texts in the language,
+/// ranked from the most common one, with arbitrary metadata (e.g. the source of texts).
+///
+/// Profiles are the common format of the built-in profiles, of profiles trained on
+/// custom corpora and of third-party collections. With `serde` feature a profile can be
+/// saved with any serde format (e.g. JSON), and without it in a compact binary format
+/// with [write_to](#method.write_to) and [read_from](#method.read_from).
+///
+/// # Example
+/// ```
+/// use whatlang::{LanguageProfile, Lang, Script};
+///
+/// let profile = LanguageProfile::train(Lang::Epo, Script::Latin, &["Ĉu vi ne volas eklerni Esperanton?"])
+///     .set_metadata("source", "example");
+///
+/// let mut bytes = Vec::new();
+/// profile.write_to(&mut bytes).unwrap();
+/// assert_eq!(LanguageProfile::read_from(&bytes[..]).unwrap(), profile);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LanguageProfile {
+    lang: Lang,
+    script: Script,
+    ngram_order: NgramOrder,
+    ngrams: Vec<String>,
+    metadata: BTreeMap<String, String>
+}
+
+impl LanguageProfile {
+    /// A profile of the given n-grams, which must be ranked from the most common one.
+    pub fn new(lang: Lang, script: Script, ngram_order: NgramOrder, ngrams: Vec<String>) -> Self {
+        LanguageProfile { lang, script, ngram_order, ngrams, metadata: BTreeMap::new() }
+    }
+
+    /// A trigram profile trained on texts, see [train_profile](fn.train_profile.html).
+    pub fn train<I, S>(lang: Lang, script: Script, texts: I) -> Self
+        where I: IntoIterator<Item = S>, S: AsRef<str>
+    {
+        Self::new(lang, script, NgramOrder::Trigrams, train_profile(texts))
+    }
+
+    /// The built-in profile of the language for the script, if there is one.
+    /// Romanized profiles (e.g. Hindi in Latin script) are included, they have
+    /// metadata entry `romanized` set to `true`.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{LanguageProfile, Lang, NgramOrder, Script};
+    ///
+    /// let profile = LanguageProfile::builtin(Lang::Ukr, Script::Cyrillic, NgramOrder::Trigrams).unwrap();
+    /// assert_eq!(profile.ngrams().len(), 300);
+    /// assert_eq!(LanguageProfile::builtin(Lang::Ukr, Script::Latin, NgramOrder::Trigrams), None);
+    /// ```
+    pub fn builtin(lang: Lang, script: Script, ngram_order: NgramOrder) -> Option<Self> {
+        let find = |profiles: LangProfileList| profiles.iter().find(|&&(profile_lang, _)| profile_lang == lang);
+        let to_profile = |&(_, ngrams): &(Lang, LangProfile)| {
+            Self::new(lang, script, ngram_order, ngrams.iter().map(|&ngram| ngram.to_string()).collect())
+        };
+
+        if let Some(profile) = find(script_profiles(script, ngram_order)) {
+            return Some(to_profile(profile));
+        }
+        match find(romanized_profiles(ngram_order)) {
+            Some(profile) if script == Script::Latin => Some(to_profile(profile).set_metadata(ROMANIZED_KEY, "true")),
+            _ => None
+        }
+    }
+
+    /// All the built-in profiles of all n-gram orders, see [builtin](#method.builtin).
+    pub fn builtin_all() -> Vec<Self> {
+        let orders = [NgramOrder::Bigrams, NgramOrder::Trigrams, NgramOrder::Fourgrams];
+        let mut profiles = Vec::new();
+        for &order in orders.iter() {
+            for &script in Script::all() {
+                for &(lang, _) in script_profiles(script, order) {
+                    profiles.extend(Self::builtin(lang, script, order));
+                }
+            }
+            for &(lang, _) in romanized_profiles(order) {
+                profiles.extend(Self::builtin(lang, Script::Latin, order));
+            }
+        }
+        profiles
+    }
+
+    // Is it a profile of a language written in Latin script instead of its native one?
+    pub(crate) fn is_romanized(&self) -> bool {
+        self.metadata.get(ROMANIZED_KEY).map_or(false, |value| value == "true")
+    }
+
+    /// Add a metadata entry, e.g. a source or a license of texts.
+    pub fn set_metadata<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    pub fn script(&self) -> Script {
+        self.script
+    }
+
+    pub fn ngram_order(&self) -> NgramOrder {
+        self.ngram_order
+    }
+
+    /// N-grams ranked from the most common one.
+    pub fn ngrams(&self) -> &[String] {
+        &self.ngrams
+    }
+
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    fn is_replaced_by(&self, other: &LanguageProfile) -> bool {
+        self.lang == other.lang && self.script == other.script && self.ngram_order == other.ngram_order
+    }
+
+    /// Write the profile in the compact binary format: a header, codes of the language
+    /// and the script, the n-gram size, n-grams and metadata. Strings are UTF-8
+    /// prefixed by their length, numbers are little endian.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION, self.ngram_order.size() as u8])?;
+        write_str(&mut writer, self.lang.code())?;
+        write_str(&mut writer, self.script.code())?;
+
+        write_u32(&mut writer, self.ngrams.len() as u32)?;
+        for ngram in &self.ngrams {
+            write_str(&mut writer, ngram)?;
+        }
+        write_u32(&mut writer, self.metadata.len() as u32)?;
+        for (key, value) in &self.metadata {
+            write_str(&mut writer, key)?;
+            write_str(&mut writer, value)?;
+        }
+        Ok(())
+    }
+
+    /// Read a profile written by [write_to](#method.write_to).
+    /// Returns an error of kind `InvalidData` if the data is not a valid profile.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data("not a language profile"));
+        }
+        if header[4] != FORMAT_VERSION {
+            return Err(invalid_data("unsupported version of a language profile"));
+        }
+        let ngram_order = match header[5] {
+            2 => NgramOrder::Bigrams,
+            3 => NgramOrder::Trigrams,
+            4 => NgramOrder::Fourgrams,
+            _ => return Err(invalid_data("unsupported n-gram size"))
+        };
+        let lang = Lang::from_code(read_string(&mut reader)?).ok_or_else(|| invalid_data("unknown language"))?;
+        let script = Script::from_code(read_string(&mut reader)?).ok_or_else(|| invalid_data("unknown script"))?;
+
+        let ngram_count = read_u32(&mut reader)?;
+        let mut ngrams = Vec::new();
+        for _ in 0..ngram_count {
+            ngrams.push(read_string(&mut reader)?);
+        }
+        let mut profile = Self::new(lang, script, ngram_order, ngrams);
+        for _ in 0..read_u32(&mut reader)? {
+            let key = read_string(&mut reader)?;
+            profile.metadata.insert(key, read_string(&mut reader)?);
+        }
+        Ok(profile)
+    }
+}
+
+/// A [LanguageModel](trait.LanguageModel.html), which works like [TrigramModel](struct.TrigramModel.html),
+/// but with profiles loaded at runtime in addition to the built-in ones or instead of them.
+///
+/// A profile replaces the built-in profile of the same language, script and n-gram order,
+/// otherwise it adds the language to candidates of its script. Scripts, which are used only
+/// by one language (e.g. Greek), are not affected by profiles.
+///
+/// # Example
+/// ```
+/// use whatlang::{Detector, Lang, LanguageProfile, Options, ProfileModel, Script};
+///
+/// // Greek written in Latin letters, usually the profile would be loaded from a file with `load_profile`
+/// let greeklish = LanguageProfile::train(Lang::Ell, Script::Latin, &[
+///     "Kalimera, ti kaneis? Eimai kala, efharisto poli. Ti tha kaneis simera to vrady?",
+///     "Pame gia kafe avrio to proi? Exo poli douleia alla tha vro ligo xrono gia sena.",
+/// ]);
+/// let model = ProfileModel::new().add_profile(greeklish);
+/// let detector = Detector::with_model(model, Options::new());
+/// assert_eq!(detector.detect_lang("Ti kaneis re file? Pame gia kafe simera to vrady?"), Some(Lang::Ell));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileModel {
+    profiles: Vec<LanguageProfile>,
+    custom_profiles: Vec<CustomProfile>,
+    builtin: bool
+}
+
+// A profile of a language, which is not in Lang.
+#[derive(Debug, Clone, PartialEq)]
+struct CustomProfile {
+    lang: CustomLang,
+    script: Script,
+    ngram_order: NgramOrder,
+    ngrams: Vec<String>
+}
+
+impl Default for ProfileModel {
+    fn default() -> Self {
+        ProfileModel { profiles: vec![], custom_profiles: vec![], builtin: true }
+    }
+}
+
+impl ProfileModel {
+    /// A model with the built-in profiles only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the profile, replacing a previously added one of the same language, script and n-gram order.
+    pub fn add_profile(mut self, profile: LanguageProfile) -> Self {
+        self.profiles.retain(|other| !other.is_replaced_by(&profile));
+        self.profiles.push(profile);
+        self
+    }
+
+    /// Add a profile from a file written by [LanguageProfile::write_to](struct.LanguageProfile.html#method.write_to).
+    pub fn load_profile<P: AsRef<Path>>(self, path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let profile = LanguageProfile::read_from(BufReader::new(file))?;
+        Ok(self.add_profile(profile))
+    }
+
+    /// Add a profile of a custom language, which is not in [Lang](enum.Lang.html), replacing
+    /// a previously added one of the same language, script and n-gram order. A text is detected
+    /// as a custom language, when its profile matches the text better than the profile of
+    /// the language detected among the built-in ones, see [Info::custom_lang](struct.Info.html#method.custom_lang).
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{train_profile, CustomLang, Detector, NgramOrder, Options, ProfileModel, Script};
+    ///
+    /// let wolof = CustomLang::new("wol");
+    /// let ngrams = train_profile(&[
+    ///     "Nanga def? Maa ngi fi rekk, jërëjëf. Naka sa waa kër? Ñu ngi fa.",
+    ///     "Dama bëgg lekk ceebu jën tey. Ndax dangay dem ca marse ba?",
+    /// ]);
+    /// let model = ProfileModel::new().add_custom_profile(wolof, Script::Latin, NgramOrder::Trigrams, ngrams);
+    /// let detector = Detector::with_model(model, Options::new());
+    /// let info = detector.detect("Nanga def? Dama bëgg dem ca marse ba tey.").unwrap();
+    /// assert_eq!(info.custom_lang(), Some(wolof));
+    /// ```
+    pub fn add_custom_profile(mut self, lang: CustomLang, script: Script, ngram_order: NgramOrder, ngrams: Vec<String>) -> Self {
+        self.custom_profiles.retain(|other| !(other.lang == lang && other.script == script && other.ngram_order == ngram_order));
+        self.custom_profiles.push(CustomProfile { lang, script, ngram_order, ngrams });
+        self
+    }
+
+    /// Use the built-in profiles for languages without added profiles. Enabled by default,
+    /// when disabled only the added profiles are used.
+    pub fn set_builtin(mut self, builtin: bool) -> Self {
+        self.builtin = builtin;
+        self
+    }
+
+    /// Added profiles.
+    pub fn profiles(&self) -> &[LanguageProfile] {
+        &self.profiles
+    }
+
+    // Profiles of languages of the script for n-grams of the order from options.
+    fn script_profiles(&self, script: Script, options: &Options) -> Vec<(Lang, Vec<&str>)> {
+        let added = self.profiles.iter()
+            .filter(|profile| profile.script == script && profile.ngram_order == options.ngram_order);
+
+        let mut profiles: Vec<(Lang, Vec<&str>)> = vec![];
+        if self.builtin {
+            profiles.extend(candidate_profiles(script, options)
+                .filter(|&&(lang, _)| !added.clone().any(|profile| profile.lang == lang))
+                .map(|&(lang, ngrams)| (lang, ngrams.to_vec())));
+        }
+        profiles.extend(added.map(|profile| (profile.lang, profile.ngrams.iter().map(String::as_str).collect())));
+        profiles
+    }
+}
+
+impl LanguageModel for ProfileModel {
+    fn detect_lang(&self, text: &str, script: Script, candidates: &[Lang], options: &Options) -> Option<(Lang, f64)> {
+        self.detect_lang_in(text, script, candidates, options, &mut Scratch::new())
+    }
+
+    fn detect_lang_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options, scratch: &mut Scratch) -> Option<(Lang, f64)> {
+        self.detect_lang_with_second_best_in(text, script, candidates, options, scratch)
+            .map(|(lang, confidence, _)| (lang, confidence))
+    }
+
+    fn detect_lang_with_second_best_in(&self, text: &str, script: Script, candidates: &[Lang], options: &Options,
+                                       scratch: &mut Scratch) -> Option<Ranking> {
+        let profiles = self.script_profiles(script, options);
+        let profiles: Vec<(Lang, &[&str])> = profiles.iter().map(|&(lang, ref ngrams)| (lang, &ngrams[..])).collect();
+        let trigrams = scratch.ngrams_with_positions(text, options.ngram_order.size());
+        let markers = count_marker_words(text, options);
+        detect_lang_by_profiles(trigrams, &markers, &profiles, script, candidates, options,
+                                |lang, rivals| resolve_close_langs(text, lang, rivals))
+    }
+
+    fn script_langs(&self, script: Script, options: &Options) -> Vec<Lang> {
+        self.script_profiles(script, options).into_iter().map(|(lang, _)| lang).collect()
+    }
+
+    fn detect_custom_lang_in(&self, text: &str, script: Script, lang: Lang, options: &Options,
+                             scratch: &mut Scratch) -> Option<(CustomLang, f64)> {
+        let custom_profiles: Vec<(CustomLang, Vec<&str>)> = self.custom_profiles.iter()
+            .filter(|profile| profile.script == script && profile.ngram_order == options.ngram_order)
+            .map(|profile| (profile.lang, profile.ngrams.iter().map(String::as_str).collect()))
+            .collect();
+        if custom_profiles.is_empty() {
+            return None;
+        }
+        let custom_profiles: Vec<(CustomLang, &[&str])> = custom_profiles.iter().map(|&(lang, ref ngrams)| (lang, &ngrams[..])).collect();
+        let profiles = self.script_profiles(script, options);
+        let lang_profile = profiles.iter().find(|&&(other, _)| other == lang).map(|(_, ngrams)| &ngrams[..]);
+        let trigrams = scratch.ngrams_with_positions(text, options.ngram_order.size());
+        detect_custom_lang(trigrams, lang_profile, &custom_profiles, options)
+    }
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+fn write_str<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+    write_u32(writer, value.len() as u32)?;
+    writer.write_all(value.as_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let size = read_u32(reader)?;
+    if size > MAX_STRING_SIZE {
+        return Err(invalid_data("string is too long"));
+    }
+    let mut bytes = vec![0; size as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| invalid_data("string is not valid UTF-8"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::TrigramModel;
+    use detector::Detector;
+
+    fn sample_profile() -> LanguageProfile {
+        LanguageProfile::builtin(Lang::Deu, Script::Latin, NgramOrder::Trigrams).unwrap()
+            .set_metadata("source", "built-in")
+            .set_metadata("license", "MIT")
+    }
+
+    #[test]
+    fn test_binary_format_round_trip() {
+        let profile = sample_profile();
+        let mut bytes = Vec::new();
+        profile.write_to(&mut bytes).unwrap();
+        assert_eq!(LanguageProfile::read_from(&bytes[..]).unwrap(), profile);
+
+        let empty = LanguageProfile::new(Lang::Tir, Script::Ethiopic, NgramOrder::Bigrams, vec![]);
+        let mut bytes = Vec::new();
+        empty.write_to(&mut bytes).unwrap();
+        assert_eq!(LanguageProfile::read_from(&bytes[..]).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_read_invalid_data() {
+        let mut bytes = Vec::new();
+        sample_profile().write_to(&mut bytes).unwrap();
+
+        let kind = |bytes: &[u8]| LanguageProfile::read_from(bytes).unwrap_err().kind();
+        assert_eq!(kind(b"JUNK\x01\x03"), io::ErrorKind::InvalidData);
+        assert_eq!(kind(b"WLPF\x02\x03"), io::ErrorKind::InvalidData);
+        assert_eq!(kind(b"WLPF\x01\x07"), io::ErrorKind::InvalidData);
+        assert_eq!(kind(&bytes[..bytes.len() - 1]), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_builtin() {
+        let profile = LanguageProfile::builtin(Lang::Eng, Script::Latin, NgramOrder::Bigrams).unwrap();
+        assert_eq!(profile.lang(), Lang::Eng);
+        assert_eq!(profile.ngram_order(), NgramOrder::Bigrams);
+        assert!(profile.ngrams().iter().all(|ngram| ngram.chars().count() == 2));
+        assert!(profile.metadata().is_empty());
+        let romanized = LanguageProfile::builtin(Lang::Arb, Script::Latin, NgramOrder::Trigrams).unwrap();
+        assert!(romanized.is_romanized());
+        assert!(!profile.is_romanized());
+        assert_eq!(LanguageProfile::builtin(Lang::Cmn, Script::Mandarin, NgramOrder::Trigrams), None);
+    }
+
+    #[test]
+    fn test_profile_model_replaces_builtin_profiles() {
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.";
+        let options = Options::new();
+        let german = LanguageProfile::builtin(Lang::Deu, Script::Latin, NgramOrder::Trigrams).unwrap();
+        let model = ProfileModel::new().add_profile(LanguageProfile { lang: Lang::Eng, ..german.clone() });
+
+        let mut langs = model.script_langs(Script::Latin, &options);
+        assert_eq!(langs.iter().filter(|&&lang| lang == Lang::Eng).count(), 1);
+        langs.retain(|&lang| lang != Lang::Deu);
+        assert_eq!(model.detect_lang(text, Script::Latin, &langs, &options).map(|(lang, _)| lang), Some(Lang::Eng));
+
+        // Only added profiles
+        let model = model.set_builtin(false).add_profile(german);
+        assert_eq!(model.script_langs(Script::Latin, &options), vec![Lang::Eng, Lang::Deu]);
+        assert_eq!(model.script_langs(Script::Cyrillic, &options), vec![]);
+    }
+
+    #[test]
+    fn test_profile_model_detects_custom_langs() {
+        let codes = CustomLang::new("product-codes");
+        let ngrams = train_profile(&["zkx qvx zkq xqv kzx vqz zxq qzk xkv zqx kvq", "qvz xzk zqk kxq vzx qkz xvq"]);
+        let model = ProfileModel::new()
+            .add_custom_profile(codes, Script::Latin, NgramOrder::Trigrams, vec!["a".to_string()])
+            .add_custom_profile(codes, Script::Latin, NgramOrder::Trigrams, ngrams);
+        assert_eq!(model.custom_profiles.len(), 1);
+        let detector = Detector::with_model(model, Options::new());
+
+        let info = detector.detect("zkx qvz kzx zqk xqv vzx").unwrap();
+        assert_eq!(info.custom_lang(), Some(codes));
+        assert_eq!(info.to_bcp47(), "product-codes");
+
+        let info = detector.detect("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.").unwrap();
+        assert_eq!(info.lang(), Lang::Deu);
+        assert_eq!(info.custom_lang(), None);
+
+        // Custom languages are not detected in other scripts
+        let info = detector.detect("Летом дети играли в саду каждый день.").unwrap();
+        assert_eq!(info.custom_lang(), None);
+    }
+
+    #[test]
+    fn test_profile_model_without_added_profiles() {
+        let text = "Та нічого, все нормально. А в тебе як?";
+        let options = Options::new().set_romanized(true);
+        let candidates = [Lang::Ukr, Lang::Rus, Lang::Bel];
+        assert_eq!(ProfileModel::new().detect_lang(text, Script::Cyrillic, &candidates, &options),
+                   TrigramModel.detect_lang(text, Script::Cyrillic, &candidates, &options));
+        assert_eq!(ProfileModel::new().script_langs(Script::Latin, &options),
+                   TrigramModel.script_langs(Script::Latin, &options));
+    }
+
+    #[test]
+    fn test_load_profile() {
+        let path = ::std::env::temp_dir().join(format!("whatlang-profile-{}.wlp", ::std::process::id()));
+        let profile = LanguageProfile::train(Lang::Ell, Script::Latin, &["Kalimera, ti kaneis?"]);
+        profile.write_to(File::create(&path).unwrap()).unwrap();
+        let model = ProfileModel::new().load_profile(&path);
+        ::std::fs::remove_file(&path).unwrap();
+        assert_eq!(model.unwrap().profiles(), &[profile]);
+
+        assert!(ProfileModel::new().load_profile(&path).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        extern crate serde_json;
+
+        let profile = sample_profile();
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<LanguageProfile>(&json).unwrap(), profile);
+    }
+}
@@ -0,0 +1,39 @@
+//! Read-only access to the built-in trigram profiles, e.g. to visualize them or to port them
+//! to another runtime. A profile lists 300 most common trigrams of a language, the most common
+//! one goes first. Spaces stand for word boundaries, text is lowercased.
+//! Only the compiled in profiles are available, see `langs-*` features.
+//!
+//! # Example
+//! ```
+//! use whatlang::raw_profiles::{LATIN_ENG, PROFILES};
+//! use whatlang::{Lang, Script};
+//!
+//! assert_eq!(&LATIN_ENG[..3], &[" th", "the", " an"]);
+//! assert!(PROFILES.contains(&(Lang::Eng, Script::Latin, LATIN_ENG)));
+//! ```
+
+use lang::Lang;
+use script::Script;
+
+include!(concat!(env!("OUT_DIR"), "/raw_profiles.rs"));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use model::{romanized_profiles, script_profiles};
+    use options::NgramOrder;
+
+    #[test]
+    fn test_raw_profiles_match_decoded_ones() {
+        let decoded: Vec<(Lang, Script, &[&str])> = Script::all().iter()
+            .flat_map(|&script| script_profiles(script, NgramOrder::Trigrams).iter().map(move |&(lang, profile)| (lang, script, profile)))
+            .collect();
+        assert_eq!(PROFILES.len(), decoded.len());
+        for profile in decoded {
+            assert!(PROFILES.contains(&profile), "{:?}", profile);
+        }
+        let romanized: Vec<(Lang, &[&str])> = romanized_profiles(NgramOrder::Trigrams).to_vec();
+        assert_eq!(ROMANIZED_PROFILES.to_vec(), romanized);
+        assert!(PROFILES.iter().all(|&(_, _, profile)| profile.len() == 300));
+    }
+}
@@ -0,0 +1,162 @@
+use std::char::REPLACEMENT_CHARACTER;
+use std::io::{self, Read};
+use std::str;
+
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+// Size of chunks, which are read from a reader.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+// Reading stops after this amount of text (in bytes), it's enough to detect
+// a language reliably, while big files are not loaded into memory.
+const MAX_TEXT_SIZE: usize = 1024 * 1024;
+
+/// Detect a language and a script by a text, read from the given reader.
+///
+/// The text is read in chunks and is expected to be UTF-8, invalid sequences are
+/// replaced with `U+FFFD`. Reading stops as soon as the result is reliable
+/// or 1 MiB of text is read, so big files are not loaded into memory.
+///
+/// # Example
+/// ```
+/// use std::io::Cursor;
+/// use whatlang::{detect_from_reader, Lang};
+///
+/// let reader = Cursor::new("Ĉu vi ne volas eklerni Esperanton? Bonvolu!");
+/// let info = detect_from_reader(reader).unwrap().unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+/// ```
+pub fn detect_from_reader<R: Read>(reader: R) -> io::Result<Option<Info>> {
+    detect_from_reader_with_options(reader, &Options::default())
+}
+
+/// Detect a language and a script by a text, read from the given reader, using the given options.
+///
+/// See [`detect_from_reader`](fn.detect_from_reader.html) for details.
+pub fn detect_from_reader_with_options<R: Read>(mut reader: R, options: &Options) -> io::Result<Option<Info>> {
+    let mut chunk = [0; CHUNK_SIZE];
+    let mut bytes = Vec::with_capacity(CHUNK_SIZE);
+    let mut text = String::new();
+    let mut next_check = CHUNK_SIZE;
+
+    loop {
+        let len = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err)
+        };
+        bytes.extend_from_slice(&chunk[..len]);
+        push_utf8(&mut bytes, &mut text);
+
+        if text.len() >= MAX_TEXT_SIZE {
+            break;
+        }
+        // Texts are detected at exponentially growing sizes, so the total work stays linear.
+        if text.len() >= next_check {
+            if let Some(info) = detect_with_options(&text, options) {
+                if info.is_reliable() {
+                    return Ok(Some(info));
+                }
+            }
+            next_check *= 2;
+        }
+    }
+
+    // The stream ended in the middle of a char
+    if !bytes.is_empty() {
+        text.push(REPLACEMENT_CHARACTER);
+    }
+    Ok(detect_with_options(&text, options))
+}
+
+// Move the decoded bytes to the text. An incomplete char at the end of the bytes
+// is kept, since its rest may come with the next chunk.
+fn push_utf8(bytes: &mut Vec<u8>, text: &mut String) {
+    let mut start = 0;
+    while start < bytes.len() {
+        match str::from_utf8(&bytes[start..]) {
+            Ok(valid) => {
+                text.push_str(valid);
+                start = bytes.len();
+            }
+            Err(err) => {
+                let valid_end = start + err.valid_up_to();
+                text.push_str(&String::from_utf8_lossy(&bytes[start..valid_end]));
+                match err.error_len() {
+                    Some(len) => {
+                        text.push(REPLACEMENT_CHARACTER);
+                        start = valid_end + len;
+                    }
+                    None => {
+                        start = valid_end;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    bytes.drain(..start);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use lang::Lang;
+
+    // Reader, which returns a single byte per read
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl<'a> Read for ByteReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_push_utf8() {
+        let mut text = String::new();
+        let mut bytes = b"ab\xD0".to_vec();
+        push_utf8(&mut bytes, &mut text);
+        assert_eq!(text, "ab");
+        assert_eq!(bytes, b"\xD0");
+
+        bytes.extend_from_slice(b"\x96\xFFc");
+        push_utf8(&mut bytes, &mut text);
+        assert_eq!(text, "abЖ\u{FFFD}c");
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn test_detect_from_reader_with_split_chars() {
+        let text = "Введите запрос, чтобы найти нужную информацию";
+        let info = detect_from_reader(ByteReader(text.as_bytes())).unwrap().unwrap();
+        assert_eq!(info.lang(), Lang::Rus);
+        assert!(detect_from_reader(ByteReader(b"")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_detect_from_reader_stops_early() {
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(10_000);
+        let mut reader = Cursor::new(text.as_bytes());
+        let info = detect_from_reader(&mut reader).unwrap().unwrap();
+        assert_eq!(info.lang(), Lang::Deu);
+        assert!(reader.position() < text.len() as u64);
+    }
+
+    #[test]
+    fn test_detect_from_reader_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Eng]);
+        let reader = Cursor::new("Der Sommer war sehr heiß");
+        let info = detect_from_reader_with_options(reader, &options).unwrap().unwrap();
+        assert_eq!(info.lang(), Lang::Eng);
+    }
+}
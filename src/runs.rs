@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use script::Script;
+
+/// Split a text into runs of consecutive chars of the same script.
+/// Chars without a script (whitespaces, digits, punctuation, etc) belong to the preceding run,
+/// chars at the beginning of the text belong to the first run.
+/// So the runs cover the whole text, unless the text has no chars of any script.
+///
+/// # Example
+/// ```
+/// use whatlang::{script_runs, Script};
+///
+/// let text = "Привет, world! Как дела?";
+/// let runs: Vec<_> = script_runs(text).collect();
+/// assert_eq!(runs, vec![
+///     (0..14, Script::Cyrillic),
+///     (14..21, Script::Latin),
+///     (21..37, Script::Cyrillic),
+/// ]);
+/// assert_eq!(&text[runs[1].0.clone()], "world! ");
+/// ```
+pub fn script_runs(text: &str) -> ScriptRuns<'_> {
+    ScriptRuns { text, pos: 0 }
+}
+
+/// An iterator over byte ranges of script runs in a text, created by [script_runs](fn.script_runs.html).
+#[derive(Debug, Clone)]
+pub struct ScriptRuns<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for ScriptRuns<'a> {
+    type Item = (Range<usize>, Script);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.pos;
+        let mut run_script: Option<Script> = None;
+
+        for (offset, ch) in self.text[start..].char_indices() {
+            if let Some(script) = Script::from_char(ch) {
+                match run_script {
+                    None => run_script = Some(script),
+                    Some(current) if current != script => {
+                        self.pos = start + offset;
+                        return Some((start..self.pos, current));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        self.pos = self.text.len();
+        run_script.map(|script| (start..self.pos, script))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(&str, Script)> {
+        script_runs(text).map(|(range, script)| (&text[range], script)).collect()
+    }
+
+    #[test]
+    fn test_script_runs() {
+        assert_eq!(runs(""), vec![]);
+        assert_eq!(runs("1234, 5678!"), vec![]);
+        assert_eq!(runs("Hello world"), vec![("Hello world", Script::Latin)]);
+        assert_eq!(
+            runs("Russian word любовь means love."),
+            vec![
+                ("Russian word ", Script::Latin),
+                ("любовь ", Script::Cyrillic),
+                ("means love.", Script::Latin)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_runs_with_leading_and_trailing_chars() {
+        assert_eq!(
+            runs("  «שלום» — hello!!  "),
+            vec![("  «שלום» — ", Script::Hebrew), ("hello!!  ", Script::Latin)]
+        );
+    }
+}
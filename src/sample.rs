@@ -0,0 +1,94 @@
+use std::borrow::Cow;
+
+// Number of the parts a sample consists of: start, middle and end of the text.
+const SAMPLE_PARTS: usize = 3;
+
+// Take approximately `size` bytes from the start, the middle and the end of the text.
+// Parts are cut on char boundaries, and partial words at the edges of the parts are dropped,
+// so they do not produce trigrams which are not present in the original text.
+// Parts without whitespaces (e.g. Chinese or Thai text) are kept as they are.
+// Texts which fit into `size` are returned as is.
+pub fn sample(text: &str, size: usize) -> Cow<'_, str> {
+    if text.len() <= size {
+        return Cow::Borrowed(text);
+    }
+
+    let part_size = size / SAMPLE_PARTS;
+    let middle = text.len() / 2 - part_size / 2;
+    let starts = [0, middle, text.len() - part_size];
+
+    let mut result = String::with_capacity(size + SAMPLE_PARTS);
+    for &start in starts.iter() {
+        let part = trim_partial_words(text, start, start + part_size);
+        result.push_str(part);
+        result.push(' ');
+    }
+    Cow::Owned(result)
+}
+
+fn trim_partial_words(text: &str, start: usize, end: usize) -> &str {
+    let mut start = ceil_char_boundary(text, start);
+    let mut end = ceil_char_boundary(text, end);
+
+    if start > 0 && !ends_with_whitespace(&text[..start]) {
+        if let Some(pos) = text[start..end].find(char::is_whitespace) {
+            start += pos;
+        }
+    }
+    if end < text.len() && !starts_with_whitespace(&text[end..]) {
+        if let Some(pos) = text[start..end].rfind(char::is_whitespace) {
+            end = start + pos;
+        }
+    }
+    &text[start..end]
+}
+
+fn ceil_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+fn starts_with_whitespace(text: &str) -> bool {
+    text.chars().next().map_or(false, char::is_whitespace)
+}
+
+fn ends_with_whitespace(text: &str) -> bool {
+    text.chars().next_back().map_or(false, char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_short_text() {
+        assert_eq!(sample("Hello world", 11), "Hello world");
+        assert_eq!(sample("", 0), "");
+    }
+
+    #[test]
+    fn test_sample_takes_start_middle_and_end() {
+        let text = "aaa aaa aaa bbb bbb bbb ccc ccc ccc";
+        let words: Vec<String> = sample(text, 12).split_whitespace().map(String::from).collect();
+        assert_eq!(words, vec!["aaa", "bbb", "ccc"]);
+    }
+
+    #[test]
+    fn test_sample_respects_char_boundaries() {
+        let text = "ёжик ёжик ёжик ёжик ёжик ёжик ёжик ёжик ёжик ёжик";
+        for size in 30..text.len() {
+            let result = sample(text, size);
+            for word in result.split_whitespace() {
+                assert_eq!(word, "ёжик");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_text_without_whitespaces() {
+        let text = "県見夜上温国阪題富販県見夜上温国阪題富販";
+        assert_eq!(sample(text, 9), "県 県 販 ");
+    }
+}
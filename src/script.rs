@@ -1,12 +1,25 @@
 use utils::is_stop_char;
+use options::Options;
+use lang::{Lang, profile_langs};
+use detect::script_lang;
+use cjk::HAN_LANGS;
+use std::cmp::{Ordering, Reverse};
+use std::error::Error;
 use std::fmt;
+use std::slice;
+use std::str::FromStr;
 
 /// Represents a writing system (Latin, Cyrillic, Arabic, etc).
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Script {
     // Keep this in alphabetic order (for C bindings)
     Arabic,
+    Armenian,
+    Balinese,
     Bengali,
+    CanadianAboriginal,
+    Cherokee,
     Cyrillic,
     Devanagari,
     Ethiopic,
@@ -17,21 +30,296 @@ pub enum Script {
     Hangul,
     Hebrew,
     Hiragana,
+    Javanese,
     Kannada,
     Katakana,
     Khmer,
+    Lao,
     Latin,
     Malayalam,
     Mandarin,
+    Mongolian,
     Myanmar,
+    Nko,
     Oriya,
     Sinhala,
+    Sundanese,
     Tamil,
     Telugu,
+    Thaana,
     Thai,
+    Tifinagh,
+    // Historic scripts follow the others, so that values of the others don't depend on features
+    #[cfg(feature = "historic-scripts")]
+    Coptic,
+    #[cfg(feature = "historic-scripts")]
+    Glagolitic,
+    #[cfg(feature = "historic-scripts")]
+    Gothic,
+    #[cfg(feature = "historic-scripts")]
+    OldItalic,
+    #[cfg(feature = "historic-scripts")]
+    Phoenician,
+    #[cfg(feature = "historic-scripts")]
+    Runic,
 }
 
+// All scripts in the order of declaration.
+const ALL_SCRIPTS: [Script; Script::COUNT] = [
+    Script::Arabic,
+    Script::Armenian,
+    Script::Balinese,
+    Script::Bengali,
+    Script::CanadianAboriginal,
+    Script::Cherokee,
+    Script::Cyrillic,
+    Script::Devanagari,
+    Script::Ethiopic,
+    Script::Georgian,
+    Script::Greek,
+    Script::Gujarati,
+    Script::Gurmukhi,
+    Script::Hangul,
+    Script::Hebrew,
+    Script::Hiragana,
+    Script::Javanese,
+    Script::Kannada,
+    Script::Katakana,
+    Script::Khmer,
+    Script::Lao,
+    Script::Latin,
+    Script::Malayalam,
+    Script::Mandarin,
+    Script::Mongolian,
+    Script::Myanmar,
+    Script::Nko,
+    Script::Oriya,
+    Script::Sinhala,
+    Script::Sundanese,
+    Script::Tamil,
+    Script::Telugu,
+    Script::Thaana,
+    Script::Thai,
+    Script::Tifinagh,
+    #[cfg(feature = "historic-scripts")]
+    Script::Coptic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Glagolitic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Gothic,
+    #[cfg(feature = "historic-scripts")]
+    Script::OldItalic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Phoenician,
+    #[cfg(feature = "historic-scripts")]
+    Script::Runic,
+];
+
 impl Script {
+    /// Number of supported scripts.
+    #[cfg(not(feature = "historic-scripts"))]
+    pub const COUNT: usize = 35;
+
+    /// Number of supported scripts.
+    #[cfg(feature = "historic-scripts")]
+    pub const COUNT: usize = 41;
+
+    /// Get all supported scripts, in alphabetic order, followed by historic scripts
+    /// with `historic-scripts` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert_eq!(Script::all().len(), Script::COUNT);
+    /// assert_eq!(Script::all()[0], Script::Arabic);
+    /// ```
+    pub fn all() -> &'static [Script] {
+        &ALL_SCRIPTS
+    }
+
+    /// Get a script of a single char, or `None` if the char does not belong to any
+    /// of the supported scripts (e.g. digits, punctuation or emoji).
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert_eq!(Script::from_char('ж'), Some(Script::Cyrillic));
+    /// assert_eq!(Script::from_char('!'), None);
+    /// ```
+    pub fn from_char(ch: char) -> Option<Script> {
+        char_script(ch)
+    }
+
+    /// Check whether the char belongs to the script.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert!(Script::Greek.contains('φ'));
+    /// assert!(!Script::Greek.contains('ф'));
+    /// ```
+    pub fn contains(&self, ch: char) -> bool {
+        char_script(ch) == Some(*self)
+    }
+
+    /// Get enum by [ISO 15924](https://en.wikipedia.org/wiki/ISO_15924) code as a string.
+    /// Codes are case insensitive.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert_eq!(Script::from_code("Cyrl"), Some(Script::Cyrillic));
+    /// assert_eq!(Script::from_code("hani"), Some(Script::Mandarin));
+    /// ```
+    pub fn from_code<S: Into<String>>(code: S) -> Option<Script> {
+        match code.into().to_lowercase().as_ref() {
+            "latn" => Some(Script::Latin),
+            "cyrl" => Some(Script::Cyrillic),
+            "arab" => Some(Script::Arabic),
+            "deva" => Some(Script::Devanagari),
+            "hira" => Some(Script::Hiragana),
+            "kana" => Some(Script::Katakana),
+            "ethi" => Some(Script::Ethiopic),
+            "hebr" => Some(Script::Hebrew),
+            "beng" => Some(Script::Bengali),
+            "geor" => Some(Script::Georgian),
+            "hani" => Some(Script::Mandarin),
+            "hang" => Some(Script::Hangul),
+            "grek" => Some(Script::Greek),
+            "knda" => Some(Script::Kannada),
+            "taml" => Some(Script::Tamil),
+            "thai" => Some(Script::Thai),
+            "gujr" => Some(Script::Gujarati),
+            "guru" => Some(Script::Gurmukhi),
+            "telu" => Some(Script::Telugu),
+            "mlym" => Some(Script::Malayalam),
+            "orya" => Some(Script::Oriya),
+            "mymr" => Some(Script::Myanmar),
+            "sinh" => Some(Script::Sinhala),
+            "khmr" => Some(Script::Khmer),
+            "armn" => Some(Script::Armenian),
+            "mong" => Some(Script::Mongolian),
+            "thaa" => Some(Script::Thaana),
+            "cher" => Some(Script::Cherokee),
+            "cans" => Some(Script::CanadianAboriginal),
+            "nkoo" => Some(Script::Nko),
+            "tfng" => Some(Script::Tifinagh),
+            "java" => Some(Script::Javanese),
+            "bali" => Some(Script::Balinese),
+            "sund" => Some(Script::Sundanese),
+            "laoo" => Some(Script::Lao),
+            #[cfg(feature = "historic-scripts")]
+            "copt" => Some(Script::Coptic),
+            #[cfg(feature = "historic-scripts")]
+            "glag" => Some(Script::Glagolitic),
+            #[cfg(feature = "historic-scripts")]
+            "goth" => Some(Script::Gothic),
+            #[cfg(feature = "historic-scripts")]
+            "ital" => Some(Script::OldItalic),
+            #[cfg(feature = "historic-scripts")]
+            "phnx" => Some(Script::Phoenician),
+            #[cfg(feature = "historic-scripts")]
+            "runr" => Some(Script::Runic),
+            _ => None
+        }
+    }
+
+    /// Get four-letter [ISO 15924](https://en.wikipedia.org/wiki/ISO_15924) code of the script.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert_eq!(Script::Latin.code(), "Latn");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match *self {
+            Script::Latin      => "Latn",
+            Script::Cyrillic   => "Cyrl",
+            Script::Arabic     => "Arab",
+            Script::Devanagari => "Deva",
+            Script::Hiragana   => "Hira",
+            Script::Katakana   => "Kana",
+            Script::Ethiopic   => "Ethi",
+            Script::Hebrew     => "Hebr",
+            Script::Bengali    => "Beng",
+            Script::Georgian   => "Geor",
+            Script::Mandarin   => "Hani",
+            Script::Hangul     => "Hang",
+            Script::Greek      => "Grek",
+            Script::Kannada    => "Knda",
+            Script::Tamil      => "Taml",
+            Script::Thai       => "Thai",
+            Script::Gujarati   => "Gujr",
+            Script::Gurmukhi   => "Guru",
+            Script::Telugu     => "Telu",
+            Script::Malayalam  => "Mlym",
+            Script::Oriya      => "Orya",
+            Script::Myanmar    => "Mymr",
+            Script::Sinhala    => "Sinh",
+            Script::Khmer      => "Khmr",
+            Script::Armenian   => "Armn",
+            Script::Mongolian  => "Mong",
+            Script::Thaana     => "Thaa",
+            Script::Cherokee   => "Cher",
+            Script::CanadianAboriginal => "Cans",
+            Script::Nko        => "Nkoo",
+            Script::Tifinagh   => "Tfng",
+            Script::Javanese   => "Java",
+            Script::Balinese   => "Bali",
+            Script::Sundanese  => "Sund",
+            Script::Lao        => "Laoo",
+            #[cfg(feature = "historic-scripts")]
+            Script::Coptic     => "Copt",
+            #[cfg(feature = "historic-scripts")]
+            Script::Glagolitic => "Glag",
+            #[cfg(feature = "historic-scripts")]
+            Script::Gothic     => "Goth",
+            #[cfg(feature = "historic-scripts")]
+            Script::OldItalic  => "Ital",
+            #[cfg(feature = "historic-scripts")]
+            Script::Phoenician => "Phnx",
+            #[cfg(feature = "historic-scripts")]
+            Script::Runic      => "Runr",
+        }
+    }
+
+    /// Check whether the script is written from right to left.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::Script;
+    /// assert!(Script::Hebrew.is_rtl());
+    /// assert!(!Script::Latin.is_rtl());
+    /// ```
+    pub fn is_rtl(&self) -> bool {
+        match *self {
+            Script::Arabic | Script::Hebrew | Script::Nko | Script::Thaana => true,
+            #[cfg(feature = "historic-scripts")]
+            Script::Phoenician => true,
+            _ => false
+        }
+    }
+
+    /// Get languages, which are detected in texts of the script, in alphabetic order of their codes.
+    /// These are the candidates, among which a language is chosen when the script is detected.
+    /// The list is empty for scripts, which are used only by unsupported languages.
+    ///
+    /// # Example
+    /// ```
+    /// use whatlang::{Lang, Script};
+    /// assert!(Script::Cyrillic.langs().contains(&Lang::Rus));
+    /// assert_eq!(Script::Greek.langs(), &[Lang::Ell]);
+    /// ```
+    pub fn langs(&self) -> &'static [Lang] {
+        if *self == Script::Mandarin {
+            return HAN_LANGS;
+        }
+        match script_lang(*self) {
+            Some(lang) => slice::from_ref(Lang::all().iter().find(|&&l| l == lang).unwrap()),
+            None => profile_langs(*self)
+        }
+    }
+
     pub fn name(&self) -> &str {
         match *self {
             Script::Latin      => "Latin",
@@ -57,330 +345,532 @@ impl Script {
             Script::Oriya      => "Oriya",
             Script::Myanmar    => "Myanmar",
             Script::Sinhala    => "Sinhala",
-            Script::Khmer      => "Khmer"
+            Script::Khmer      => "Khmer",
+            Script::Armenian   => "Armenian",
+            Script::Mongolian  => "Mongolian",
+            Script::Thaana     => "Thaana",
+            Script::Cherokee   => "Cherokee",
+            Script::CanadianAboriginal => "Canadian Aboriginal",
+            Script::Nko        => "N'Ko",
+            Script::Tifinagh   => "Tifinagh",
+            Script::Javanese   => "Javanese",
+            Script::Balinese   => "Balinese",
+            Script::Sundanese  => "Sundanese",
+            Script::Lao        => "Lao",
+            #[cfg(feature = "historic-scripts")]
+            Script::Coptic     => "Coptic",
+            #[cfg(feature = "historic-scripts")]
+            Script::Glagolitic => "Glagolitic",
+            #[cfg(feature = "historic-scripts")]
+            Script::Gothic     => "Gothic",
+            #[cfg(feature = "historic-scripts")]
+            Script::OldItalic  => "Old Italic",
+            #[cfg(feature = "historic-scripts")]
+            Script::Phoenician => "Phoenician",
+            #[cfg(feature = "historic-scripts")]
+            Script::Runic      => "Runic",
         }
     }
 }
 
-impl fmt::Display for Script {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.name())
-    }
-}
-
-type ScriptCounter = (Script, fn(char) -> bool, usize);
-
-/// Detect only a script by a given text
+/// Parses a script from its ISO 15924 code, see [Script::from_code](enum.Script.html#method.from_code).
 ///
 /// # Example
 /// ```
-/// use whatlang::{detect_script, Script};
-/// let script = detect_script("Благодаря Эсперанто вы обрётете друзей по всему миру!").unwrap();
-/// assert_eq!(script, Script::Cyrillic);
+/// use whatlang::Script;
+/// let script: Script = "Arab".parse().unwrap();
+/// assert_eq!(script, Script::Arabic);
+/// assert!("Xxxx".parse::<Script>().is_err());
 /// ```
-pub fn detect_script(text: &str) -> Option<Script> {
-    let mut script_counters: [ScriptCounter; 24] = [
-        (Script::Latin      , is_latin      , 0),
-        (Script::Cyrillic   , is_cyrillic   , 0),
-        (Script::Arabic     , is_arabic     , 0),
-        (Script::Mandarin   , is_mandarin   , 0),
-        (Script::Devanagari , is_devanagari , 0),
-        (Script::Hebrew     , is_hebrew     , 0),
-        (Script::Ethiopic   , is_ethiopic   , 0),
-        (Script::Georgian   , is_georgian   , 0),
-        (Script::Bengali    , is_bengali    , 0),
-        (Script::Hangul     , is_hangul     , 0),
-        (Script::Hiragana   , is_hiragana   , 0),
-        (Script::Katakana   , is_katakana   , 0),
-        (Script::Greek      , is_greek      , 0),
-        (Script::Kannada    , is_kannada    , 0),
-        (Script::Tamil      , is_tamil      , 0),
-        (Script::Thai       , is_thai       , 0),
-        (Script::Gujarati   , is_gujarati   , 0),
-        (Script::Gurmukhi   , is_gurmukhi   , 0),
-        (Script::Telugu     , is_telugu     , 0),
-        (Script::Malayalam  , is_malayalam  , 0),
-        (Script::Oriya      , is_oriya      , 0),
-        (Script::Myanmar    , is_myanmar    , 0),
-        (Script::Sinhala    , is_sinhala    , 0),
-        (Script::Khmer      , is_khmer      , 0)
-    ];
-
-    let half = text.chars().count() / 2;
-
-    for ch in text.chars() {
-        if is_stop_char(ch) { continue; }
-
-        // For performance reasons, we need to mutate script_counters by calling
-        // `swap` function, it would not be possible to do using normal iterator.
-        for i in 0..script_counters.len() {
-            let found = {
-                let (script, check_fn, ref mut count) = script_counters[i];
-                if check_fn(ch) {
-                    *count += 1;
-                    if *count > half {
-                        return Some(script);
-                    }
-                    true
-                } else {
-                    false
-                }
-            };
-            // Have to let borrow of count fall out of scope before doing swapping, or we could
-            // do this above.
-            if found {
-                // If script was found, move it closer to the front.
-                // If the text contains largely 1 or 2 scripts, this will
-                // cause these scripts to be eventually checked first.
-                if i > 0 {
-                    script_counters.swap(i - 1, i);
-                }
-                break;
-            }
-        }
-    }
+impl FromStr for Script {
+    type Err = ParseScriptError;
 
-    let (script, _, count) = script_counters
-        .iter()
-        .cloned()
-        .max_by_key(|&(_, _, count)| count)
-        .unwrap();
-    if count != 0 {
-        Some(script)
-    } else {
-        None
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Script::from_code(code).ok_or_else(|| ParseScriptError(code.to_string()))
     }
 }
 
-fn is_cyrillic(ch: char) -> bool {
-   match ch {
-       '\u{0400}'...'\u{0484}' |
-       '\u{0487}'...'\u{052F}' |
-       '\u{2DE0}'...'\u{2DFF}' |
-       '\u{A640}'...'\u{A69D}' |
-       '\u{1D2B}' |
-       '\u{1D78}' |
-       '\u{A69F}' => true,
-       _ => false
-   }
-}
+/// An error returned when a string is not an ISO 15924 code of a supported script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseScriptError(String);
 
-// https://en.wikipedia.org/wiki/Latin_script_in_Unicode
-fn is_latin(ch : char) -> bool {
-    match ch {
-        'a'...'z' |
-        'A'...'Z' |
-        '\u{0080}'...'\u{00FF}' |
-        '\u{0100}'...'\u{017F}' |
-        '\u{0180}'...'\u{024F}' |
-        '\u{0250}'...'\u{02AF}' |
-        '\u{1D00}'...'\u{1D7F}' |
-        '\u{1D80}'...'\u{1DBF}' |
-        '\u{1E00}'...'\u{1EFF}' |
-        '\u{2100}'...'\u{214F}' |
-        '\u{2C60}'...'\u{2C7F}' |
-        '\u{A720}'...'\u{A7FF}' |
-        '\u{AB30}'...'\u{AB6F}' => true,
-        _ => false
+impl fmt::Display for ParseScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown script code: {:?}", self.0)
     }
 }
 
-// Based on https://en.wikipedia.org/wiki/Arabic_script_in_Unicode
-fn is_arabic(ch : char) -> bool {
-    match ch {
-        '\u{0600}'...'\u{06FF}' |
-        '\u{0750}'...'\u{07FF}' |
-        '\u{08A0}'...'\u{08FF}' |
-        '\u{FB50}'...'\u{FDFF}' |
-        '\u{FE70}'...'\u{FEFF}' |
-        '\u{10E60}'...'\u{10E7F}' |
-        '\u{1EE00}'...'\u{1EEFF}' => true,
-        _ => false
+impl Error for ParseScriptError {}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
     }
 }
 
-// Based on https://en.wikipedia.org/wiki/Devanagari#Unicode
-fn is_devanagari(ch : char) -> bool {
-    match ch {
-        '\u{0900}'...'\u{097F}' |
-        '\u{A8E0}'...'\u{A8FF}' |
-        '\u{1CD0}'...'\u{1CFF}' => true,
-        _ => false
+// Scripts in a fixed order, in which counts are listed before sorting. Ties between scripts
+// with equal number of characters are resolved by the text (see most_common_script),
+// and by this order only if the text does not tell (which never happens for counts of the text).
+const SCRIPTS_BY_PRIORITY: [Script; Script::COUNT] = [
+    Script::Latin,
+    Script::Cyrillic,
+    Script::Arabic,
+    Script::Mandarin,
+    Script::Devanagari,
+    Script::Hebrew,
+    Script::Ethiopic,
+    Script::Georgian,
+    Script::Bengali,
+    Script::Hangul,
+    Script::Hiragana,
+    Script::Katakana,
+    Script::Greek,
+    Script::Kannada,
+    Script::Tamil,
+    Script::Thai,
+    Script::Gujarati,
+    Script::Gurmukhi,
+    Script::Telugu,
+    Script::Malayalam,
+    Script::Oriya,
+    Script::Myanmar,
+    Script::Sinhala,
+    Script::Khmer,
+    Script::Armenian,
+    Script::Mongolian,
+    Script::Thaana,
+    Script::Cherokee,
+    Script::CanadianAboriginal,
+    Script::Nko,
+    Script::Tifinagh,
+    Script::Javanese,
+    Script::Balinese,
+    Script::Sundanese,
+    Script::Lao,
+    #[cfg(feature = "historic-scripts")]
+    Script::Coptic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Glagolitic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Gothic,
+    #[cfg(feature = "historic-scripts")]
+    Script::OldItalic,
+    #[cfg(feature = "historic-scripts")]
+    Script::Phoenician,
+    #[cfg(feature = "historic-scripts")]
+    Script::Runic,
+];
+
+type ScriptCounts = [usize; Script::COUNT];
+
+// Definition of SCRIPT_RANGES is generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/script_ranges.rs"));
+
+// Scripts of chars are found by the script property from unicode-script crate with `unicode-script`
+// feature, otherwise in the ranges generated from misc/Scripts.txt.
+#[cfg(feature = "unicode-script")]
+use unicode_script_interop::{char_script, is_inherited};
+#[cfg(not(feature = "unicode-script"))]
+use self::{ranges_char_script as char_script, ranges_is_inherited as is_inherited};
+
+// Find a script of the char with a single binary search in SCRIPT_RANGES.
+#[cfg_attr(feature = "unicode-script", allow(dead_code))]
+pub(crate) fn ranges_char_script(ch: char) -> Option<Script> {
+    // Fast path for ASCII, which is the most common case
+    if ch.is_ascii() {
+        return if ch.is_ascii_alphabetic() { Some(Script::Latin) } else { None };
     }
+
+    let code = ch as u32;
+    SCRIPT_RANGES.binary_search_by(|&(start, end, _)| {
+        if end < code {
+            Ordering::Less
+        } else if start > code {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }).ok().map(|index| SCRIPT_RANGES[index].2)
 }
 
-// Based on https://www.key-shortcut.com/en/writing-systems/ethiopian-script/
-fn is_ethiopic(ch : char) -> bool {
-    match ch {
-        '\u{1200}'...'\u{139F}' |
-        '\u{2D80}'...'\u{2DDF}' |
-        '\u{AB00}'...'\u{AB2F}' => true,
-        _ => false
+// Pick the script with the biggest count of chars of the text. Ties are resolved in favour
+// of the script, which appears first in the text.
+fn most_common_script(text: &str, counts: &ScriptCounts) -> Option<Script> {
+    let max = counts.iter().cloned().max().filter(|&max| max > 0)?;
+    let mut tied = SCRIPTS_BY_PRIORITY.iter().cloned().filter(|&script| counts[script as usize] == max);
+    let first = tied.next();
+    if tied.next().is_none() {
+        return first;
     }
+    let mut winner = None;
+    visit_scripts(text, None, |script, _, _| {
+        if counts[script as usize] == max {
+            winner = Some(script);
+            return false;
+        }
+        true
+    });
+    winner.or(first)
 }
 
-// Based on https://en.wikipedia.org/wiki/Hebrew_(Unicode_block)
-fn is_hebrew(ch : char) -> bool {
-    match ch {
-        '\u{0590}'...'\u{05FF}' => true,
-        _ => false
-    }
+// Scripts, which have chars in the counts, in the order of their first chars in the text.
+// The text follows a char of the script `base`, see count_scripts_after.
+pub(crate) fn scripts_by_appearance(text: &str, base: Option<Script>, counts: &[usize]) -> Vec<Script> {
+    let total = counts.iter().filter(|&&count| count > 0).count();
+    let mut scripts = Vec::with_capacity(total);
+    visit_scripts(text, base, |script, _, _| {
+        if counts[script as usize] > 0 && !scripts.contains(&script) {
+            scripts.push(script);
+        }
+        scripts.len() < total
+    });
+    scripts
+}
+
+/// Detect only a script by a given text. If several scripts have the same number of chars,
+/// the one, which appears first in the text, is detected.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_script, Script};
+/// let script = detect_script("Благодаря Эсперанто вы обрётете друзей по всему миру!").unwrap();
+/// assert_eq!(script, Script::Cyrillic);
+/// ```
+pub fn detect_script(text: &str) -> Option<Script> {
+    detect_script_with_options(text, &Options::default())
 }
 
-fn is_georgian(ch : char) -> bool {
-   match ch {
-       '\u{10A0}'...'\u{10FF}' => true,
-       _ => false
-   }
+// With `parallel` feature, texts longer than the parallel threshold are processed
+// by rayon, shorter ones are faster to process in a simple loop.
+pub(crate) fn detect_script_with_options(text: &str, options: &Options) -> Option<Script> {
+    enter_span!("detect_script", len = text.len());
+    #[cfg(parallel)]
+    let script = if text.len() >= options.parallel_threshold && options.script_exit_threshold.is_none() {
+        options.install(|| parallel_detect_script(text, options))
+    } else {
+        sequential_detect_script(text, options)
+    };
+    #[cfg(not(parallel))]
+    let script = sequential_detect_script(text, options);
+    debug_event!(?script, "detected script");
+    script
 }
 
-fn is_mandarin(ch : char) -> bool {
-    match ch {
-        '\u{2E80}'...'\u{2E99}' |
-        '\u{2E9B}'...'\u{2EF3}' |
-        '\u{2F00}'...'\u{2FD5}' |
-        '\u{3005}' |
-        '\u{3007}' |
-        '\u{3021}'...'\u{3029}' |
-        '\u{3038}'...'\u{303B}' |
-        '\u{3400}'...'\u{4DB5}' |
-        '\u{4E00}'...'\u{9FCC}' |
-        '\u{F900}'...'\u{FA6D}' |
-        '\u{FA70}'...'\u{FAD9}' => true,
-        _ => false
-    }
+/// Count characters of every script in a given text.
+/// Only scripts which occur in the text are returned, the most common script goes first.
+/// Scripts with the same number of chars go in the order of their first chars in the text.
+/// Digits, punctuation and other chars which do not belong to any script are not counted.
+///
+/// # Example
+/// ```
+/// use whatlang::{script_counts, Script};
+/// let counts = script_counts("Привет! Hello, мир!");
+/// assert_eq!(counts, vec![(Script::Cyrillic, 9), (Script::Latin, 5)]);
+/// ```
+pub fn script_counts(text: &str) -> Vec<(Script, usize)> {
+    script_counts_with_options(text, &Options::default())
 }
 
-fn is_bengali(ch : char) -> bool {
-   match ch {
-       '\u{0980}'...'\u{09FF}' => true,
-       _ => false
-   }
+/// Detect all scripts of a given text, ordered by their share of characters (and by their
+/// first chars in the text for equal shares). Shares are in the range from 0 to 1 and add up to 1.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_scripts, Script};
+/// let scripts = detect_scripts("مرحبا hello");
+/// assert_eq!(scripts, vec![(Script::Arabic, 0.5), (Script::Latin, 0.5)]);
+/// ```
+pub fn detect_scripts(text: &str) -> Vec<(Script, f64)> {
+    script_shares(&script_counts(text))
 }
 
-fn is_hiragana(ch : char) -> bool {
-   match ch {
-       '\u{3040}'...'\u{309F}' => true,
-       _ => false
-   }
+pub(crate) fn script_shares(counts: &[(Script, usize)]) -> Vec<(Script, f64)> {
+    let total: usize = counts.iter().map(|&(_, count)| count).sum();
+    counts.iter()
+        .map(|&(script, count)| (script, count as f64 / total as f64))
+        .collect()
 }
 
-fn is_katakana(ch : char) -> bool {
-   match ch {
-       '\u{30A0}'...'\u{30FF}' => true,
-       _ => false
+pub(crate) fn script_counts_with_options(text: &str, options: &Options) -> Vec<(Script, usize)> {
+    // Pure ASCII texts (common in logs and emails) have only Latin letters, so other scripts
+    // are not looked up at all. The scan stops at the first non-ASCII byte otherwise.
+    let (letters, ascii_len) = count_ascii_letters(text.as_bytes());
+    if ascii_len == text.len() {
+        return if letters > 0 && options.is_script_allowed(Script::Latin) { vec![(Script::Latin, letters)] } else { vec![] };
+    }
+    if let Some(threshold) = options.script_exit_threshold {
+        return script_counts_till_threshold(text, options, threshold);
     }
+    let counts = count_scripts_with_options(text, options);
+    sorted_script_counts(&counts, || scripts_by_appearance(text, None, &counts))
 }
 
+// Counts chars only till a script has the share of at least `min_chars` counted chars
+// (see Options::set_script_exit_threshold), so the counts are of a prefix of the text.
+// The prefix has at least `Options::set_min_length` chars, if the text has them.
+fn script_counts_till_threshold(text: &str, options: &Options, (share, min_chars): (f64, usize)) -> Vec<(Script, usize)> {
+    let min_chars = min_chars.max(options.min_length);
+    let mut counts: ScriptCounts = [0; Script::COUNT];
+    let mut total = 0;
+    let mut end = text.len();
 
-// Hangul is Korean Alphabet. Unicode ranges are taken from: https://en.wikipedia.org/wiki/Hangul
-fn is_hangul(ch : char) -> bool {
-    match ch {
-        '\u{AC00}'...'\u{D7AF}' |
-        '\u{1100}'...'\u{11FF}' |
-        '\u{3130}'...'\u{318F}' |
-        '\u{3200}'...'\u{32FF}' |
-        '\u{A960}'...'\u{A97F}' |
-        '\u{D7B0}'...'\u{D7FF}' |
-        '\u{FF00}'...'\u{FFEF}' => true,
-        _ => false
-    }
+    visit_scripts(text, None, |script, n, remaining| {
+        if !options.is_script_allowed(script) {
+            return true;
+        }
+        let count = &mut counts[script as usize];
+        *count += n;
+        total += n;
+        if total >= min_chars && *count as f64 >= share * total as f64 {
+            end = text.len() - remaining;
+            return false;
+        }
+        true
+    });
+
+    sorted_script_counts(&counts, || scripts_by_appearance(&text[..end], None, &counts))
 }
 
-// Taken from: https://en.wikipedia.org/wiki/Greek_and_Coptic
-fn is_greek(ch : char) -> bool {
-    match ch {
-        '\u{0370}'...'\u{03FF}' => true,
-        _ => false
+// Non-zero counts of scripts, the most common script goes first. Ties are resolved in favour
+// of the script, which goes first in `by_appearance`, it's called only if there are ties.
+pub(crate) fn sorted_script_counts<F>(counts: &[usize], by_appearance: F) -> Vec<(Script, usize)>
+    where F: FnOnce() -> Vec<Script>
+{
+    let mut result: Vec<(Script, usize)> = SCRIPTS_BY_PRIORITY.iter()
+        .map(|&script| (script, counts[script as usize]))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    result.sort_by_key(|&(_, count)| Reverse(count));
+    if result.windows(2).any(|pair| pair[0].1 == pair[1].1) {
+        let scripts = by_appearance();
+        let position = |script| scripts.iter().position(|&other| other == script).unwrap_or(scripts.len());
+        result.sort_by_key(|&(script, count)| (Reverse(count), position(script)));
     }
+    result
 }
 
-// Based on: https://en.wikipedia.org/wiki/Kannada_(Unicode_block)
-fn is_kannada(ch : char) -> bool {
-    match ch {
-        '\u{0C80}'...'\u{0CFF}' => true,
-        _ => false
+#[cfg_attr(not(parallel), allow(unused_variables))]
+pub(crate) fn count_scripts_with_options(text: &str, options: &Options) -> ScriptCounts {
+    #[cfg(parallel)]
+    {
+        if text.len() >= options.parallel_threshold {
+            let mut counts = options.install(|| parallel_count_scripts(text));
+            retain_allowed_scripts(&mut counts, options);
+            return counts;
+        }
     }
+    let mut counts = count_scripts(text);
+    retain_allowed_scripts(&mut counts, options);
+    counts
 }
 
-// Based on: https://en.wikipedia.org/wiki/Tamil_(Unicode_block)
-fn is_tamil(ch: char) -> bool {
-    match ch {
-        '\u{0B80}'...'\u{0BFF}' => true,
-        _ => false
+// Zero the counts of scripts, which are not detected with the options (see Options::set_scripts).
+pub(crate) fn retain_allowed_scripts(counts: &mut ScriptCounts, options: &Options) {
+    if options.scripts.is_none() {
+        return;
+    }
+    for &script in SCRIPTS_BY_PRIORITY.iter() {
+        if !options.is_script_allowed(script) {
+            counts[script as usize] = 0;
+        }
     }
 }
 
-// Based on: https://en.wikipedia.org/wiki/Thai_(Unicode_block)
-fn is_thai(ch: char) -> bool {
-    match ch {
-        '\u{0E00}'...'\u{0E7F}' => true,
-        _ => false
-    }
+// Returns as soon as a script has more chars than all the other ones can get,
+// the number of the remaining chars is bounded by the number of the remaining bytes,
+// so the text is passed only once. With a threshold `(share, min_chars)` it also returns as soon as
+// a script has at least the share of at least `min_chars` counted chars.
+// Chars of scripts, which are not allowed by the options, are skipped.
+fn sequential_detect_script(text: &str, options: &Options) -> Option<Script> {
+    let (share, min_chars) = options.script_exit_threshold.unwrap_or((1.0, ::std::usize::MAX));
+    let mut counts: ScriptCounts = [0; Script::COUNT];
+    let mut total = 0;
+    let mut winner = None;
+
+    visit_scripts(text, None, |script, n, remaining| {
+        if !options.is_script_allowed(script) {
+            return true;
+        }
+        let count = &mut counts[script as usize];
+        *count += n;
+        total += n;
+        if *count > total - *count + remaining || (total >= min_chars && *count as f64 >= share * total as f64) {
+            winner = Some(script);
+            return false;
+        }
+        true
+    });
+
+    winner.or_else(|| most_common_script(text, &counts))
 }
 
-// Based on: https://en.wikipedia.org/wiki/Gujarati_(Unicode_block)
-fn is_gujarati(ch: char) -> bool {
-    match ch {
-        '\u{0A80}'...'\u{0AFF}' => true,
-        _ => false
+// Calls `f` with scripts of chars of the text, numbers of the chars (more than one for a block
+// of ASCII letters) and the number of the remaining bytes of the text, until `f` returns false.
+// Chars which do not belong to any script are skipped. Combining marks of the Inherited script
+// (e.g. diacritics of NFD-normalized text) take the script of the char before them, which is `base`
+// for the first char, and are skipped after a char without script.
+// Returns the script, which a combining mark after the text would take.
+#[inline]
+fn visit_scripts<F>(text: &str, mut base: Option<Script>, mut f: F) -> Option<Script>
+    where F: FnMut(Script, usize, usize) -> bool
+{
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let (letters, ascii_len) = count_ascii_letters(&bytes[pos..]);
+        pos += ascii_len;
+        if ascii_len > 0 {
+            base = if bytes[pos - 1].is_ascii_alphabetic() { Some(Script::Latin) } else { None };
+        }
+        if letters > 0 && !f(Script::Latin, letters, bytes.len() - pos) {
+            return base;
+        }
+        if let Some(ch) = text[pos..].chars().next() {
+            pos += ch.len_utf8();
+            let script = if is_stop_char(ch) {
+                base = None;
+                continue;
+            } else if let Some(script) = char_script(ch) {
+                base = Some(script);
+                script
+            } else if is_inherited(ch) {
+                match base {
+                    Some(script) => script,
+                    None => continue
+                }
+            } else {
+                base = None;
+                continue;
+            };
+            if !f(script, 1, bytes.len() - pos) {
+                return base;
+            }
+        }
     }
+    base
 }
 
-// Gurmukhi is the script for Punjabi language.
-// Based on: https://en.wikipedia.org/wiki/Gurmukhi_(Unicode_block)
-fn is_gurmukhi(ch: char) -> bool {
-    match ch {
-        '\u{0A00}'...'\u{0A7F}' => true,
-        _ => false
-    }
+#[cfg_attr(feature = "unicode-script", allow(dead_code))]
+pub(crate) fn ranges_is_inherited(ch: char) -> bool {
+    let code = ch as u32;
+    INHERITED_RANGES.binary_search_by(|&(start, end)| {
+        if end < code {
+            Ordering::Less
+        } else if start > code {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }).is_ok()
 }
 
-fn is_telugu(ch: char) -> bool {
-    match ch {
-        '\u{0C00}'...'\u{0C7F}' => true,
-        _ => false
+const ASCII_BLOCK_SIZE: usize = 8;
+const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+const REPEATED_BYTE: u64 = 0x0101_0101_0101_0101;
+
+// Counts ASCII letters at the beginning of the bytes until the first non-ASCII byte.
+// Returns the number of the letters and the number of the ASCII bytes.
+// Blocks of 8 bytes are processed at once as u64, so texts in English are counted
+// several times faster than char by char.
+#[inline]
+fn count_ascii_letters(bytes: &[u8]) -> (usize, usize) {
+    let mut letters = 0;
+    let mut pos = 0;
+    while pos + ASCII_BLOCK_SIZE <= bytes.len() {
+        let mut block = [0; ASCII_BLOCK_SIZE];
+        block.copy_from_slice(&bytes[pos..pos + ASCII_BLOCK_SIZE]);
+        let word = u64::from_le_bytes(block);
+        if word & HIGH_BITS != 0 {
+            break;
+        }
+        // Setting bit 0x20 maps upper case letters to lower case ones and no other byte to a letter.
+        // For a byte below 0x80, adding 0x1F sets its high bit if it's at least 'a' (0x61)
+        // and adding 0x05 does if it's above 'z' (0x7A), there are no carries between the bytes.
+        let lower = word | (0x20 * REPEATED_BYTE);
+        let from_a = (lower + 0x1F * REPEATED_BYTE) & HIGH_BITS;
+        let after_z = (lower + 0x05 * REPEATED_BYTE) & HIGH_BITS;
+        letters += (from_a & !after_z).count_ones() as usize;
+        pos += ASCII_BLOCK_SIZE;
     }
+    for &byte in &bytes[pos..] {
+        if !byte.is_ascii() {
+            break;
+        }
+        if byte.is_ascii_alphabetic() {
+            letters += 1;
+        }
+        pos += 1;
+    }
+    (letters, pos)
 }
 
-// Based on: https://en.wikipedia.org/wiki/Malayalam_(Unicode_block)
-fn is_malayalam(ch: char) -> bool {
-    match ch {
-        '\u{0D00}'...'\u{0D7F}' => true,
-        _ => false
-    }
+// Size of text chunks, which are counted by separate rayon tasks.
+#[cfg(parallel)]
+const PARALLEL_CHUNK_SIZE: usize = 16 * 1024;
+
+// Counts characters of every script in chunks of the text in parallel.
+// Counts are summed up, so the result does not depend on how rayon schedules the work.
+#[cfg(parallel)]
+fn parallel_detect_script(text: &str, options: &Options) -> Option<Script> {
+    let mut counts = parallel_count_scripts(text);
+    retain_allowed_scripts(&mut counts, options);
+    most_common_script(text, &counts)
 }
 
-// Based on: https://en.wikipedia.org/wiki/Malayalam_(Unicode_block)
-fn is_oriya(ch: char) -> bool {
-    match ch {
-        '\u{0B00}'...'\u{0B7F}' => true,
-        _ => false
-    }
+#[cfg(parallel)]
+fn parallel_count_scripts(text: &str) -> ScriptCounts {
+    use rayon::prelude::*;
+
+    split_into_chunks(text, PARALLEL_CHUNK_SIZE)
+        .par_iter()
+        .map(|chunk| count_scripts(chunk))
+        .reduce(|| [0; Script::COUNT], |mut total, counts| {
+            for (sum, count) in total.iter_mut().zip(counts.iter()) {
+                *sum += count;
+            }
+            total
+        })
 }
 
-// Based on: https://en.wikipedia.org/wiki/Myanmar_(Unicode_block)
-fn is_myanmar(ch: char) -> bool {
-    match ch {
-        '\u{1000}'...'\u{109F}' => true,
-        _ => false
-    }
+// Same as sequential_detect_script, but without early return.
+fn count_scripts(text: &str) -> ScriptCounts {
+    count_scripts_after(text, None).0
 }
 
-// Based on: https://en.wikipedia.org/wiki/Sinhala_(Unicode_block)
-fn is_sinhala(ch: char) -> bool {
-    match ch {
-        '\u{0D80}'...'\u{0DFF}' => true,
-        _ => false
-    }
+// Counts scripts of a part of a text, which follows a char of the script `base` (None if the char
+// has no script), e.g. of a chunk fed to IncrementalDetector. Returns the counts and the script,
+// which is taken by combining marks at the beginning of the next part.
+pub(crate) fn count_scripts_after(text: &str, base: Option<Script>) -> (ScriptCounts, Option<Script>) {
+    let mut counts: ScriptCounts = [0; Script::COUNT];
+    let base = visit_scripts(text, base, |script, n, _| {
+        counts[script as usize] += n;
+        true
+    });
+    (counts, base)
 }
 
-// Based on: https://en.wikipedia.org/wiki/Khmer_alphabet
-fn is_khmer(ch: char) -> bool {
-    match ch {
-        '\u{1780}'...'\u{17FF}' | '\u{19E0}'...'\u{19FF}' => true,
-        _ => false
+// Split text into chunks of approximately the given size, respecting char boundaries.
+#[cfg(parallel)]
+fn split_into_chunks(text: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::with_capacity(text.len() / size + 1);
+    let mut rest = text;
+    while rest.len() > size {
+        let mut end = size;
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        // Combining marks are kept in the chunk of the char before them
+        while let Some(ch) = rest[end..].chars().next().filter(|&ch| is_inherited(ch)) {
+            end += ch.len_utf8();
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    if !rest.is_empty() || chunks.is_empty() {
+        chunks.push(rest);
     }
+    chunks
 }
 
 #[cfg(test)]
@@ -393,136 +883,517 @@ mod tests {
         assert_eq!(Script::Katakana.name(), "Katakana");
     }
 
+    #[test]
+    fn test_script_all() {
+        // Counts of scripts are indexed by `Script as usize`
+        for (index, &script) in Script::all().iter().enumerate() {
+            assert_eq!(script as usize, index);
+            assert!(SCRIPTS_BY_PRIORITY.contains(&script));
+        }
+    }
+
+    #[test]
+    fn test_script_code() {
+        for &script in Script::all() {
+            assert_eq!(script.code().len(), 4);
+            assert_eq!(Script::from_code(script.code()), Some(script));
+            assert_eq!(script.code().to_uppercase().parse::<Script>(), Ok(script));
+        }
+        assert_eq!(Script::from_code("Latin"), None);
+        assert_eq!(
+            "Zzzz".parse::<Script>().unwrap_err().to_string(),
+            "unknown script code: \"Zzzz\""
+        );
+    }
+
+    #[test]
+    fn test_script_langs() {
+        assert_eq!(Script::Hebrew.langs(), &[Lang::Heb, Lang::Ydd]);
+        assert_eq!(Script::Katakana.langs(), &[Lang::Jpn]);
+        assert_eq!(Script::Mandarin.langs(), &[Lang::Cmn, Lang::Jpn, Lang::Kor, Lang::Yue]);
+        assert!(Script::Latin.langs().contains(&Lang::Srp));
+        assert!(Script::Cyrillic.langs().contains(&Lang::Srp));
+        assert!(Script::Latin.langs().contains(&Lang::Uzb));
+        assert!(Script::Cyrillic.langs().contains(&Lang::Uzb));
+        assert!(Script::CanadianAboriginal.langs().is_empty());
+    }
+
+    #[test]
+    fn test_script_is_rtl() {
+        assert!(Script::Arabic.is_rtl());
+        assert!(Script::Hebrew.is_rtl());
+        assert!(Script::Thaana.is_rtl());
+        assert!(Script::Nko.is_rtl());
+        assert!(!Script::Tifinagh.is_rtl());
+        assert!(!Script::Cyrillic.is_rtl());
+        assert!(!Script::Mandarin.is_rtl());
+    }
+
+    #[cfg(feature = "historic-scripts")]
+    #[test]
+    fn test_detect_historic_scripts() {
+        use detect::detect;
+
+        assert_eq!(detect_script("ⲧⲙⲛⲧⲣⲙⲛⲕⲏⲙⲉ"), Some(Script::Coptic));
+        assert_eq!(detect_script("ⰳⰾⰰⰳⱁⰾⰹⱌⰰ"), Some(Script::Glagolitic));
+        assert_eq!(detect_script("𐌰𐍄𐍄𐌰 𐌿𐌽𐍃𐌰𐍂"), Some(Script::Gothic));
+        assert_eq!(detect_script("𐌀𐌋𐌄𐌐𐌀"), Some(Script::OldItalic));
+        assert_eq!(detect_script("𐤀𐤁𐤂𐤃"), Some(Script::Phoenician));
+        assert_eq!(detect_script("ᚠᚢᚦᚨᚱᚲ"), Some(Script::Runic));
+        assert_eq!(Script::from_char('ϣ'), Some(Script::Coptic));
+        assert!(Script::Phoenician.is_rtl());
+        assert!(Script::Gothic.langs().is_empty());
+        assert_eq!(Script::OldItalic.code(), "Ital");
+        assert_eq!(Script::OldItalic.to_string(), "Old Italic");
+
+        // Only the script is detected, no language uses it
+        assert_eq!(detect("𐌰𐍄𐍄𐌰 𐌿𐌽𐍃𐌰𐍂 𐌸𐌿 𐌹𐌽 𐌷𐌹𐌼𐌹𐌽𐌰𐌼"), None);
+    }
+
+    #[test]
+    fn test_script_from_char() {
+        assert_eq!(Script::from_char('a'), Some(Script::Latin));
+        assert_eq!(Script::from_char('ひ'), Some(Script::Hiragana));
+        assert_eq!(Script::from_char('7'), None);
+    }
+
+    #[test]
+    fn test_script_contains() {
+        assert!(Script::Latin.contains('z'));
+        assert!(Script::Cyrillic.contains('ї'));
+        assert!(!Script::Latin.contains('ї'));
+        assert!(!Script::Latin.contains('1'));
+    }
+
     #[test]
     fn test_detect_script() {
-        assert_eq!(detect_script(&"1234567890-,;!".to_string()), None);
+        assert_eq!(detect_script("1234567890-,;!"), None);
 
         // One script
-        assert_eq!(detect_script(&"Hello!".to_string()), Some(Script::Latin));
-        assert_eq!(detect_script(&"Привет всем!".to_string()), Some(Script::Cyrillic));
-        assert_eq!(detect_script(&"ქართული ენა მსოფლიო ".to_string()), Some(Script::Georgian));
-        assert_eq!(detect_script(&"県見夜上温国阪題富販".to_string()), Some(Script::Mandarin));
-        assert_eq!(detect_script(&" ككل حوالي 1.6، ومعظم الناس ".to_string()), Some(Script::Arabic));
-        assert_eq!(detect_script(&"हिमालयी वन चिड़िया (जूथेरा सालिमअली) चिड़िया की एक प्रजाति है".to_string()), Some(Script::Devanagari));
-        assert_eq!(detect_script(&"היסטוריה והתפתחות של האלפבית העברי".to_string()), Some(Script::Hebrew));
-        assert_eq!(detect_script(&"የኢትዮጵያ ፌዴራላዊ ዴሞክራሲያዊሪፐብሊክ".to_string()), Some(Script::Ethiopic));
+        assert_eq!(detect_script("Hello!"), Some(Script::Latin));
+        assert_eq!(detect_script("Привет всем!"), Some(Script::Cyrillic));
+        assert_eq!(detect_script("ქართული ენა მსოფლიო "), Some(Script::Georgian));
+        assert_eq!(detect_script("県見夜上温国阪題富販"), Some(Script::Mandarin));
+        assert_eq!(detect_script(" ككل حوالي 1.6، ومعظم الناس "), Some(Script::Arabic));
+        assert_eq!(detect_script("हिमालयी वन चिड़िया (जूथेरा सालिमअली) चिड़िया की एक प्रजाति है"), Some(Script::Devanagari));
+        assert_eq!(detect_script("היסטוריה והתפתחות של האלפבית העברי"), Some(Script::Hebrew));
+        assert_eq!(detect_script("የኢትዮጵያ ፌዴራላዊ ዴሞክራሲያዊሪፐብሊክ"), Some(Script::Ethiopic));
+        assert_eq!(detect_script("Հայաստանի Հանրապետություն"), Some(Script::Armenian));
+        assert_eq!(detect_script("ᠮᠣᠩᠭᠣᠯ ᠪᠢᠴᠢᠭ"), Some(Script::Mongolian));
+        assert_eq!(detect_script("ދިވެހި ބަސް"), Some(Script::Thaana));
+        assert_eq!(detect_script("ᏣᎳᎩ ᎦᏬᏂᎯᏍᏗ"), Some(Script::Cherokee));
+        assert_eq!(detect_script("ᐃᓄᒃᑎᑐᑦ"), Some(Script::CanadianAboriginal));
+        assert_eq!(detect_script("ߒߞߏ ߛߓߍߛߎ߲"), Some(Script::Nko));
+        assert_eq!(detect_script("ⵜⴰⵎⴰⵣⵉⵖⵜ"), Some(Script::Tifinagh));
+        assert_eq!(detect_script("ꦧꦱꦗꦮ"), Some(Script::Javanese));
+        assert_eq!(detect_script("ᬪᬵᬱᬩᬮᬶ"), Some(Script::Balinese));
+        assert_eq!(detect_script("ᮘᮞ ᮞᮥᮔ᮪ᮓ"), Some(Script::Sundanese));
+        assert_eq!(detect_script("ພາສາລາວ"), Some(Script::Lao));
 
         // Mixed scripts
-        assert_eq!(detect_script(&"Привет! Текст на русском with some English.".to_string()), Some(Script::Cyrillic));
-        assert_eq!(detect_script(&"Russian word любовь means love.".to_string()), Some(Script::Latin));
+        assert_eq!(detect_script("Привет! Текст на русском with some English."), Some(Script::Cyrillic));
+        assert_eq!(detect_script("Russian word любовь means love."), Some(Script::Latin));
+
+        // Equal number of chars: the script, which appears first, is detected
+        assert_eq!(detect_script("где abc"), Some(Script::Cyrillic));
+        assert_eq!(detect_script("abc где"), Some(Script::Latin));
+        assert_eq!(detect_script("ひらがな カタカナ"), Some(Script::Hiragana));
+        assert_eq!(detect_script("カタカナ ひらがな"), Some(Script::Katakana));
+        assert_eq!(detect_script("1. ab где cd"), Some(Script::Latin));
+    }
+
+    #[test]
+    fn test_script_counts() {
+        assert_eq!(script_counts(""), vec![]);
+        assert_eq!(script_counts("1234567890-,;!"), vec![]);
+        assert_eq!(script_counts("Hello!"), vec![(Script::Latin, 5)]);
+        assert_eq!(
+            script_counts("Russian word любовь means love."),
+            vec![(Script::Latin, 20), (Script::Cyrillic, 6)]
+        );
+
+        // Scripts with equal number of chars go in the order of appearance
+        assert_eq!(script_counts("где abc"), vec![(Script::Cyrillic, 3), (Script::Latin, 3)]);
+        assert_eq!(script_counts("abc где"), vec![(Script::Latin, 3), (Script::Cyrillic, 3)]);
+        assert_eq!(script_counts("αβ где abc γ"), vec![(Script::Greek, 3), (Script::Cyrillic, 3), (Script::Latin, 3)]);
+    }
+
+    #[test]
+    fn test_detect_scripts() {
+        assert_eq!(detect_scripts("1234567890-,;!"), vec![]);
+        assert_eq!(detect_scripts("Hello!"), vec![(Script::Latin, 1.0)]);
+        assert_eq!(
+            detect_scripts("هذا النص abc"),
+            vec![(Script::Arabic, 0.7), (Script::Latin, 0.3)]
+        );
+    }
+
+    #[test]
+    fn test_count_ascii_letters() {
+        let all_ascii: String = (0u8..128).map(char::from).collect();
+        assert_eq!(count_ascii_letters(all_ascii.as_bytes()), (52, 128));
+        assert_eq!(count_ascii_letters(b""), (0, 0));
+        assert_eq!(count_ascii_letters("Hello, world! Привет".as_bytes()), (10, 14));
+        assert_eq!(count_ascii_letters("abcdefg\u{e9}xyz".as_bytes()), (7, 7));
+        assert_eq!(count_ascii_letters("@[`{ AZaz".as_bytes()), (4, 9));
+    }
+
+    #[test]
+    fn test_script_counts_of_ascii_texts() {
+        let options = Options::default();
+        for &text in ["Hello, world!", "42 + 1 = 43", "", "a", "Hello, мир!"].iter() {
+            assert_eq!(script_counts_with_options(text, &options), sorted_script_counts(&count_scripts(text), || scripts_by_appearance(text, None, &count_scripts(text))), "{}", text);
+        }
+        assert_eq!(script_counts("Hello, world!"), vec![(Script::Latin, 10)]);
+    }
+
+    #[test]
+    fn test_count_scripts_matches_chars() {
+        let texts = [
+            "Hello, world!",
+            "Привет, мир! Hello, world!",
+            "naïve café, 東京 and ガギグ, 1234567890 abcdefghijklmnop",
+            "ab\u{1F600}cdefghijk\u{1F600}",
+            "",
+        ];
+        for &text in texts.iter() {
+            let mut expected: ScriptCounts = [0; Script::COUNT];
+            for ch in text.chars().filter(|&ch| !is_stop_char(ch)) {
+                if let Some(script) = char_script(ch) {
+                    expected[script as usize] += 1;
+                }
+            }
+            assert_eq!(count_scripts(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_sequential_detect_script_matches_counts() {
+        let texts = [
+            "Hello, мир!!!",
+            "Привет, world",
+            "ab ガギグゲゴ ab",
+            "!!! שלום ... Hello ... עולם",
+            "東京 Tokyo 東京",
+            "ab",
+            "",
+        ];
+        for &text in texts.iter() {
+            for &repeat in [1, 10].iter() {
+                let text = text.repeat(repeat);
+                assert_eq!(sequential_detect_script(&text, &Options::default()), most_common_script(&text, &count_scripts(&text)), "{}", text);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sequential_detect_script_with_exit_threshold() {
+        let text = "Привет, мир! ".repeat(20) + &"Hello, world! ".repeat(100);
+        assert_eq!(sequential_detect_script(&text, &Options::default()), Some(Script::Latin));
+        assert_eq!(sequential_detect_script(&text, &Options::new().set_script_exit_threshold(0.8, 100)), Some(Script::Cyrillic));
+        // Not enough chars to exit early
+        assert_eq!(sequential_detect_script(&text, &Options::new().set_script_exit_threshold(0.8, 1000)), Some(Script::Latin));
+        // The share is never reached, so the exact check decides
+        assert_eq!(sequential_detect_script(&text, &Options::new().set_script_exit_threshold(1.1, 0)), Some(Script::Latin));
+
+        let options = Options::new().set_script_exit_threshold(0.8, 100).set_parallel_threshold(0);
+        assert_eq!(detect_script_with_options(&text, &options), Some(Script::Cyrillic));
+    }
+
+    #[test]
+    fn test_detect_script_with_allowed_scripts() {
+        let options = Options::new().set_scripts(vec![Script::Latin, Script::Cyrillic]);
+        let text = "Meeting notes 会议记录会议记录会议记录会议记录";
+        assert_eq!(detect_script(text), Some(Script::Mandarin));
+        assert_eq!(detect_script_with_options(text, &options), Some(Script::Latin));
+        assert_eq!(script_counts_with_options(text, &options), vec![(Script::Latin, 12)]);
+
+        // Skipped chars do not count for the exact check of the early return
+        let text = "東京".repeat(100) + "Москва";
+        assert_eq!(sequential_detect_script(&text, &options), Some(Script::Cyrillic));
+        assert_eq!(detect_script_with_options(&text, &options.clone().set_parallel_threshold(0)), Some(Script::Cyrillic));
+
+        // A text of only other scripts has no script
+        assert_eq!(detect_script_with_options("東京", &options), None);
+        assert_eq!(script_counts_with_options("東京", &options), vec![]);
+        let options = Options::new().set_scripts(vec![Script::Cyrillic]);
+        assert_eq!(script_counts_with_options("Hello", &options), vec![]);
+    }
+
+    #[cfg(parallel)]
+    #[test]
+    fn test_parallel_detect_script() {
+        let texts = [
+            "",
+            "1234567890-,;!",
+            "Hello!",
+            "Привет! Текст на русском with some English.",
+            "Russian word любовь means love.",
+        ];
+        for text in texts.iter() {
+            let long_text = text.repeat(5000);
+            assert_eq!(parallel_detect_script(&long_text, &Options::default()), sequential_detect_script(&long_text, &Options::default()));
+        }
+
+        // Equal number of chars: the script, which appears first, is detected
+        let text = "abcабв".repeat(10000);
+        assert_eq!(parallel_detect_script(&text, &Options::default()), Some(Script::Latin));
+        let text = "абвabc".repeat(10000);
+        assert_eq!(parallel_detect_script(&text, &Options::default()), Some(Script::Cyrillic));
+    }
+
+    #[cfg(parallel)]
+    #[test]
+    fn test_detect_script_with_parallel_threshold() {
+        let text = "Привет! Текст на русском with some English.";
+        let options = Options::new().set_parallel_threshold(0);
+        assert_eq!(detect_script_with_options(text, &options), Some(Script::Cyrillic));
+        let options = Options::new().set_parallel_threshold(1000);
+        assert_eq!(detect_script_with_options(text, &options), Some(Script::Cyrillic));
+
+        let long_text = text.repeat(1000);
+        let options = Options::new().set_parallel_threshold(0);
+        assert_eq!(script_counts_with_options(&long_text, &options), script_counts(&long_text));
+    }
+
+    #[cfg(parallel)]
+    #[test]
+    fn test_split_into_chunks() {
+        assert_eq!(split_into_chunks("", 3), vec![""]);
+        assert_eq!(split_into_chunks("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(split_into_chunks("абвг", 3), vec!["аб", "вг"]);
+        assert_eq!(split_into_chunks("ae\u{301}\u{308}bc", 2), vec!["ae\u{301}\u{308}", "bc"]);
+    }
+
+    #[test]
+    fn test_script_ranges_are_sorted() {
+        for &(start, end, _) in SCRIPT_RANGES.iter() {
+            assert!(start <= end);
+        }
+        for pair in SCRIPT_RANGES.windows(2) {
+            assert!(pair[0].1 < pair[1].0, "{:?} overlaps with {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_char_script() {
+        assert_eq!(char_script('1'), None);
+        assert_eq!(char_script(' '), None);
+        assert_eq!(char_script('\u{0300}'), None);
+        assert_eq!(char_script('\u{10FFFF}'), None);
+
+        // Cyrillic chars inside of Phonetic Extensions block
+        assert_eq!(char_script('\u{1D2B}'), Some(Script::Cyrillic));
+        assert_eq!(char_script('\u{1D78}'), Some(Script::Cyrillic));
+        assert_eq!(char_script('\u{1D2C}'), Some(Script::Latin));
+
+        // Fullwidth Latin letters, punctuation inside of Latin-1 Supplement block
+        assert_eq!(char_script('Ａ'), Some(Script::Latin));
+        assert_eq!(char_script('«'), None);
+
+        // Boundaries of ranges outside of BMP
+        assert_eq!(char_script('\u{10E60}'), Some(Script::Arabic));
+        assert_eq!(char_script('\u{1EEF1}'), Some(Script::Arabic));
+        assert_eq!(char_script('\u{20000}'), Some(Script::Mandarin));
+    }
+
+    #[test]
+    fn test_char_script_halfwidth_and_fullwidth_forms() {
+        // The block is split by its content, as in Scripts.txt
+        assert_eq!(char_script('Ａ'), Some(Script::Latin));
+        assert_eq!(char_script('ｚ'), Some(Script::Latin));
+        assert_eq!(char_script('ｦ'), Some(Script::Katakana));
+        assert_eq!(char_script('ｶ'), Some(Script::Katakana));
+        assert_eq!(char_script('ﾝ'), Some(Script::Katakana));
+        assert_eq!(char_script('ﾡ'), Some(Script::Hangul));
+        assert_eq!(char_script('ￜ'), Some(Script::Hangul));
+        for &ch in ['！', '１', '［', '｡', '･', 'ｰ', 'ﾞ', '￥', '￮'].iter() {
+            assert_eq!(char_script(ch), None, "{}", ch);
+        }
+    }
+
+    #[test]
+    fn test_script_counts_of_polytonic_greek() {
+        // Greek Extended block, precomposed and with combining breathings and accents
+        assert_eq!(char_script('ἀ'), Some(Script::Greek));
+        assert_eq!(char_script('ῷ'), Some(Script::Greek));
+        assert_eq!(char_script('Ὦ'), Some(Script::Greek));
+        assert_eq!(script_counts("Ἐν ἀρχῇ ἦν ὁ λόγος"), vec![(Script::Greek, 14)]);
+        assert_eq!(script_counts("α\u{313}\u{301}ρχη\u{342}"), vec![(Script::Greek, 7)]);
+    }
+
+    #[test]
+    fn test_script_counts_with_native_digits() {
+        // Native digits belong to their scripts, ASCII digits to none
+        assert_eq!(script_counts("٠١٢٣ ٤٥٦ 789"), vec![(Script::Arabic, 7)]);
+        assert_eq!(script_counts("۱۲۳"), vec![(Script::Arabic, 3)]);
+        assert_eq!(script_counts("०१२ ३४"), vec![(Script::Devanagari, 5)]);
+        assert_eq!(script_counts("১২৩ ๑๒"), vec![(Script::Bengali, 3), (Script::Thai, 2)]);
+        assert_eq!(detect_script("Invoice ١٢٣٤٥ ٦٧٨٩٠ ١١١١١"), Some(Script::Arabic));
+    }
+
+    #[test]
+    fn test_script_counts_with_combining_marks() {
+        // Combining marks take the script of the char before them
+        assert_eq!(script_counts("e\u{301}te\u{301}"), vec![(Script::Latin, 5)]);
+        assert_eq!(script_counts("мо\u{301}ре"), vec![(Script::Cyrillic, 5)]);
+        assert_eq!(script_counts("كَتَبَ"), vec![(Script::Arabic, 6)]);
+        assert_eq!(script_counts("я\u{301}a\u{301}\u{308}"), vec![(Script::Latin, 3), (Script::Cyrillic, 2)]);
+
+        // Marks without a letter before them are not counted
+        assert_eq!(script_counts("\u{301}"), vec![]);
+        assert_eq!(script_counts("1\u{301} \u{301}!\u{301}"), vec![]);
+        assert_eq!(script_counts("é \u{301}"), vec![(Script::Latin, 1)]);
+    }
+
+    #[test]
+    fn test_count_scripts_after() {
+        let (counts, base) = count_scripts_after("\u{301}\u{301}x", Some(Script::Cyrillic));
+        assert_eq!(sorted_script_counts(&counts, Vec::new), vec![(Script::Cyrillic, 2), (Script::Latin, 1)]);
+        assert_eq!(base, Some(Script::Latin));
+
+        let (counts, base) = count_scripts_after("\u{301}", None);
+        assert_eq!(sorted_script_counts(&counts, Vec::new), vec![]);
+        assert_eq!(base, None);
+
+        let (_, base) = count_scripts_after("\u{301}", Some(Script::Greek));
+        assert_eq!(base, Some(Script::Greek));
+        let (_, base) = count_scripts_after("мир!", None);
+        assert_eq!(base, None);
+    }
+
+    #[test]
+    fn test_char_script_cjk_symbols_and_punctuation() {
+        // Shared by Chinese, Japanese and Korean, so not counted for any script
+        for &ch in ['\u{3000}', '、', '。', '「', '」', '《', '》', '々', '〇', '〡', '\u{302E}', '〻', '〽'].iter() {
+            assert_eq!(char_script(ch), None, "{}", ch);
+        }
+        assert_eq!(char_script('\u{2FFF}'), None);
+        assert_eq!(char_script('\u{3041}'), Some(Script::Hiragana));
+
+        assert_eq!(script_counts("「人々」。"), vec![(Script::Mandarin, 1)]);
+    }
+
+    #[test]
+    fn test_script_counts_of_japanese_web_text() {
+        // Halfwidth Katakana and fullwidth Latin letters and digits are common in Japanese web text
+        let text = "【送料無料】ﾜｲﾔﾚｽｲﾔﾎﾝ ＢＴ５．０ 最大２０時間再生！ご注文はこちら";
+        assert_eq!(script_counts(text), vec![
+            (Script::Mandarin, 12), (Script::Katakana, 9), (Script::Hiragana, 5), (Script::Latin, 2)
+        ]);
     }
 
     #[test]
-    fn test_is_latin() {
-        assert_eq!(is_latin('z'), true);
-        assert_eq!(is_latin('A'), true);
-        assert_eq!(is_latin('č'), true);
-        assert_eq!(is_latin('š'), true);
-        assert_eq!(is_latin('Ĵ'), true);
+    fn test_char_script_latin() {
+        assert_eq!(char_script('z'), Some(Script::Latin));
+        assert_eq!(char_script('A'), Some(Script::Latin));
+        assert_eq!(char_script('č'), Some(Script::Latin));
+        assert_eq!(char_script('š'), Some(Script::Latin));
+        assert_eq!(char_script('Ĵ'), Some(Script::Latin));
 
-        assert_eq!(is_latin('ж'), false);
+        assert_ne!(char_script('ж'), Some(Script::Latin));
     }
 
     #[test]
-    fn test_is_cyrillic() {
-        assert_eq!(is_cyrillic('а'), true);
-        assert_eq!(is_cyrillic('Я'), true);
-        assert_eq!(is_cyrillic('Ґ'), true);
-        assert_eq!(is_cyrillic('ї'), true);
-        assert_eq!(is_cyrillic('Ꙕ'), true);
+    fn test_char_script_cyrillic() {
+        assert_eq!(char_script('а'), Some(Script::Cyrillic));
+        assert_eq!(char_script('Я'), Some(Script::Cyrillic));
+        assert_eq!(char_script('Ґ'), Some(Script::Cyrillic));
+        assert_eq!(char_script('ї'), Some(Script::Cyrillic));
+        assert_eq!(char_script('Ꙕ'), Some(Script::Cyrillic));
 
-        assert_eq!(is_cyrillic('L'), false);
+        assert_ne!(char_script('L'), Some(Script::Cyrillic));
     }
 
     #[test]
-    fn test_is_ethiopic() {
-        assert_eq!(is_ethiopic('ፚ'), true);
-        assert_eq!(is_ethiopic('ᎀ'), true);
+    fn test_char_script_ethiopic() {
+        assert_eq!(char_script('ፚ'), Some(Script::Ethiopic));
+        assert_eq!(char_script('ᎀ'), Some(Script::Ethiopic));
 
-        assert_eq!(is_ethiopic('а'), false);
-        assert_eq!(is_ethiopic('L'), false);
+        assert_ne!(char_script('а'), Some(Script::Ethiopic));
+        assert_ne!(char_script('L'), Some(Script::Ethiopic));
     }
 
     #[test]
-    fn test_is_georgian() {
-        assert_eq!(is_georgian('რ'), true);
-        assert_eq!(is_georgian('ж'), false);
+    fn test_char_script_georgian() {
+        assert_eq!(char_script('რ'), Some(Script::Georgian));
+        assert_ne!(char_script('ж'), Some(Script::Georgian));
     }
 
     #[test]
-    fn test_is_bengali() {
-        assert_eq!(is_bengali('ই'), true);
-        assert_eq!(is_bengali('z'), false);
+    fn test_char_script_bengali() {
+        assert_eq!(char_script('ই'), Some(Script::Bengali));
+        assert_ne!(char_script('z'), Some(Script::Bengali));
     }
 
     #[test]
-    fn test_is_katakana() {
-        assert_eq!(is_katakana('カ'), true);
-        assert_eq!(is_katakana('f'), false);
+    fn test_char_script_katakana() {
+        assert_eq!(char_script('カ'), Some(Script::Katakana));
+        assert_ne!(char_script('f'), Some(Script::Katakana));
     }
 
     #[test]
-    fn test_is_hiragana() {
-        assert_eq!(is_hiragana('ひ'), true);
-        assert_eq!(is_hiragana('a'), false);
+    fn test_char_script_hiragana() {
+        assert_eq!(char_script('ひ'), Some(Script::Hiragana));
+        assert_ne!(char_script('a'), Some(Script::Hiragana));
     }
 
     #[test]
-    fn test_is_hangul() {
-        assert_eq!(is_hangul('ᄁ'), true);
-        assert_eq!(is_hangul('t'), false);
+    fn test_char_script_hangul() {
+        assert_eq!(char_script('ᄁ'), Some(Script::Hangul));
+        assert_ne!(char_script('t'), Some(Script::Hangul));
     }
 
     #[test]
-    fn test_is_greek() {
-        assert_eq!(is_greek('φ'), true);
-        assert_eq!(is_greek('ф'), false);
+    fn test_char_script_greek() {
+        assert_eq!(char_script('φ'), Some(Script::Greek));
+        assert_ne!(char_script('ф'), Some(Script::Greek));
     }
 
     #[test]
-    fn test_is_kannada() {
-        assert_eq!(is_kannada('ಡ'), true);
-        assert_eq!(is_kannada('S'), false);
+    fn test_char_script_kannada() {
+        assert_eq!(char_script('ಡ'), Some(Script::Kannada));
+        assert_ne!(char_script('S'), Some(Script::Kannada));
     }
 
     #[test]
-    fn test_is_tamil() {
-        assert_eq!(is_tamil('ஐ'), true);
-        assert_eq!(is_tamil('Ж'), false);
+    fn test_char_script_tamil() {
+        assert_eq!(char_script('ஐ'), Some(Script::Tamil));
+        assert_ne!(char_script('Ж'), Some(Script::Tamil));
     }
 
     #[test]
-    fn test_is_thai() {
-        assert_eq!(is_thai('ก'), true);
-        assert_eq!(is_thai('๛'), true);
-        assert_eq!(is_thai('Ж'), false);
+    fn test_char_script_thai() {
+        assert_eq!(char_script('ก'), Some(Script::Thai));
+        assert_eq!(char_script('๛'), Some(Script::Thai));
+        assert_ne!(char_script('Ж'), Some(Script::Thai));
     }
 
     #[test]
-    fn test_is_gujarati() {
-        assert_eq!(is_gujarati('ઁ'), true);
-        assert_eq!(is_gujarati('૱'), true);
-        assert_eq!(is_gujarati('Ж'), false);
+    fn test_char_script_gujarati() {
+        assert_eq!(char_script('ઁ'), Some(Script::Gujarati));
+        assert_eq!(char_script('૱'), Some(Script::Gujarati));
+        assert_ne!(char_script('Ж'), Some(Script::Gujarati));
     }
 
     #[test]
-    fn test_is_gurmukhi() {
-        assert_eq!(is_gurmukhi('ਁ'), true);
-        assert_eq!(is_gurmukhi('ੴ'), true);
-        assert_eq!(is_gurmukhi('Ж'), false);
+    fn test_char_script_gurmukhi() {
+        assert_eq!(char_script('ਁ'), Some(Script::Gurmukhi));
+        assert_eq!(char_script('ੴ'), Some(Script::Gurmukhi));
+        assert_ne!(char_script('Ж'), Some(Script::Gurmukhi));
     }
 
     #[test]
-    fn test_is_telugu() {
-        assert_eq!(is_telugu('ఁ'), true);
-        assert_eq!(is_telugu('౿'), true);
-        assert_eq!(is_telugu('Ж'), false);
+    fn test_char_script_telugu() {
+        assert_eq!(char_script('ఁ'), Some(Script::Telugu));
+        assert_eq!(char_script('౿'), Some(Script::Telugu));
+        assert_ne!(char_script('Ж'), Some(Script::Telugu));
     }
 
     #[test]
-    fn test_is_oriya() {
-        assert_eq!(is_oriya('ଐ'), true);
-        assert_eq!(is_oriya('୷'), true);
-        assert_eq!(is_oriya('౿'), false);
+    fn test_char_script_oriya() {
+        assert_eq!(char_script('ଐ'), Some(Script::Oriya));
+        assert_eq!(char_script('୷'), Some(Script::Oriya));
+        assert_ne!(char_script('౿'), Some(Script::Oriya));
     }
 }
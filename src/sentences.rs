@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+use detect::detect_with_options;
+use info::Info;
+use options::Options;
+
+// Chars which end a sentence, when followed by a whitespace or the end of the text.
+const TERMINATORS: &[char] = &[
+    '!', '.', '?',
+    '\u{0589}', // Armenian full stop
+    '\u{061F}', // Arabic question mark
+    '\u{06D4}', // Arabic full stop (Urdu)
+    '\u{0964}', '\u{0965}', // Devanagari danda and double danda
+    '\u{104A}', '\u{104B}', // Myanmar section and little section
+    '\u{1362}', '\u{1367}', // Ethiopic full stop and question mark
+    '\u{2026}', // Horizontal ellipsis
+];
+
+// Full-width chars which end a sentence right away, since CJK texts have no spaces between sentences.
+const CJK_TERMINATORS: &[char] = &[
+    '\u{3002}', // Ideographic full stop
+    '\u{FF01}', // Fullwidth exclamation mark
+    '\u{FF0E}', // Fullwidth full stop
+    '\u{FF1F}', // Fullwidth question mark
+    '\u{FF61}', // Halfwidth ideographic full stop
+];
+
+// Quotes and brackets, which may close a sentence after a terminator.
+const CLOSING_CHARS: &[char] = &[
+    '"', '\'', ')', ']', '}', '»', '’', '”', '」', '』', '）', '】', '》', '〉',
+];
+
+/// Split a text into sentences.
+///
+/// A sentence ends with a terminator (e.g. `.`, `?`, `!`, `।`, `؟`) followed by a whitespace,
+/// or with a CJK terminator (`。`, `！`, `？`), possibly followed by closing quotes or brackets.
+/// A full stop followed by a lowercase word (like in "e.g. this") and an empty line
+/// are handled as well. Byte ranges of sentences do not include surrounding whitespaces.
+///
+/// # Example
+/// ```
+/// use whatlang::sentences;
+///
+/// let text = "Hello! How are you? 我很好。谢谢！";
+/// let sentences: Vec<&str> = sentences(text).map(|range| &text[range]).collect();
+/// assert_eq!(sentences, vec!["Hello!", "How are you?", "我很好。", "谢谢！"]);
+/// ```
+pub fn sentences(text: &str) -> Sentences<'_> {
+    Sentences { text, pos: 0 }
+}
+
+/// An iterator over byte ranges of sentences in a text, created by [sentences](fn.sentences.html).
+#[derive(Debug, Clone)]
+pub struct Sentences<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.text;
+        let start = text.len() - text[self.pos..].trim_start().len();
+        if start == text.len() {
+            self.pos = start;
+            return None;
+        }
+
+        let mut end = start;
+        while let Some(ch) = text[end..].chars().next() {
+            end += ch.len_utf8();
+            if ch == '\n' && starts_with_empty_line(&text[end..]) {
+                end -= 1;
+                break;
+            }
+
+            let mut is_cjk = CJK_TERMINATORS.contains(&ch);
+            if !is_cjk && !TERMINATORS.contains(&ch) {
+                continue;
+            }
+            // Take all the terminators and closing chars, e.g. `?!` or `."`
+            for next in text[end..].chars() {
+                if CJK_TERMINATORS.contains(&next) {
+                    is_cjk = true;
+                } else if !TERMINATORS.contains(&next) && !CLOSING_CHARS.contains(&next) {
+                    break;
+                }
+                end += next.len_utf8();
+            }
+            if is_cjk || is_sentence_end(ch, &text[end..]) {
+                break;
+            }
+        }
+
+        self.pos = end;
+        let sentence = text[start..end].trim_end();
+        Some(start..start + sentence.len())
+    }
+}
+
+// A terminator ends a sentence, if it's followed by a whitespace or the end of the text.
+// A full stop followed by a lowercase word is rather an abbreviation, e.g. "etc. and".
+fn is_sentence_end(terminator: char, rest: &str) -> bool {
+    match rest.chars().next() {
+        None => true,
+        Some(ch) if ch.is_whitespace() => {
+            terminator != '.' || !rest.trim_start().chars().next().map_or(false, char::is_lowercase)
+        }
+        _ => false
+    }
+}
+
+// Whether the text after a line break starts with an empty line.
+fn starts_with_empty_line(rest: &str) -> bool {
+    match rest.find('\n') {
+        Some(pos) => rest[..pos].trim().is_empty(),
+        None => false
+    }
+}
+
+/// Detect a language and a script of every sentence of a text,
+/// see [sentences](fn.sentences.html) for how the text is split.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_sentences, Lang};
+///
+/// let text = "Введите запрос, чтобы найти нужную информацию. There is no reason not to learn Esperanto.";
+/// let langs: Vec<_> = detect_sentences(text)
+///     .map(|(_, info)| info.map(|info| info.lang()))
+///     .collect();
+/// assert_eq!(langs, vec![Some(Lang::Rus), Some(Lang::Eng)]);
+/// ```
+pub fn detect_sentences(text: &str) -> DetectSentences<'_> {
+    detect_sentences_with_options(text, &Options::default())
+}
+
+/// Detect a language and a script of every sentence of a text, using the given options.
+///
+/// See [`detect_sentences`](fn.detect_sentences.html) for details.
+pub fn detect_sentences_with_options<'a>(text: &'a str, options: &Options) -> DetectSentences<'a> {
+    DetectSentences { sentences: sentences(text), options: options.clone() }
+}
+
+/// An iterator over byte ranges of sentences in a text with results of their detection,
+/// created by [detect_sentences](fn.detect_sentences.html).
+#[derive(Debug, Clone)]
+pub struct DetectSentences<'a> {
+    sentences: Sentences<'a>,
+    options: Options
+}
+
+impl<'a> Iterator for DetectSentences<'a> {
+    type Item = (Range<usize>, Option<Info>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.sentences.next()?;
+        let info = detect_with_options(&self.sentences.text[range.clone()], &self.options);
+        Some((range, info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lang::Lang;
+
+    fn split(text: &str) -> Vec<&str> {
+        sentences(text).map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_sentences() {
+        assert_eq!(split(""), Vec::<&str>::new());
+        assert_eq!(split("  \n "), Vec::<&str>::new());
+        assert_eq!(split("Hello world"), vec!["Hello world"]);
+        assert_eq!(split(" One. Two?! Three… "), vec!["One.", "Two?!", "Three…"]);
+        assert_eq!(split("He said: \"Stop.\" Then he left."), vec!["He said: \"Stop.\"", "Then he left."]);
+        assert_eq!(split("Pi is 3.14, e.g. about three. Yes"), vec!["Pi is 3.14, e.g. about three.", "Yes"]);
+        assert_eq!(split("Title\n\nFirst line\nsecond line"), vec!["Title", "First line\nsecond line"]);
+    }
+
+    #[test]
+    fn test_sentences_with_other_scripts() {
+        assert_eq!(split("今日は晴れです。明日は雨です！「本当？」はい"), vec!["今日は晴れです。", "明日は雨です！", "「本当？」", "はい"]);
+        assert_eq!(split("यह एक वाक्य है। यह दूसरा है।"), vec!["यह एक वाक्य है।", "यह दूसरा है।"]);
+        assert_eq!(split("كيف حالك؟ أنا بخير."), vec!["كيف حالك؟", "أنا بخير."]);
+    }
+
+    #[test]
+    fn test_detect_sentences_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Eng, Lang::Deu]);
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. There is no reason not to learn Esperanto.";
+        let langs: Vec<_> = detect_sentences_with_options(text, &options)
+            .map(|(range, info)| (&text[range], info.map(|info| info.lang())))
+            .collect();
+        assert_eq!(langs, vec![
+            ("Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten.", Some(Lang::Deu)),
+            ("There is no reason not to learn Esperanto.", Some(Lang::Eng))
+        ]);
+    }
+}
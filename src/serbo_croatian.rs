@@ -0,0 +1,115 @@
+use lang::Lang;
+
+// Serbian and Croatian in Latin script are almost identical, so their trigram profiles
+// can not tell them apart reliably. The most visible difference is the reflex of the old
+// vowel "yat": Serbian standard is Ekavian ("reka", "mleko"), Croatian is Ijekavian
+// ("rijeka", "mlijeko"). There are also some differences in vocabulary ("hleb" and "kruh").
+//
+// Words are sorted, to be found with binary search.
+const SERBIAN_WORDS: &[&str] = &[
+    "bela", "beli", "belo", "cela", "celi", "celo", "cena", "cene", "ceo", "deca", "dece",
+    "dete", "devojka", "gde", "hiljada", "hiljade", "hleb", "istorija", "istorije", "istoriji",
+    "juče", "lep", "lepa", "lepo", "leva", "levo", "levoj", "mera", "mere", "mesta", "mesto",
+    "mleka", "mleko", "nedelja", "nedelje", "negde", "ovde", "posle", "pre", "primer",
+    "primera", "reka", "reke", "reč", "reči", "svest", "svesti", "svet", "sveta", "svetu",
+    "svešću", "tela", "telo", "uvek", "vek", "veka", "vera", "vere", "verovatno", "čovek",
+    "čoveka",
+];
+
+const CROATIAN_WORDS: &[&str] = &[
+    "bijela", "bijeli", "bijelo", "cijela", "cijeli", "cijelo", "cijena", "cijene", "dijete",
+    "djeca", "djece", "djevojka", "gdje", "jučer", "kruh", "lijep", "lijepa", "lijepo",
+    "lijeva", "lijevo", "lijevoj", "mjera", "mjere", "mjesta", "mjesto", "mlijeka", "mlijeko",
+    "nedjelja", "nedjelje", "negdje", "ovdje", "poslije", "povijest", "prije", "primjer",
+    "primjera", "rijeka", "rijeke", "riječ", "riječi", "svijest", "svijesti", "svijet",
+    "svijeta", "svijetu", "sviješću", "također", "tijela", "tijelo", "tisuća", "tisuće", "tko",
+    "uvijek", "vijek", "vijeka", "vjera", "vjere", "vjerojatno", "čovjek", "čovjeka",
+];
+
+// Choose between Serbian and Croatian written in Latin script by the words, which are used
+// only in one of the languages. The trigram profile of Latin Serbian is transliterated
+// from the Cyrillic one, so Serbian is chosen only when the text has more Serbian words.
+// Returns None if `lang` is neither Serbian nor Croatian.
+pub(crate) fn resolve_serbo_croatian(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_serbo_croatian(lang) {
+        return None;
+    }
+    resolve_serbo_croatian_by_counts(count_serbo_croatian_words(text), lang)
+}
+
+// Same as resolve_serbo_croatian, but with the words already counted by count_serbo_croatian_words.
+pub(crate) fn resolve_serbo_croatian_by_counts(words: SerboCroatianWords, lang: Lang) -> Option<Lang> {
+    if !is_serbo_croatian(lang) {
+        return None;
+    }
+
+    if words.serbian > words.croatian {
+        Some(Lang::Srp)
+    } else {
+        Some(Lang::Hrv)
+    }
+}
+
+// Numbers of words, which are used only in Serbian or only in Croatian.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SerboCroatianWords {
+    pub serbian: usize,
+    pub croatian: usize
+}
+
+impl SerboCroatianWords {
+    pub fn add(&mut self, other: SerboCroatianWords) {
+        self.serbian += other.serbian;
+        self.croatian += other.croatian;
+    }
+}
+
+pub(crate) fn count_serbo_croatian_words(text: &str) -> SerboCroatianWords {
+    let mut result = SerboCroatianWords::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(SERBIAN_WORDS, &word) {
+            result.serbian += 1;
+        } else if contains(CROATIAN_WORDS, &word) {
+            result.croatian += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_serbo_croatian(lang: Lang) -> bool {
+    lang == Lang::Srp || lang == Lang::Hrv
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted() {
+        for words in [SERBIAN_WORDS, CROATIAN_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_serbo_croatian() {
+        let text = "Juče sam kupio hleb i mleko, a posle smo otišli kod prijatelja.";
+        assert_eq!(resolve_serbo_croatian(text, Lang::Hrv), Some(Lang::Srp));
+
+        let text = "Jučer sam kupio kruh i mlijeko, a poslije smo otišli kod prijatelja.";
+        assert_eq!(resolve_serbo_croatian(text, Lang::Srp), Some(Lang::Hrv));
+
+        // Without evidence Croatian is preferred
+        let text = "Službeni je jezik u državi.";
+        assert_eq!(resolve_serbo_croatian(text, Lang::Srp), Some(Lang::Hrv));
+
+        assert_eq!(resolve_serbo_croatian(text, Lang::Slv), None);
+    }
+}
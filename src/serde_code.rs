@@ -0,0 +1,108 @@
+//! Represent languages and scripts by their codes instead of enum names,
+//! when they are serialized with serde, e.g. `"ukr"` instead of `"Ukr"`.
+//!
+//! # Example
+//! ```
+//! extern crate serde;
+//! extern crate serde_json;
+//! extern crate whatlang;
+//!
+//! use whatlang::{Lang, Script};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(with = "whatlang::serde_code::lang")]
+//!     lang: Lang,
+//!     #[serde(with = "whatlang::serde_code::script")]
+//!     script: Script,
+//! }
+//!
+//! # fn main() {
+//! let config: Config = serde_json::from_str(r#"{"lang": "en-US", "script": "Latn"}"#).unwrap();
+//! assert_eq!(config.lang, Lang::Eng);
+//! assert_eq!(serde_json::to_string(&config).unwrap(), r#"{"lang":"eng","script":"Latn"}"#);
+//! # }
+//! ```
+
+/// Serialize `Lang` as ISO 639-3 code, deserialize it with [Lang::from_code](../../enum.Lang.html#method.from_code).
+pub mod lang {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use lang::Lang;
+
+    pub fn serialize<S: Serializer>(lang: &Lang, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(lang.code())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lang, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(Error::custom)
+    }
+}
+
+/// Serialize `Script` as ISO 15924 code, deserialize it with [Script::from_code](../../enum.Script.html#method.from_code).
+pub mod script {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use script::Script;
+
+    pub fn serialize<S: Serializer>(script: &Script, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(script.code())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Script, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        code.parse().map_err(Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use lang::Lang;
+    use script::Script;
+    use detect::detect;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Codes {
+        #[serde(with = "super::lang")]
+        lang: Lang,
+        #[serde(with = "super::script")]
+        script: Script,
+    }
+
+    #[test]
+    fn test_serialize_enum_names() {
+        assert_eq!(serde_json::to_string(&Lang::Ukr).unwrap(), r#""Ukr""#);
+        assert_eq!(serde_json::from_str::<Script>(r#""Cyrillic""#).unwrap(), Script::Cyrillic);
+
+        let info = detect("Та нічого, все нормально. А в тебе як?").unwrap();
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.starts_with(r#"{"lang":"Ukr","script":"Cyrillic","confidence":"#));
+        assert_eq!(serde_json::from_str::<::info::Info>(&json).unwrap(), info);
+    }
+
+    #[test]
+    fn test_serialize_model_version() {
+        let info = detect("Та нічого, все нормально. А в тебе як?").unwrap();
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.contains(r#""model_version":{"algorithm":"#), "{}", json);
+
+        // Results stored without the version are never of the current one
+        let json = r#"{"lang":"Ukr","script":"Cyrillic","confidence":1.0,"secondary_script":null,"partial":false}"#;
+        let info = serde_json::from_str::<::info::Info>(json).unwrap();
+        assert_ne!(info.model_version(), ::version::model_version());
+    }
+
+    #[test]
+    fn test_serialize_codes() {
+        let codes = Codes { lang: Lang::Srp, script: Script::Latin };
+        let json = serde_json::to_string(&codes).unwrap();
+        assert_eq!(json, r#"{"lang":"srp","script":"Latn"}"#);
+        assert_eq!(serde_json::from_str::<Codes>(&json).unwrap(), codes);
+
+        let error = serde_json::from_str::<Codes>(r#"{"lang":"xx","script":"Latn"}"#).unwrap_err();
+        assert!(error.to_string().starts_with(r#"unknown language code: "xx""#));
+    }
+}
@@ -0,0 +1,141 @@
+use lang::Lang;
+
+// Spanish and Portuguese share much of their vocabulary, so short texts of one language are often
+// detected as the other one. But only Spanish has "ñ", while only Portuguese has the nasal vowels
+// "ã" and "õ", "ç" and the circumflex in "ê" and "ô". The most common words also differ:
+// articles ("un" and "um", "una" and "uma"), prepositions ("en" and "em", "con" and "com"),
+// pronouns ("yo" and "eu"), adverbs ("muy" and "muito").
+const SPANISH_LETTERS: &[char] = &['ñ'];
+const PORTUGUESE_LETTERS: &[char] = &['ã', 'ç', 'ê', 'ô', 'õ'];
+
+// Words are sorted, to be found with binary search. Words of both languages are not included
+// even if they are much more common in one of them, e.g. "no" is "not" in Spanish, but "in the" in Portuguese.
+const SPANISH_WORDS: &[&str] = &[
+    "ahora", "al", "aquí", "bien", "con", "cuando", "del", "después", "donde", "el", "ella", "ellos",
+    "en", "es", "eso", "esto", "hay", "hoy", "la", "las", "lo", "los", "muchas", "mucho", "muy",
+    "nosotros", "pero", "quiero", "qué", "siempre", "son", "su", "sus", "también", "tengo", "un", "una",
+    "y", "yo", "él",
+];
+
+const PORTUGUESE_WORDS: &[&str] = &[
+    "agora", "ao", "aqui", "as", "bem", "com", "depois", "do", "ela", "elas", "ele", "eles", "em",
+    "eu", "hoje", "isso", "isto", "mais", "muita", "muito", "não", "nós", "onde", "quando", "quero",
+    "sempre", "seu", "sua", "são", "também", "tenho", "um", "uma", "você", "é",
+];
+
+// Choose between Spanish and Portuguese by the letters and words, which are used only in one
+// of the languages. Returns None if `lang` is neither Spanish nor Portuguese,
+// or if the text has as much evidence of one language as of the other one.
+pub(crate) fn resolve_spanish_portuguese(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_spanish_portuguese(lang) {
+        return None;
+    }
+    let mut evidence = count_spanish_portuguese_letters(text);
+    evidence.add(count_spanish_portuguese_words(text));
+    resolve_spanish_portuguese_by_counts(evidence, lang)
+}
+
+// Same as resolve_spanish_portuguese, but with the letters and words already counted.
+pub(crate) fn resolve_spanish_portuguese_by_counts(evidence: SpanishPortugueseEvidence, lang: Lang) -> Option<Lang> {
+    if !is_spanish_portuguese(lang) {
+        return None;
+    }
+
+    if evidence.spanish > evidence.portuguese {
+        Some(Lang::Spa)
+    } else if evidence.portuguese > evidence.spanish {
+        Some(Lang::Por)
+    } else {
+        None
+    }
+}
+
+// Numbers of letters and words, which are used only in Spanish or only in Portuguese.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SpanishPortugueseEvidence {
+    pub spanish: usize,
+    pub portuguese: usize
+}
+
+impl SpanishPortugueseEvidence {
+    pub fn add(&mut self, other: SpanishPortugueseEvidence) {
+        self.spanish += other.spanish;
+        self.portuguese += other.portuguese;
+    }
+}
+
+pub(crate) fn count_spanish_portuguese_letters(text: &str) -> SpanishPortugueseEvidence {
+    let mut result = SpanishPortugueseEvidence::default();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if SPANISH_LETTERS.contains(&ch) {
+            result.spanish += 1;
+        } else if PORTUGUESE_LETTERS.contains(&ch) {
+            result.portuguese += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn count_spanish_portuguese_words(text: &str) -> SpanishPortugueseEvidence {
+    let mut result = SpanishPortugueseEvidence::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        let word = word.to_lowercase();
+        if contains(SPANISH_WORDS, &word) {
+            result.spanish += 1;
+        } else if contains(PORTUGUESE_WORDS, &word) {
+            result.portuguese += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_spanish_portuguese(lang: Lang) -> bool {
+    lang == Lang::Spa || lang == Lang::Por
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [SPANISH_WORDS, PORTUGUESE_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in SPANISH_WORDS.iter() {
+            assert!(!contains(PORTUGUESE_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_spanish_portuguese() {
+        let text = "Yo no quiero ir a la playa hoy.";
+        assert_eq!(resolve_spanish_portuguese(text, Lang::Por), Some(Lang::Spa));
+
+        let text = "Eu não quero ir à praia hoje.";
+        assert_eq!(resolve_spanish_portuguese(text, Lang::Spa), Some(Lang::Por));
+
+        // Without evidence the language is not resolved
+        let text = "Nunca fumaba.";
+        assert_eq!(resolve_spanish_portuguese(text, Lang::Por), None);
+
+        assert_eq!(resolve_spanish_portuguese(text, Lang::Ita), None);
+    }
+
+    #[test]
+    fn test_count_spanish_portuguese_evidence() {
+        let text = "El niño come con su abuela.";
+        assert_eq!(count_spanish_portuguese_letters(text), SpanishPortugueseEvidence { spanish: 1, portuguese: 0 });
+        assert_eq!(count_spanish_portuguese_words(text), SpanishPortugueseEvidence { spanish: 3, portuguese: 0 });
+
+        let text = "VOCÊ TEM UMA CANÇÃO?";
+        assert_eq!(count_spanish_portuguese_letters(text), SpanishPortugueseEvidence { spanish: 0, portuguese: 3 });
+        assert_eq!(count_spanish_portuguese_words(text), SpanishPortugueseEvidence { spanish: 0, portuguese: 2 });
+    }
+}
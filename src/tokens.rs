@@ -0,0 +1,61 @@
+use incremental::IncrementalDetector;
+use info::Info;
+use options::Options;
+
+/// Detect a language and a script of a text, which is already split into tokens (e.g. words).
+/// Tokens are not joined into a new string: they are fed one by one to
+/// [IncrementalDetector](struct.IncrementalDetector.html), as if they were separated by spaces.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect_tokens, Lang};
+///
+/// let tokens = vec!["Ĉu", "vi", "ne", "volas", "eklerni", "Esperanton", "?"];
+/// let info = detect_tokens(tokens).unwrap();
+/// assert_eq!(info.lang(), Lang::Epo);
+/// ```
+pub fn detect_tokens<'a, I: IntoIterator<Item = &'a str>>(tokens: I) -> Option<Info> {
+    detect_tokens_with_options(tokens, &Options::default())
+}
+
+/// Detect a language and a script of a text, which is already split into tokens, using the given options.
+///
+/// See [`detect_tokens`](fn.detect_tokens.html) for details.
+pub fn detect_tokens_with_options<'a, I: IntoIterator<Item = &'a str>>(tokens: I, options: &Options) -> Option<Info> {
+    let mut detector = IncrementalDetector::with_options(options.clone());
+    for token in tokens {
+        detector.feed(token);
+        detector.feed(" ");
+    }
+    detector.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use detect::detect_with_options;
+    use lang::Lang;
+
+    #[test]
+    fn test_detect_tokens_matches_detect() {
+        let texts = [
+            "Введите запрос, чтобы найти нужную информацию",
+            "Gde je reka? Ovde, uvek ovde, deca se igraju.",
+            "我们今天去北京大学参观了图书馆和博物馆",
+        ];
+        let options = Options::new();
+        for text in texts.iter() {
+            let tokens: Vec<&str> = text.split_whitespace().collect();
+            assert_eq!(detect_tokens(tokens.iter().cloned()), detect_with_options(text, &options), "{}", text);
+        }
+        assert_eq!(detect_tokens(vec![]), None);
+        assert_eq!(detect_tokens(vec!["", " "]), None);
+    }
+
+    #[test]
+    fn test_detect_tokens_with_options() {
+        let options = Options::new().set_whitelist(vec![Lang::Eng, Lang::Deu]);
+        let tokens = "Der Sommer war sehr heiß".split(' ');
+        assert_eq!(detect_tokens_with_options(tokens, &options).unwrap().lang(), Lang::Deu);
+    }
+}
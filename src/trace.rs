@@ -0,0 +1,93 @@
+// Spans and events of detection with `tracing` feature. Without the feature the macros
+// expand to nothing, so values which are computed only for an event are not allowed.
+
+// Enters a debug span till the end of the enclosing block.
+#[cfg(feature = "tracing")]
+macro_rules! enter_span {
+    ($($arg:tt)*) => {
+        let _span = ::tracing::debug_span!($($arg)*).entered();
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! enter_span {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        ::tracing::debug!($($arg)*);
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug_event {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tracing::{Event, Metadata, Subscriber};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::field::{Field, Visit};
+    use tracing::subscriber::with_default;
+
+    use detect::detect;
+
+    // Collects names of spans and messages of events.
+    #[derive(Default)]
+    struct Collector {
+        next_id: AtomicUsize,
+        records: Mutex<Vec<String>>
+    }
+
+    struct Message<'a>(&'a mut String);
+
+    impl<'a> Visit for Message<'a> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes) -> Id {
+            self.records.lock().unwrap().push(span.metadata().name().to_string());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) as u64 + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event) {
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.records.lock().unwrap().push(message);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_detection_is_traced() {
+        let collector = Arc::new(Collector::default());
+        with_default(collector.clone(), || detect("Der Sommer war sehr heiß und die Kinder spielten im Garten."));
+        let records = collector.records.lock().unwrap();
+        for &record in ["detect", "detected script", "filtered candidates", "score", "chose language"].iter() {
+            assert!(records.iter().any(|r| r == record), "{} not in {:?}", record, *records);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use fnv::FnvHashMap;
+
+use constants::LANG_PROFILE_SIZE;
+use trigrams::{Ngram, TrigramCounter, sort_by_count};
+use utils::is_stop_char;
+
+/// Build a trigram profile of a language from texts in it, in the same format as
+/// the built-in profiles: the 300 most common trigrams, the most common go first.
+/// Trigrams do not span two texts.
+///
+/// Profiles of the built-in languages are stored in `misc/data.json`, where trigrams
+/// of a profile are joined by `|`.
+///
+/// # Example
+/// ```
+/// use whatlang::train_profile;
+///
+/// let texts = ["Ĉu vi ne volas eklerni Esperanton?", "Bonvolu! Estas unu de la plej bonaj aferoj!"];
+/// let profile = train_profile(texts.iter());
+/// assert!(profile.len() <= 300);
+/// assert!(profile.contains(&" vi".to_string()));
+/// ```
+pub fn train_profile<I, S>(texts: I) -> Vec<String>
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+{
+    let mut counts: FnvHashMap<Ngram, u32> = FnvHashMap::default();
+    for text in texts {
+        let mut counter = TrigramCounter::with_capacity(0);
+        counter.push_str(text.as_ref());
+        for (trigram, count) in counter.finish() {
+            *counts.entry(trigram).or_insert(0) += count;
+        }
+    }
+    sort_by_count(counts)
+        .into_iter()
+        .take(LANG_PROFILE_SIZE)
+        .map(|(_, trigram)| trigram.to_string())
+        .collect()
+}
+
+/// The `size` most common words of texts, lowercased, the most common go first.
+/// Words are separated by spaces, digits, punctuation and symbols.
+/// Lists of words are useful to tell apart very close languages, like whatlang does
+/// for Serbian and Croatian.
+///
+/// # Example
+/// ```
+/// use whatlang::train_word_list;
+///
+/// let texts = ["Gde je reka? Ovde, uvek ovde.", "Ovde je dobro."];
+/// assert_eq!(train_word_list(texts.iter(), 2), vec!["ovde", "je"]);
+/// ```
+pub fn train_word_list<I, S>(texts: I, size: usize) -> Vec<String>
+    where I: IntoIterator<Item = S>, S: AsRef<str>
+{
+    let mut counts: FnvHashMap<String, u32> = FnvHashMap::default();
+    for text in texts {
+        for word in text.as_ref().split(|ch: char| is_stop_char(ch) || ch.is_numeric()).filter(|word| !word.is_empty()) {
+            *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+    sort_by_count(counts)
+        .into_iter()
+        .take(size)
+        .map(|(_, word)| word)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_train_profile() {
+        let empty: &[&str] = &[];
+        assert!(train_profile(empty).is_empty());
+        assert_eq!(train_profile(&["ab", "ab"]), vec!["ab ", " ab"]);
+        // Ties are ordered like trigrams of texts in detection
+        assert_eq!(train_profile(&["aaaa bb", "bb"]), vec!["bb ", "aaa", " bb", "aa ", "a b", " aa"]);
+
+        let text = "Der Sommer war sehr heiß und die Kinder spielten jeden Tag im Garten. ".repeat(50);
+        let profile = train_profile(&[text]);
+        assert!(profile.len() <= LANG_PROFILE_SIZE);
+        assert!(profile.iter().all(|trigram| trigram.chars().count() == 3));
+    }
+
+    #[test]
+    fn test_train_word_list() {
+        assert_eq!(train_word_list(&["A a, b; C c c 42"], 10), vec!["c", "a", "b"]);
+        assert!(train_word_list(&["Word"], 0).is_empty());
+    }
+}
@@ -1,55 +1,255 @@
-use utils::is_stop_char;
+use std::char;
+use std::fmt;
+use std::hash::Hash;
+use std::mem;
+use utils::{fold_case, has_turkic_letters, is_stop_char, is_unspaced_script_char};
 use fnv::FnvHashMap;
 use constants::TEXT_TRIGRAMS_SIZE;
 
 const MAX_INITIAL_HASH_CAPACITY: usize = 2048;
 
-pub fn get_trigrams_with_positions(text : &str) -> FnvHashMap<String, u32> {
+// Positions and counts of the most common n-grams (trigrams by default) of the given size.
+pub(crate) fn get_ngrams_with_positions(text: &str, size: usize) -> FnvHashMap<Ngram, (u32, u32)> {
+    let mut scratch = Scratch::default();
+    scratch.count_ngrams(text, size);
+    scratch.positions
+}
+
+/// Buffers for n-grams of a text, which are reused by repeated detections instead of
+/// allocating them for every text, see [Detector::detect_in](struct.Detector.html#method.detect_in).
+///
+/// Every thread needs its own scratch: it's `Send`, but is borrowed mutably during detection.
+#[derive(Debug, Clone, Default)]
+pub struct Scratch {
+    counts: FnvHashMap<Ngram, u32>,
+    sorted: Vec<(u32, Ngram)>,
+    positions: FnvHashMap<Ngram, (u32, u32)>,
+}
+
+impl Scratch {
+    /// Create an empty scratch, buffers are allocated by the first detection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The same as get_ngrams_with_positions, the result is valid until the next call.
+    pub(crate) fn ngrams_with_positions(&mut self, text: &str, size: usize) -> &FnvHashMap<Ngram, (u32, u32)> {
+        self.count_ngrams(text, size);
+        &self.positions
+    }
+
+    fn count_ngrams(&mut self, text: &str, size: usize) {
+        let mut counts = mem::take(&mut self.counts);
+        counts.clear();
+        counts.reserve(calculate_initial_hash_capacity(text));
+        let mut counter = TrigramCounter::with_counts(size, counts);
+        counter.push_str(text);
+        let counts = counter.finish();
+
+        self.sorted.clear();
+        self.sorted.extend(counts.iter().map(|(&ngram, &count)| (count, ngram)));
+        self.sorted.sort_by(|a, b| b.cmp(a));
+        self.positions.clear();
+        self.positions.extend(self.sorted.iter()
+            .take(TEXT_TRIGRAMS_SIZE)
+            .enumerate()
+            .map(|(i, &(count, ngram))| (ngram, (i as u32, count))));
+        self.counts = counts;
+    }
+}
 
-    // Sort in descending order by number of occurrences and trigrams
-    let mut count_vec: Vec<_> = count(text)
+/// Extract trigrams of a text with their numbers of occurrences, the same as they are
+/// used for language detection. The most common trigrams go first.
+///
+/// The text is lowercased (by Turkic rules for "I" and "İ", if it contains letters specific
+/// for Turkic languages), digits, punctuation and symbols are replaced with spaces,
+/// a space is added before and after every word, e.g. "Yes!" gives " ye", "yes" and "es ".
+/// Trigrams with two spaces are not counted.
+///
+/// Thai, Lao, Myanmar and Khmer are written without spaces between words, so no space is added
+/// around their runs of letters: their texts give only trigrams of letters in a row.
+///
+/// # Example
+/// ```
+/// use whatlang::extract_trigrams;
+///
+/// let trigrams = extract_trigrams("Banana!");
+/// assert_eq!(trigrams[0], ("ana".to_string(), 2));
+/// assert_eq!(trigrams.len(), 5);
+/// ```
+pub fn extract_trigrams(text: &str) -> Vec<(String, u32)> {
+    sort_by_count(count(text))
         .into_iter()
-        .map(|(trigram, count)| (count, trigram))
-        .collect();
-    count_vec.sort_by(|a, b| b.cmp(a));
+        .map(|(count, trigram)| (trigram.to_string(), count))
+        .collect()
+}
 
-    count_vec.into_iter()
+// Position and count of an n-gram of a profile among the n-grams of a text.
+#[inline]
+pub(crate) fn find_ngram(ngrams: &FnvHashMap<Ngram, (u32, u32)>, ngram: &str) -> Option<(u32, u32)> {
+    Ngram::new(ngram).and_then(|ngram| ngrams.get(&ngram).cloned())
+}
+
+// Positions of the most common trigrams, sorted in descending order
+// by number of occurrences and trigrams, with their numbers of occurrences.
+pub(crate) fn trigram_positions(counts: FnvHashMap<Ngram, u32>) -> FnvHashMap<Ngram, (u32, u32)> {
+    sort_by_count(counts).into_iter()
         .take(TEXT_TRIGRAMS_SIZE)
         .enumerate()
-        .map(|(i, (_, trigram))| (trigram, i as u32))
+        .map(|(i, (count, trigram))| (trigram, (i as u32, count)))
         .collect()
 }
 
-fn count(text : &str) -> FnvHashMap<String, u32> {
-    let hash_capacity = calculate_initial_hash_capacity(text);
-    let mut counter_hash : FnvHashMap<String, u32> = FnvHashMap::with_capacity_and_hasher(hash_capacity, Default::default());
-
-    // iterate through the string and count trigrams
-    let mut chars_iter = text.chars().map(to_trigram_char).flat_map(char::to_lowercase).chain(Some(' '));
-    let mut c1 = ' ';
-    // unwrap is safe, because we always chain a space character on the end of the iterator
-    let mut c2 = chars_iter.next().unwrap();
-    for cur_char in chars_iter {
-        let c3 = cur_char;
-        if !(c2 == ' ' && (c1 == ' ' || c3 == ' ')) {
-            let mut trigram = String::with_capacity(c1.len_utf8() + c2.len_utf8() + c3.len_utf8());
-            trigram.push(c1);
-            trigram.push(c2);
-            trigram.push(c3);
-            let count = counter_hash.entry(trigram).or_insert(0);
+pub(crate) fn sort_by_count<K: Ord + Hash>(counts: FnvHashMap<K, u32>) -> Vec<(u32, K)> {
+    let mut count_vec: Vec<_> = counts
+        .into_iter()
+        .map(|(trigram, count)| (count, trigram))
+        .collect();
+    count_vec.sort_by(|a, b| b.cmp(a));
+    count_vec
+}
+
+fn count(text : &str) -> FnvHashMap<Ngram, u32> {
+    let mut counter = TrigramCounter::with_capacity(calculate_initial_hash_capacity(text));
+    counter.push_str(text);
+    counter.finish()
+}
+
+// The longest n-grams, which can be counted.
+const MAX_NGRAM_SIZE: usize = 4;
+
+// Enough bits for any char (up to U+10FFFF) plus one.
+const CHAR_BITS: usize = 21;
+const CHAR_MASK: u128 = (1 << CHAR_BITS) - 1;
+
+// An n-gram packed into an integer, so counting n-grams of a text doesn't allocate.
+// Every char is stored plus one, so zero marks the end of a shorter n-gram,
+// and the first char takes the highest bits, so n-grams are ordered the same as strings.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Ngram(u128);
+
+impl Ngram {
+    // None if the string is longer than MAX_NGRAM_SIZE chars, so it can't be an n-gram of a text.
+    pub fn new(ngram: &str) -> Option<Self> {
+        let mut chars = ['\0'; MAX_NGRAM_SIZE];
+        let mut len = 0;
+        for ch in ngram.chars() {
+            if len == MAX_NGRAM_SIZE {
+                return None;
+            }
+            chars[len] = ch;
+            len += 1;
+        }
+        Some(Self::from_chars(&chars[..len]))
+    }
+
+    fn from_chars(chars: &[char]) -> Self {
+        debug_assert!(chars.len() <= MAX_NGRAM_SIZE);
+        let packed = chars.iter().fold(0, |packed, &ch| (packed << CHAR_BITS) | (ch as u128 + 1));
+        Ngram(packed << (CHAR_BITS * (MAX_NGRAM_SIZE - chars.len())))
+    }
+
+    pub fn packed(self) -> u128 {
+        self.0
+    }
+
+    fn chars(self) -> impl Iterator<Item = char> {
+        (0..MAX_NGRAM_SIZE)
+            .map(move |i| (self.0 >> (CHAR_BITS * (MAX_NGRAM_SIZE - 1 - i))) & CHAR_MASK)
+            .take_while(|&code| code != 0)
+            .filter_map(|code| char::from_u32(code as u32 - 1))
+    }
+}
+
+impl fmt::Display for Ngram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.chars() {
+            write!(f, "{}", ch)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Ngram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_string())
+    }
+}
+
+// Counts trigrams (or n-grams of another size) of a text, which can be pushed in several parts.
+// Trigrams on the boundaries of the parts are counted as in the whole text.
+#[derive(Debug, Clone)]
+pub(crate) struct TrigramCounter {
+    counts: FnvHashMap<Ngram, u32>,
+    size: usize,
+    // The last chars of the text, aligned to the end
+    last: [char; MAX_NGRAM_SIZE],
+    // Letters of Turkic languages were pushed, so the rest is lowercased by their rules
+    turkic: bool
+}
+
+impl TrigramCounter {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_size(3, capacity)
+    }
+
+    pub fn with_size(size: usize, capacity: usize) -> Self {
+        Self::with_counts(size, FnvHashMap::with_capacity_and_hasher(capacity, Default::default()))
+    }
+
+    // Counts are added to the given map.
+    fn with_counts(size: usize, counts: FnvHashMap<Ngram, u32>) -> Self {
+        assert!((2..=MAX_NGRAM_SIZE).contains(&size), "Unsupported n-gram size {}", size);
+        TrigramCounter {
+            counts,
+            size,
+            last: [' '; MAX_NGRAM_SIZE],
+            turkic: false
+        }
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.turkic = self.turkic || has_turkic_letters(text);
+        let turkic = self.turkic;
+        for ch in text.chars().map(to_trigram_char) {
+            fold_case(ch, turkic, |lower| self.push(lower));
+        }
+    }
+
+    fn push(&mut self, ch: char) {
+        self.last.rotate_left(1);
+        self.last[MAX_NGRAM_SIZE - 1] = ch;
+        let ngram = &self.last[MAX_NGRAM_SIZE - self.size..];
+        if !ngram.windows(2).any(is_uncounted_pair) {
+            let count = self.counts.entry(Ngram::from_chars(ngram)).or_insert(0);
             *count += 1;
         }
-        c1 = c2;
-        c2 = c3;
     }
 
-    counter_hash
+    // Counts of trigrams, the text is ended with a space.
+    pub fn finish(mut self) -> FnvHashMap<Ngram, u32> {
+        self.push(' ');
+        self.counts
+    }
 }
 
-// Convert punctuations and digits to a space.
+// N-grams with two spaces in a row are not counted. Neither are n-grams with a space next to
+// a letter of a script without spaces between words (e.g. Thai): a space there ends a phrase
+// rather than a word, so texts of these scripts give plain character n-grams.
+#[inline]
+fn is_uncounted_pair(pair: &[char]) -> bool {
+    match *pair {
+        [' ', ' '] => true,
+        [' ', ch] | [ch, ' '] => is_unspaced_script_char(ch),
+        _ => false
+    }
+}
+
+// Convert punctuations and digits to a space. Native digits (e.g. Arabic-Indic or Devanagari ones)
+// are counted for their scripts, but tell nothing about a language of the script.
 #[inline]
 fn to_trigram_char(ch : char) -> char {
-    if is_stop_char(ch) { ' ' } else { ch }
+    if is_stop_char(ch) || ch.is_numeric() { ' ' } else { ch }
 }
 
 // In order to improve performance, define the initial capacity for trigrams hash map,
@@ -94,6 +294,8 @@ mod tests {
         assert_not_valuable_trigram_chars(&['[', ']', '^', '\\', '`']);
         // 0x7B - 0x7E
         assert_not_valuable_trigram_chars(&['[', '|', '{', '}', '~']);
+        // native digits and other numerals
+        assert_not_valuable_trigram_chars(&['٠', '٩', '۵', '०', '९', '১', '๓', '²', 'Ⅻ', '〇']);
     }
 
 
@@ -101,7 +303,7 @@ mod tests {
     fn assert_count(text: &str, pairs: &[(&str, u32)]) {
         let result = count(text);
         for &(trigram, expected_n) in pairs.iter() {
-            let actual_n = result[trigram];
+            let actual_n = result[&Ngram::new(trigram).unwrap()];
             assert_eq!(actual_n, expected_n, "trigram '{}' expected to occur {} times, got {}", trigram, expected_n, actual_n);
         }
         assert_eq!(result.len(), pairs.len());
@@ -118,9 +320,89 @@ mod tests {
     }
 
     #[test]
-    fn test_get_trigrams_with_positions() {
-        let res = get_trigrams_with_positions("xaaaaabbbbd");
-        assert_eq!(res["aaa"], 0);
-        assert_eq!(res["bbb"], 1);
+    fn test_count_text_without_spaces_between_words() {
+        // Spaces separate phrases, so only trigrams of letters in a row are counted
+        assert_count("ภาษา ไทย", &[("ภาษ", 1), ("าษา", 1), ("ไทย", 1)]);
+        assert_count("ພາສາລາວ", &[("ພາສ", 1), ("າສາ", 1), ("ສາລ", 1), ("າລາ", 1), ("ລາວ", 1)]);
+        assert_count("ok ไทย", &[(" ok", 1), ("ok ", 1), ("ไทย", 1)]);
+    }
+
+    #[test]
+    fn test_count_turkic_text() {
+        // "İ" is lowercased without a combining dot, "I" is dotless only in Turkic texts
+        assert_count("İyi", &[(" iy", 1), ("iyi", 1), ("yi ", 1)]);
+        assert_count("IŞIĞI", &[(" ış", 1), ("ışı", 1), ("şığ", 1), ("ığı", 1), ("ğı ", 1)]);
+        assert_count("ISIK", &[(" is", 1), ("isi", 1), ("sik", 1), ("ik ", 1)]);
+    }
+
+    #[test]
+    fn test_count_ngrams_of_other_sizes() {
+        let mut counter = TrigramCounter::with_size(2, 0);
+        counter.push_str("Yes, yes");
+        let counts = counter.finish();
+        assert_eq!(counts.len(), 4);
+        let count = |bigram| counts[&Ngram::new(bigram).unwrap()];
+        assert_eq!((count(" y"), count("ye"), count("es"), count("s ")), (2, 2, 2, 2));
+
+        let mut counter = TrigramCounter::with_size(4, 0);
+        counter.push_str("a Yes");
+        let mut fourgrams: Vec<String> = counter.finish().into_iter().map(|(fourgram, _)| fourgram.to_string()).collect();
+        fourgrams.sort();
+        assert_eq!(fourgrams, vec![" a y", " yes", "a ye", "yes "]);
+    }
+
+    #[test]
+    fn test_trigram_counter_with_parts() {
+        let text = "Give - IT, yes! Привет мир";
+        for split in 0..text.chars().count() {
+            let (head, tail) = text.split_at(text.char_indices().nth(split).unwrap().0);
+            let mut counter = TrigramCounter::with_capacity(0);
+            counter.push_str(head);
+            counter.push_str(tail);
+            assert_eq!(counter.finish(), count(text));
+        }
+    }
+
+    #[test]
+    fn test_extract_trigrams() {
+        assert_eq!(extract_trigrams(""), vec![]);
+        let expected: Vec<(String, u32)> = vec![("ana", 2), ("nan", 1), ("na ", 1), ("ban", 1), (" ba", 1)]
+            .into_iter()
+            .map(|(trigram, count)| (trigram.to_string(), count))
+            .collect();
+        assert_eq!(extract_trigrams("Banana!"), expected);
+    }
+
+    #[test]
+    fn test_get_ngrams_with_positions() {
+        let res = get_ngrams_with_positions("xaaaaabbbbd", 3);
+        assert_eq!(find_ngram(&res, "aaa"), Some((0, 3)));
+        assert_eq!(find_ngram(&res, "bbb"), Some((1, 2)));
+        assert_eq!(find_ngram(&res, "xyz"), None);
+        assert_eq!(find_ngram(&res, "aaaaa"), None);
+    }
+
+    #[test]
+    fn test_scratch_is_reused() {
+        let mut scratch = Scratch::new();
+        let texts = [("Give - IT, yes! Привет мир", 3), ("xaaaaabbbbd", 3), ("", 3), ("a Yes", 4), ("Banana!", 2)];
+        for &(text, size) in texts.iter() {
+            assert_eq!(scratch.ngrams_with_positions(text, size), &get_ngrams_with_positions(text, size), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_ngram() {
+        for &text in ["a", "ab", " ab", "абв ", "ж\u{10FFFF}z"].iter() {
+            assert_eq!(Ngram::new(text).unwrap().to_string(), text);
+        }
+        assert_eq!(Ngram::new("abcde"), None);
+
+        // N-grams of the same size are ordered the same as strings
+        let mut ngrams = vec!["zz ", "ab ", "a\u{10FFFF}a", "abc", " ab", "жab", "a  "];
+        let mut packed: Vec<Ngram> = ngrams.iter().map(|&ngram| Ngram::new(ngram).unwrap()).collect();
+        ngrams.sort();
+        packed.sort();
+        assert_eq!(packed.iter().map(Ngram::to_string).collect::<Vec<_>>(), ngrams);
     }
 }
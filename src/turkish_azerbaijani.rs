@@ -0,0 +1,131 @@
+use lang::Lang;
+use utils::to_lowercase;
+
+// Turkish and Azerbaijani are close Oghuz languages with almost the same Latin alphabet, so short
+// Azerbaijani texts are often detected as Turkish. But only Azerbaijani has the schwa "ə", which is
+// one of its most common letters. The most common words also differ: pronouns ("ben" and "mən"),
+// the conjunction "ve" and "və", postpositions ("için" and "üçün", "ile" and "ilə"), "çok" and "çox".
+const AZERBAIJANI_LETTERS: &[char] = &['ə'];
+
+// Words are sorted, to be found with binary search.
+const TURKISH_WORDS: &[&str] = &[
+    "ama", "ben", "bugün", "değil", "evet", "gibi", "hayır", "her", "ile", "iyi", "için", "kadar",
+    "nasıl", "ne", "olarak", "sen", "ve", "yok", "çok", "şimdi",
+];
+
+const AZERBAIJANI_WORDS: &[&str] = &[
+    "amma", "bəli", "deyil", "hər", "ilə", "mən", "necə", "nə", "olaraq", "qədər", "sən", "və",
+    "xeyr", "yaxşı", "yox", "çox", "üçün", "ən",
+];
+
+// Choose between Turkish and Azerbaijani by the letters and words, which are used only in one
+// of the languages. Returns None if `lang` is neither Turkish nor Azerbaijani,
+// or if the text has as much evidence of one language as of the other one.
+pub(crate) fn resolve_turkish_azerbaijani(text: &str, lang: Lang) -> Option<Lang> {
+    if !is_turkish_azerbaijani(lang) {
+        return None;
+    }
+    let mut evidence = count_turkish_azerbaijani_letters(text);
+    evidence.add(count_turkish_azerbaijani_words(text));
+    resolve_turkish_azerbaijani_by_counts(evidence, lang)
+}
+
+// Same as resolve_turkish_azerbaijani, but with the letters and words already counted.
+pub(crate) fn resolve_turkish_azerbaijani_by_counts(evidence: TurkishAzerbaijaniEvidence, lang: Lang) -> Option<Lang> {
+    if !is_turkish_azerbaijani(lang) {
+        return None;
+    }
+
+    if evidence.turkish > evidence.azerbaijani {
+        Some(Lang::Tur)
+    } else if evidence.azerbaijani > evidence.turkish {
+        Some(Lang::Azj)
+    } else {
+        None
+    }
+}
+
+// Numbers of letters and words, which are used only in Turkish or only in Azerbaijani.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TurkishAzerbaijaniEvidence {
+    pub turkish: usize,
+    pub azerbaijani: usize
+}
+
+impl TurkishAzerbaijaniEvidence {
+    pub fn add(&mut self, other: TurkishAzerbaijaniEvidence) {
+        self.turkish += other.turkish;
+        self.azerbaijani += other.azerbaijani;
+    }
+}
+
+pub(crate) fn count_turkish_azerbaijani_letters(text: &str) -> TurkishAzerbaijaniEvidence {
+    let mut result = TurkishAzerbaijaniEvidence::default();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if AZERBAIJANI_LETTERS.contains(&ch) {
+            result.azerbaijani += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn count_turkish_azerbaijani_words(text: &str) -> TurkishAzerbaijaniEvidence {
+    let mut result = TurkishAzerbaijaniEvidence::default();
+    for word in text.split(|ch: char| !ch.is_alphabetic()) {
+        // Words are compared only for Turkic languages, so "I" is always the capital of "ı"
+        let word = to_lowercase(word, true);
+        if contains(TURKISH_WORDS, &word) {
+            result.turkish += 1;
+        } else if contains(AZERBAIJANI_WORDS, &word) {
+            result.azerbaijani += 1;
+        }
+    }
+    result
+}
+
+pub(crate) fn is_turkish_azerbaijani(lang: Lang) -> bool {
+    lang == Lang::Tur || lang == Lang::Azj
+}
+
+fn contains(words: &[&str], word: &str) -> bool {
+    words.binary_search(&word).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_lists_are_sorted_and_distinct() {
+        for words in [TURKISH_WORDS, AZERBAIJANI_WORDS].iter() {
+            for pair in words.windows(2) {
+                assert!(pair[0] < pair[1], "{} must go before {}", pair[1], pair[0]);
+            }
+        }
+        for word in TURKISH_WORDS.iter() {
+            assert!(!contains(AZERBAIJANI_WORDS, word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_resolve_turkish_azerbaijani() {
+        let text = "Bu kitab çox maraqlıdır.";
+        assert_eq!(resolve_turkish_azerbaijani(text, Lang::Tur), Some(Lang::Azj));
+
+        let text = "Hava bugün çok sıcak değil.";
+        assert_eq!(resolve_turkish_azerbaijani(text, Lang::Azj), Some(Lang::Tur));
+
+        // Without evidence the language is not resolved
+        let text = "Biz top oynadık.";
+        assert_eq!(resolve_turkish_azerbaijani(text, Lang::Azj), None);
+
+        assert_eq!(resolve_turkish_azerbaijani(text, Lang::Tuk), None);
+    }
+
+    #[test]
+    fn test_count_turkish_azerbaijani_evidence() {
+        let text = "Mən və sən, ben ve sen.";
+        assert_eq!(count_turkish_azerbaijani_letters(text), TurkishAzerbaijaniEvidence { turkish: 0, azerbaijani: 3 });
+        assert_eq!(count_turkish_azerbaijani_words(text), TurkishAzerbaijaniEvidence { turkish: 3, azerbaijani: 3 });
+    }
+}
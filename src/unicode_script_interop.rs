@@ -0,0 +1,114 @@
+use unicode_script::{Script as UcdScript, UnicodeScript};
+
+use script::Script;
+
+// The same as NEUTRAL_RANGES of build.rs: chars of CJK Symbols and Punctuation are not counted
+// for any script, whatever their script property is.
+const NEUTRAL_RANGES: &[(u32, u32)] = &[(0x3000, 0x303F)];
+
+// Find a script of the char by its script property from unicode-script crate.
+pub(crate) fn char_script(ch: char) -> Option<Script> {
+    // Fast path for ASCII, which is the most common case
+    if ch.is_ascii() {
+        return if ch.is_ascii_alphabetic() { Some(Script::Latin) } else { None };
+    }
+    if is_neutral(ch) {
+        return None;
+    }
+    from_ucd_script(ch.script())
+}
+
+pub(crate) fn is_inherited(ch: char) -> bool {
+    ch.script() == UcdScript::Inherited && !is_neutral(ch)
+}
+
+fn is_neutral(ch: char) -> bool {
+    let code = ch as u32;
+    NEUTRAL_RANGES.iter().any(|&(start, end)| code >= start && code <= end)
+}
+
+// Scripts of the Unicode Character Database, which are supported by whatlang,
+// the same as SUPPORTED_SCRIPTS (and HISTORIC_SCRIPTS with `historic-scripts` feature) of build.rs.
+fn from_ucd_script(script: UcdScript) -> Option<Script> {
+    let script = match script {
+        UcdScript::Arabic => Script::Arabic,
+        UcdScript::Armenian => Script::Armenian,
+        UcdScript::Balinese => Script::Balinese,
+        UcdScript::Bengali => Script::Bengali,
+        UcdScript::Canadian_Aboriginal => Script::CanadianAboriginal,
+        UcdScript::Cherokee => Script::Cherokee,
+        UcdScript::Cyrillic => Script::Cyrillic,
+        UcdScript::Devanagari => Script::Devanagari,
+        UcdScript::Ethiopic => Script::Ethiopic,
+        UcdScript::Georgian => Script::Georgian,
+        UcdScript::Greek => Script::Greek,
+        UcdScript::Gujarati => Script::Gujarati,
+        UcdScript::Gurmukhi => Script::Gurmukhi,
+        UcdScript::Han => Script::Mandarin,
+        UcdScript::Hangul => Script::Hangul,
+        UcdScript::Hebrew => Script::Hebrew,
+        UcdScript::Hiragana => Script::Hiragana,
+        UcdScript::Javanese => Script::Javanese,
+        UcdScript::Kannada => Script::Kannada,
+        UcdScript::Katakana => Script::Katakana,
+        UcdScript::Khmer => Script::Khmer,
+        UcdScript::Lao => Script::Lao,
+        UcdScript::Latin => Script::Latin,
+        UcdScript::Malayalam => Script::Malayalam,
+        UcdScript::Mongolian => Script::Mongolian,
+        UcdScript::Myanmar => Script::Myanmar,
+        UcdScript::Nko => Script::Nko,
+        UcdScript::Oriya => Script::Oriya,
+        UcdScript::Sinhala => Script::Sinhala,
+        UcdScript::Sundanese => Script::Sundanese,
+        UcdScript::Tamil => Script::Tamil,
+        UcdScript::Telugu => Script::Telugu,
+        UcdScript::Thaana => Script::Thaana,
+        UcdScript::Thai => Script::Thai,
+        UcdScript::Tifinagh => Script::Tifinagh,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Coptic => Script::Coptic,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Glagolitic => Script::Glagolitic,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Gothic => Script::Gothic,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Old_Italic => Script::OldItalic,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Phoenician => Script::Phoenician,
+        #[cfg(feature = "historic-scripts")]
+        UcdScript::Runic => Script::Runic,
+        _ => return None,
+    };
+    Some(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use script::{ranges_char_script, ranges_is_inherited};
+    use std::char;
+
+    #[test]
+    fn test_char_script_matches_script_ranges() {
+        // Scripts.txt of whatlang and unicode-script may be of different Unicode versions,
+        // so chars, which are unassigned in one of them, may differ.
+        for code in 0..0x3_0000 {
+            if let Some(ch) = char::from_u32(code) {
+                let (ranges_script, ucd_script) = (ranges_char_script(ch), char_script(ch));
+                if ranges_script.is_some() && ucd_script.is_some() {
+                    assert_eq!(ranges_script, ucd_script, "{:?}", ch);
+                }
+                if ranges_is_inherited(ch) {
+                    assert!(is_inherited(ch), "{:?}", ch);
+                }
+            }
+        }
+        assert_eq!(char_script('ж'), Some(Script::Cyrillic));
+        assert_eq!(char_script('\u{20000}'), Some(Script::Mandarin));
+        assert_eq!(char_script('々'), None);
+        assert_eq!(char_script('1'), None);
+        assert!(is_inherited('\u{0301}'));
+        assert!(!is_inherited('\u{302A}'));
+    }
+}
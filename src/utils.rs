@@ -1,14 +1,72 @@
-// Is it space, punctuation or digit?
+// Is it space, punctuation, digit or symbol (e.g. emoji)?
 // Stop character is a character that does not give any value for script
 // or language detection.
 #[inline]
 pub fn is_stop_char(ch : char) -> bool {
+    if ch.is_ascii() {
+        return matches!(ch, '\u{0000}'..='\u{0040}' | '\u{005B}'..='\u{0060}' | '\u{007B}'..='\u{007E}');
+    }
+    is_symbol(ch)
+}
+
+// Symbols, which are used in texts of any language: emoji with their modifiers,
+// dingbats, arrows, box drawing, geometric shapes, math and currency symbols.
+fn is_symbol(ch: char) -> bool {
+    matches!(ch,
+        '\u{00A9}' | '\u{00AE}' | '\u{00B0}' |  // Copyright, registered and degree signs
+        '\u{20A0}'..='\u{20CF}' |                // Currency Symbols
+        '\u{2190}'..='\u{2BFF}' |                // Arrows, Math, Technical, Box Drawing, Shapes, Dingbats
+        '\u{FE00}'..='\u{FE0F}' |                // Variation Selectors (emoji presentation)
+        '\u{1F000}'..='\u{1FBFF}' |              // Emoji, Pictographs and other symbols
+        '\u{E0020}'..='\u{E007F}'                // Tags (subdivision flags)
+    )
+}
+
+// Letters, which are used only by Turkic languages (Turkish, Azerbaijani, Tatar and others).
+// In their alphabets "I" is the capital of the dotless "ı", and "İ" is the capital of "i".
+const TURKIC_LETTERS: &[char] = &['ı', 'İ', 'ğ', 'Ğ', 'ə', 'Ə'];
+
+// Whether a text is likely written in a Turkic language, so it's lowercased by Turkic rules.
+// ASCII texts are not looked through.
+pub fn has_turkic_letters(text: &str) -> bool {
+    !text.is_ascii() && text.contains(TURKIC_LETTERS)
+}
+
+// Chars of scripts, which are written without spaces between words: Thai, Lao, Myanmar and Khmer.
+// Spaces in their texts separate phrases and sentences, not words.
+#[inline]
+pub fn is_unspaced_script_char(ch: char) -> bool {
+    matches!(ch,
+        '\u{0E00}'..='\u{0EFF}' |  // Thai and Lao
+        '\u{1000}'..='\u{109F}' |  // Myanmar
+        '\u{1780}'..='\u{17FF}' |  // Khmer
+        '\u{19E0}'..='\u{19FF}' |  // Khmer Symbols
+        '\u{A9E0}'..='\u{A9FF}' |  // Myanmar Extended-B
+        '\u{AA60}'..='\u{AA7F}'    // Myanmar Extended-A
+    )
+}
+
+// Push the lowercase char(s) of a char, "I" is lowercased to "ı" if `turkic`. "İ" is always
+// lowercased to "i" (instead of "i" with a combining dot above, which is not in any profile).
+#[inline]
+pub fn fold_case<F: FnMut(char)>(ch: char, turkic: bool, mut push: F) {
     match ch {
-        '\u{0000}'...'\u{0040}' | '\u{005B}'...'\u{0060}' | '\u{007B}'...'\u{007E}' => true,
-        _ => false
+        'I' if turkic => push('ı'),
+        'İ' => push('i'),
+        _ => for lower in ch.to_lowercase() {
+            push(lower);
+        }
     }
 }
 
+pub fn to_lowercase(text: &str, turkic: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        fold_case(ch, turkic, |lower| result.push(lower));
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,4 +89,23 @@ mod tests {
         assert!(!is_stop_char('я'));
         assert!(!is_stop_char('А')); // cyrillic A
     }
+
+    #[test]
+    fn test_symbols_are_stop_chars() {
+        for &ch in ['😀', '👍', '🏽', '\u{FE0F}', '🇩', '❤', '✔', '★', '→', '─', '╔', '█', '€', '©', '∑', '🀄'].iter() {
+            assert!(is_stop_char(ch), "{}", ch);
+        }
+        for &ch in ['é', 'ß', 'µ', '中', 'あ', 'ㄱ', 'ا', '\u{200C}'].iter() {
+            assert!(!is_stop_char(ch), "{}", ch);
+        }
+    }
+
+    #[test]
+    fn test_fold_case() {
+        assert!(has_turkic_letters("Işık İstanbul'da"));
+        assert!(!has_turkic_letters("Istanbul"));
+        assert!(!has_turkic_letters("Straße"));
+        assert_eq!(to_lowercase("IŞIK İSTANBUL", true), "ışık istanbul");
+        assert_eq!(to_lowercase("İstanbul IS big", false), "istanbul is big");
+    }
 }
@@ -0,0 +1,64 @@
+use std::fmt;
+
+include!(concat!(env!("OUT_DIR"), "/data_hash.rs"));
+
+// Revision of the detection algorithm. It must be increased by every change, which may change
+// results of detection with the same built-in data, e.g. of scoring or of resolving close languages.
+const ALGORITHM_REVISION: u32 = 2;
+
+/// Version of the built-in model: the revision of the detection algorithm and the hash
+/// of the built-in data (profiles of languages selected by features and script ranges).
+/// Results of detection may differ only between different versions, so stored results
+/// can be invalidated when the version changes, e.g. after upgrading the crate.
+///
+/// Results of models other than [TrigramModel](struct.TrigramModel.html) depend on their
+/// profiles too, which are not covered by the version.
+///
+/// # Example
+/// ```
+/// use whatlang::{detect, model_version};
+///
+/// let info = detect("Ĉu vi ne volas eklerni Esperanton? Bonvolu!").unwrap();
+/// assert_eq!(info.model_version(), model_version());
+/// println!("detected by model {}", model_version());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ModelVersion {
+    algorithm: u32,
+    data: u32
+}
+
+impl ModelVersion {
+    pub fn algorithm(&self) -> u32 {
+        self.algorithm
+    }
+
+    pub fn data(&self) -> u32 {
+        self.data
+    }
+}
+
+impl fmt::Display for ModelVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:08x}", self.algorithm, self.data)
+    }
+}
+
+/// Version of the built-in model, see [ModelVersion](struct.ModelVersion.html).
+pub fn model_version() -> ModelVersion {
+    ModelVersion { algorithm: ALGORITHM_REVISION, data: DATA_HASH }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_version() {
+        let version = model_version();
+        assert_eq!(version.algorithm(), ALGORITHM_REVISION);
+        assert_ne!(version, ModelVersion::default());
+        assert_eq!(version.to_string(), format!("{}.{:08x}", ALGORITHM_REVISION, DATA_HASH));
+    }
+}
@@ -1,12 +1,22 @@
 /// Represents a language following [ISO 639-3](https://en.wikipedia.org/wiki/ISO_639-3) standard.
 #[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Lang {
     {% for lang in lang_infos %}
     /// {{ lang.name }} ({{ lang.eng_name }})
-    {{ lang.code | capitalize }} = {{ loop.index }},
+    {{ lang.code | capitalize }} = {{ lang.id }},
     {% endfor %}
 }
 
+const LANG_COUNT: usize = {{ lang_infos | length }};
+
+// All languages in the order of declaration.
+const ALL_LANGS: [Lang; LANG_COUNT] = [
+    {% for lang in lang_infos %}
+    Lang::{{ lang.code | capitalize }},
+    {% endfor %}
+];
+
 fn lang_from_code<S: Into<String>>(code: S) -> Option<Lang> {
     match code.into().to_lowercase().as_ref() {
         {% for lang in lang_infos %}
@@ -16,6 +26,15 @@ fn lang_from_code<S: Into<String>>(code: S) -> Option<Lang> {
     }
 }
 
+fn lang_from_iso639_1(code: &str) -> Option<Lang> {
+    match code {
+        {% for lang in lang_infos %}{% if lang.iso639_1 %}
+        "{{ lang.iso639_1 }}" => Some(Lang::{{ lang.code | capitalize }}),
+        {% endif %}{% endfor %}
+        _ => None,
+    }
+}
+
 fn lang_to_code(lang: Lang) -> &'static str {
     match lang {
         {% for lang in lang_infos %}
@@ -24,6 +43,22 @@ fn lang_to_code(lang: Lang) -> &'static str {
     }
 }
 
+fn lang_to_iso639_1(lang: Lang) -> Option<&'static str> {
+    match lang {
+        {% for lang in lang_infos %}
+        Lang::{{ lang.code | capitalize }} => {% if lang.iso639_1 %}Some("{{ lang.iso639_1 }}"){% else %}None{% endif %},
+        {% endfor %}
+    }
+}
+
+fn lang_to_family(lang: Lang) -> Family {
+    match lang {
+        {% for lang in lang_infos %}
+        Lang::{{ lang.code | capitalize }} => Family::{{ lang.family }},
+        {% endfor %}
+    }
+}
+
 fn lang_to_name(lang: Lang) -> &'static str {
     match lang {
         {% for lang in lang_infos %}
@@ -40,11 +75,48 @@ fn lang_to_eng_name(lang: Lang) -> &'static str {
     }
 }
 
-{% for script, langs in scripts %}
-/// Languages for script {{ script }}
-pub static {{ script | upper }}_LANGS: LangProfileList = &[
-    {% for lang in langs %}
-    (Lang::{{ lang.info.code | capitalize }}, &[ {% for trigram in lang.trigrams %} "{{ trigram }}", {% endfor %} ]),
+// Unique n-grams of all the built-in profiles, separated by `|`.
+static NGRAMS: &str = "{{ ngrams }}";
+
+{% for list in profile_lists %}
+pub(crate) const {{ list.name }}: ProfileListId = ProfileListId({{ loop.index0 }});
+{% endfor %}
+
+const PROFILE_LIST_COUNT: usize = {{ profile_lists | length }};
+
+// Profiles encoded by build.rs as indices of n-grams in NGRAMS.
+static ENCODED_PROFILE_LISTS: [&[u16]; PROFILE_LIST_COUNT] = [
+    {% for list in profile_lists %}
+    &[{% for n in list.data %}{{ n }},{% endfor %}],
     {% endfor %}
 ];
-{% endfor %}
+
+// Perfect hash tables of n-grams of the profile lists, generated by build.rs.
+static NGRAM_TABLES: [NgramTable; PROFILE_LIST_COUNT] = [
+    {% for list in profile_lists %}
+    NgramTable {
+        langs: &[{% for lang in list.table.langs %}Lang::{{ lang }},{% endfor %}],
+        sizes: &[{% for size in list.table.sizes %}{{ size }},{% endfor %}],
+        displacements: &[{% for n in list.table.displacements %}{{ n }},{% endfor %}],
+        keys: &[{% for key in list.table.keys %}{{ key }},{% endfor %}],
+        offsets: &[{% for n in list.table.offsets %}{{ n }},{% endfor %}],
+        postings: &[{% for n in list.table.postings %}{{ n }},{% endfor %}],
+    },
+    {% endfor %}
+];
+
+static DECODED_PROFILE_LISTS: [Lazy<Vec<(Lang, LangProfile)>>; PROFILE_LIST_COUNT] = [
+    {% for list in profile_lists %}
+    Lazy::new(),
+    {% endfor %}
+];
+
+// Languages, which have trigram profiles for the script.
+pub(crate) fn profile_langs(script: Script) -> &'static [Lang] {
+    match script {
+        {% for script, langs in scripts %}
+        Script::{{ script }} => &[ {% for lang in langs %}Lang::{{ lang.info.code | capitalize }}, {% endfor %}],
+        {% endfor %}
+        _ => &[]
+    }
+}
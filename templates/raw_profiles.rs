@@ -0,0 +1,24 @@
+// Trigram profiles of the built-in languages, generated by build.rs from misc/data.json
+// and misc/romanized.json.
+{% for profile in profiles %}
+/// Trigrams of {{ profile.eng_name }} in the {{ profile.script }} script.
+pub const {{ profile.name }}: &[&str] = &[{% for trigram in profile.trigrams %}{{ trigram }},{% endfor %}];
+{% endfor %}
+{% for profile in romanized %}
+/// Trigrams of {{ profile.eng_name }} written in the Latin script.
+pub const {{ profile.name }}: &[&str] = &[{% for trigram in profile.trigrams %}{{ trigram }},{% endfor %}];
+{% endfor %}
+
+/// Every trigram profile of the languages of the scripts, by scripts and codes of languages.
+pub const PROFILES: &[(Lang, Script, &[&str])] = &[
+    {% for profile in profiles %}
+    (Lang::{{ profile.lang }}, Script::{{ profile.script }}, {{ profile.name }}),
+    {% endfor %}
+];
+
+/// Every romanized trigram profile, see [Options::set_romanized](../struct.Options.html#method.set_romanized).
+pub const ROMANIZED_PROFILES: &[(Lang, &[&str])] = &[
+    {% for profile in romanized %}
+    (Lang::{{ profile.lang }}, {{ profile.name }}),
+    {% endfor %}
+];
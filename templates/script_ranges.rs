@@ -0,0 +1,15 @@
+// Code point ranges of the scripts, sorted by the start of a range.
+// Generated by build.rs from misc/Scripts.txt.
+const SCRIPT_RANGES: &[(u32, u32, Script)] = &[
+    {% for range in script_ranges %}
+    ({{ range.start }}, {{ range.end }}, Script::{{ range.script }}),
+    {% endfor %}
+];
+
+// Code point ranges of combining marks, which take the script of the char before them,
+// sorted by the start of a range.
+const INHERITED_RANGES: &[(u32, u32)] = &[
+    {% for range in inherited_ranges %}
+    ({{ range.start }}, {{ range.end }}),
+    {% endfor %}
+];
@@ -1,7 +1,7 @@
 extern crate whatlang;
 extern crate serde_json;
 
-use whatlang::{detect, Lang, Script};
+use whatlang::{detect, detect_with_options, Lang, Options, Script};
 
 use std::collections::HashMap;
 
@@ -23,6 +23,172 @@ fn test_with_multiple_examples() {
     }
 }
 
+#[test]
+fn test_sampling_of_big_texts() {
+    let example_data = include_str!("examples.json");
+    let examples: HashMap<String, String> = serde_json::from_str(example_data).unwrap();
+    // Closely related languages (e.g. Maithili and Nepali) need bigger samples,
+    // especially in scripts which take 3 bytes per character.
+    let options = Options::new().set_sample_size(8192);
+
+    for (lang_code, text) in examples {
+        let lang = Lang::from_code(lang_code).unwrap();
+        let big_text = vec![text.as_str(); 100].join("\n");
+
+        let info = detect_with_options(&big_text, &options).unwrap();
+        assert_eq!(info.lang(), lang);
+    }
+}
+
+#[test]
+fn test_sampling_of_small_samples() {
+    // Even with a sample of 150 bytes, the most of examples are still detected correctly.
+    let example_data = include_str!("examples.json");
+    let examples: HashMap<String, String> = serde_json::from_str(example_data).unwrap();
+    let options = Options::new().set_sample_size(150);
+
+    let total = examples.len();
+    let correct = examples.iter()
+        .filter(|&(lang_code, text)| {
+            let lang = Lang::from_code(lang_code.as_str()).unwrap();
+            detect_with_options(text, &options).map(|info| info.lang()) == Some(lang)
+        })
+        .count();
+    assert!(correct * 100 / total >= 80, "{} of {} examples are detected correctly", correct, total);
+}
+
+// Sentences of a JSON object, which maps language codes to lists of sentences.
+fn load_sentences(data: &str) -> Vec<(Lang, String)> {
+    let examples: HashMap<String, Vec<String>> = serde_json::from_str(data).unwrap();
+    examples.into_iter()
+        .flat_map(|(lang_code, texts)| {
+            let lang = Lang::from_code(lang_code).unwrap();
+            texts.into_iter().map(move |text| (lang, text))
+        })
+        .collect()
+}
+
+// Sentences, which are detected wrong with the options: the expected language,
+// the detected one and the sentence.
+fn misdetected<'a>(sentences: &'a [(Lang, String)], options: &Options) -> Vec<(Lang, Option<Lang>, &'a str)> {
+    sentences.iter()
+        .map(|(lang, text)| (*lang, detect_with_options(text, options).map(|info| info.lang()), text.as_str()))
+        .filter(|&(lang, detected, _)| detected != Some(lang))
+        .collect()
+}
+
+// Misdetected sentences, which are detected as another of `langs`.
+fn confused<'a>(misses: &[(Lang, Option<Lang>, &'a str)], langs: &[Lang]) -> Vec<(Lang, Option<Lang>, &'a str)> {
+    misses.iter().cloned().filter(|&(_, detected, _)| detected.map_or(false, |lang| langs.contains(&lang))).collect()
+}
+
+#[test]
+fn test_bulgarian_and_macedonian() {
+    // Short sentences of the two languages, which are often confused by trigrams
+    let sentences = load_sentences(include_str!("bulgarian_macedonian.json"));
+    assert_eq!(misdetected(&sentences, &Options::new()), vec![]);
+    let options = Options::new().set_whitelist(vec![Lang::Bul, Lang::Mkd]);
+    assert_eq!(misdetected(&sentences, &options), vec![]);
+}
+
+#[test]
+fn test_bokmal_and_nynorsk() {
+    // Short sentences of the two standards of Norwegian
+    let sentences = load_sentences(include_str!("bokmal_nynorsk.json"));
+    let langs = [Lang::Nob, Lang::Nno];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist other languages win a quarter of them (mostly the shortest ones,
+    // e.g. "Hvor bor du nå?"), but the two standards are never confused with each other
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 6, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_danish_and_norwegian() {
+    // Parallel sentences of Danish and Bokmål, which are told apart by the words specific for each of them
+    let sentences = load_sentences(include_str!("danish_norwegian.json"));
+    let langs = [Lang::Dan, Lang::Nob];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist Nynorsk, Swedish and Dutch win some of them, but the two are never confused
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 9, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_czech_and_slovak() {
+    // Parallel sentences of the two languages, which are told apart by their specific letters and words
+    let sentences = load_sentences(include_str!("czech_slovak.json"));
+    let langs = [Lang::Ces, Lang::Slk];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist other languages win some of the ones without the specific letters,
+    // but the two are never confused
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 9, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_turkish_and_azerbaijani() {
+    // Parallel sentences of Turkish and Azerbaijani, which are told apart by the schwa and the words specific for each of them
+    let sentences = load_sentences(include_str!("turkish_azerbaijani.json"));
+    let langs = [Lang::Tur, Lang::Azj];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist other languages (e.g. Turkmen) win some of the short ones, but the two are never confused
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 7, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_malay_and_indonesian() {
+    // Parallel sentences of Malay and Indonesian, which are told apart by the words specific for each of them
+    let sentences = load_sentences(include_str!("malay_indonesian.json"));
+    let langs = [Lang::Zlm, Lang::Ind];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+    assert_eq!(misdetected(&sentences, &Options::new()), vec![]);
+}
+
+#[test]
+fn test_french_and_haitian_creole() {
+    // Parallel sentences of French and Haitian Creole, which has mostly French vocabulary in its own spelling
+    let sentences = load_sentences(include_str!("french_haitian.json"));
+    let langs = [Lang::Fra, Lang::Hat];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist nearly all of them are detected right, and the two are never confused
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 1, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_spanish_and_portuguese() {
+    // Parallel sentences of the two languages, which are told apart by their specific letters and words
+    let sentences = load_sentences(include_str!("spanish_portuguese.json"));
+    let langs = [Lang::Spa, Lang::Por];
+    assert_eq!(misdetected(&sentences, &Options::new().set_whitelist(langs.to_vec())), vec![]);
+
+    // Without the whitelist other languages (mostly Galician) win a few of them, but the two are never confused
+    let misses = misdetected(&sentences, &Options::new());
+    assert!(misses.len() <= 5, "{:?}", misses);
+    assert_eq!(confused(&misses, &langs), vec![]);
+}
+
+#[test]
+fn test_devanagari_languages() {
+    // Parallel sentences of the languages, which are told apart by the function words specific for each of them
+    let sentences = load_sentences(include_str!("devanagari.json"));
+    assert_eq!(misdetected(&sentences, &Options::new()), vec![]);
+    let options = Options::new().set_whitelist(vec![Lang::Hin, Lang::Mar, Lang::Nep, Lang::Mai, Lang::Bho]);
+    assert_eq!(misdetected(&sentences, &options), vec![]);
+}
+
 #[test]
 fn test_with_russian_text() {
     let text = r#"
@@ -0,0 +1,199 @@
+#ifndef WHATLANG_H
+#define WHATLANG_H
+
+/* Generated by cbindgen from whatlang-ffi, do not edit by hand. */
+
+#include <stdbool.h>
+
+/**
+ * A language, see `whatlang::Lang`. Values never change, new languages get the next free value.
+ */
+typedef enum WhatlangLang {
+  WHATLANG_LANG_AKA = 1,
+  WHATLANG_LANG_AMH = 2,
+  WHATLANG_LANG_ARB = 3,
+  WHATLANG_LANG_AZJ = 4,
+  WHATLANG_LANG_BEL = 5,
+  WHATLANG_LANG_BEN = 6,
+  WHATLANG_LANG_BHO = 7,
+  WHATLANG_LANG_BUL = 8,
+  WHATLANG_LANG_CEB = 9,
+  WHATLANG_LANG_CES = 10,
+  WHATLANG_LANG_CHR = 11,
+  WHATLANG_LANG_CMN = 12,
+  WHATLANG_LANG_DAN = 13,
+  WHATLANG_LANG_DEU = 14,
+  WHATLANG_LANG_DIV = 15,
+  WHATLANG_LANG_ELL = 16,
+  WHATLANG_LANG_ENG = 17,
+  WHATLANG_LANG_EPO = 18,
+  WHATLANG_LANG_EST = 19,
+  WHATLANG_LANG_FIN = 20,
+  WHATLANG_LANG_FRA = 21,
+  WHATLANG_LANG_GUJ = 22,
+  WHATLANG_LANG_HAT = 23,
+  WHATLANG_LANG_HAU = 24,
+  WHATLANG_LANG_HEB = 25,
+  WHATLANG_LANG_HIN = 26,
+  WHATLANG_LANG_HRV = 27,
+  WHATLANG_LANG_HUN = 28,
+  WHATLANG_LANG_HYE = 29,
+  WHATLANG_LANG_IBO = 30,
+  WHATLANG_LANG_ILO = 31,
+  WHATLANG_LANG_IND = 32,
+  WHATLANG_LANG_ITA = 33,
+  WHATLANG_LANG_JAV = 34,
+  WHATLANG_LANG_JPN = 35,
+  WHATLANG_LANG_KAN = 36,
+  WHATLANG_LANG_KAT = 37,
+  WHATLANG_LANG_KHK = 38,
+  WHATLANG_LANG_KHM = 39,
+  WHATLANG_LANG_KIN = 40,
+  WHATLANG_LANG_KOR = 41,
+  WHATLANG_LANG_KUR = 42,
+  WHATLANG_LANG_LAV = 43,
+  WHATLANG_LANG_LIT = 44,
+  WHATLANG_LANG_MAI = 45,
+  WHATLANG_LANG_MAL = 46,
+  WHATLANG_LANG_MAR = 47,
+  WHATLANG_LANG_MKD = 48,
+  WHATLANG_LANG_MLG = 49,
+  WHATLANG_LANG_MYA = 50,
+  WHATLANG_LANG_NEP = 51,
+  WHATLANG_LANG_NLD = 52,
+  WHATLANG_LANG_NNO = 53,
+  WHATLANG_LANG_NOB = 54,
+  WHATLANG_LANG_NQO = 55,
+  WHATLANG_LANG_NYA = 56,
+  WHATLANG_LANG_ORI = 57,
+  WHATLANG_LANG_ORM = 58,
+  WHATLANG_LANG_PAN = 59,
+  WHATLANG_LANG_PES = 60,
+  WHATLANG_LANG_POL = 61,
+  WHATLANG_LANG_POR = 62,
+  WHATLANG_LANG_RON = 63,
+  WHATLANG_LANG_RUN = 64,
+  WHATLANG_LANG_RUS = 65,
+  WHATLANG_LANG_SIN = 66,
+  WHATLANG_LANG_SKR = 67,
+  WHATLANG_LANG_SLV = 68,
+  WHATLANG_LANG_SNA = 69,
+  WHATLANG_LANG_SOM = 70,
+  WHATLANG_LANG_SPA = 71,
+  WHATLANG_LANG_SRP = 72,
+  WHATLANG_LANG_SWE = 73,
+  WHATLANG_LANG_TAM = 74,
+  WHATLANG_LANG_TEL = 75,
+  WHATLANG_LANG_TGL = 76,
+  WHATLANG_LANG_THA = 77,
+  WHATLANG_LANG_TIR = 78,
+  WHATLANG_LANG_TUK = 79,
+  WHATLANG_LANG_TUR = 80,
+  WHATLANG_LANG_UIG = 81,
+  WHATLANG_LANG_UKR = 82,
+  WHATLANG_LANG_URD = 83,
+  WHATLANG_LANG_UZB = 84,
+  WHATLANG_LANG_VIE = 85,
+  WHATLANG_LANG_YDD = 86,
+  WHATLANG_LANG_YOR = 87,
+  WHATLANG_LANG_ZGH = 88,
+  WHATLANG_LANG_ZUL = 89,
+  WHATLANG_LANG_SLK = 90,
+  WHATLANG_LANG_SWH = 91,
+  WHATLANG_LANG_KAZ = 92,
+  WHATLANG_LANG_KIR = 93,
+  WHATLANG_LANG_TAT = 94,
+  WHATLANG_LANG_CAT = 95,
+  WHATLANG_LANG_GLG = 96,
+  WHATLANG_LANG_YUE = 97,
+  WHATLANG_LANG_ZLM = 98,
+  WHATLANG_LANG_CYM = 99,
+  WHATLANG_LANG_GLE = 100,
+  WHATLANG_LANG_GLA = 101,
+  WHATLANG_LANG_EUS = 102,
+  WHATLANG_LANG_ISL = 103,
+  WHATLANG_LANG_FAO = 104,
+  WHATLANG_LANG_LAT = 105,
+  WHATLANG_LANG_LAO = 106,
+} WhatlangLang;
+
+/**
+ * A script, see `whatlang::Script`. Values never change, new scripts get the next free value.
+ */
+typedef enum WhatlangScript {
+  WHATLANG_SCRIPT_ARABIC = 1,
+  WHATLANG_SCRIPT_ARMENIAN = 2,
+  WHATLANG_SCRIPT_BALINESE = 3,
+  WHATLANG_SCRIPT_BENGALI = 4,
+  WHATLANG_SCRIPT_CANADIAN_ABORIGINAL = 5,
+  WHATLANG_SCRIPT_CHEROKEE = 6,
+  WHATLANG_SCRIPT_CYRILLIC = 7,
+  WHATLANG_SCRIPT_DEVANAGARI = 8,
+  WHATLANG_SCRIPT_ETHIOPIC = 9,
+  WHATLANG_SCRIPT_GEORGIAN = 10,
+  WHATLANG_SCRIPT_GREEK = 11,
+  WHATLANG_SCRIPT_GUJARATI = 12,
+  WHATLANG_SCRIPT_GURMUKHI = 13,
+  WHATLANG_SCRIPT_HANGUL = 14,
+  WHATLANG_SCRIPT_HEBREW = 15,
+  WHATLANG_SCRIPT_HIRAGANA = 16,
+  WHATLANG_SCRIPT_JAVANESE = 17,
+  WHATLANG_SCRIPT_KANNADA = 18,
+  WHATLANG_SCRIPT_KATAKANA = 19,
+  WHATLANG_SCRIPT_KHMER = 20,
+  WHATLANG_SCRIPT_LATIN = 21,
+  WHATLANG_SCRIPT_MALAYALAM = 22,
+  WHATLANG_SCRIPT_MANDARIN = 23,
+  WHATLANG_SCRIPT_MONGOLIAN = 24,
+  WHATLANG_SCRIPT_MYANMAR = 25,
+  WHATLANG_SCRIPT_NKO = 26,
+  WHATLANG_SCRIPT_ORIYA = 27,
+  WHATLANG_SCRIPT_SINHALA = 28,
+  WHATLANG_SCRIPT_SUNDANESE = 29,
+  WHATLANG_SCRIPT_TAMIL = 30,
+  WHATLANG_SCRIPT_TELUGU = 31,
+  WHATLANG_SCRIPT_THAANA = 32,
+  WHATLANG_SCRIPT_THAI = 33,
+  WHATLANG_SCRIPT_TIFINAGH = 34,
+  WHATLANG_SCRIPT_COPTIC = 35,
+  WHATLANG_SCRIPT_GLAGOLITIC = 36,
+  WHATLANG_SCRIPT_GOTHIC = 37,
+  WHATLANG_SCRIPT_OLD_ITALIC = 38,
+  WHATLANG_SCRIPT_PHOENICIAN = 39,
+  WHATLANG_SCRIPT_RUNIC = 40,
+  WHATLANG_SCRIPT_LAO = 41,
+} WhatlangScript;
+
+/**
+ * A result of language detection, see `whatlang::Info`.
+ */
+typedef struct WhatlangInfo {
+  enum WhatlangLang lang;
+  enum WhatlangScript script;
+  double confidence;
+  bool is_reliable;
+} WhatlangInfo;
+
+/**
+ * Detect a language and a script of a NUL-terminated UTF-8 text.
+ * Returns `false` and leaves `info` untouched, if the language can not be detected
+ * or the text is not valid UTF-8.
+ *
+ * # Safety
+ * `text` must be a valid pointer to a NUL-terminated string, `info` must be a valid pointer
+ * to `WhatlangInfo`. Null pointers are rejected.
+ */
+bool whatlang_detect(const char *text, struct WhatlangInfo *info);
+
+/**
+ * Detect a script of a NUL-terminated UTF-8 text.
+ * Returns `false` and leaves `script` untouched, if the script can not be detected
+ * or the text is not valid UTF-8.
+ *
+ * # Safety
+ * `text` must be a valid pointer to a NUL-terminated string, `script` must be a valid pointer
+ * to `WhatlangScript`. Null pointers are rejected.
+ */
+bool whatlang_detect_script(const char *text, enum WhatlangScript *script);
+
+#endif  /* WHATLANG_H */
@@ -0,0 +1,200 @@
+//! C bindings for [whatlang](https://crates.io/crates/whatlang).
+//!
+//! Texts are passed as NUL-terminated UTF-8 strings. Languages and scripts are represented
+//! by `WhatlangLang` and `WhatlangScript` enums with stable values: a value is never changed
+//! or reused, new languages and scripts get the next free one. The C header is `include/whatlang.h`.
+extern crate whatlang;
+
+mod values;
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+use whatlang::{Lang, Script};
+
+pub use values::{WhatlangLang, WhatlangScript};
+use values::{ALL_LANGS, ALL_SCRIPTS};
+
+/// A result of language detection, see `whatlang::Info`.
+#[repr(C)]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct WhatlangInfo {
+    pub lang: WhatlangLang,
+    pub script: WhatlangScript,
+    pub confidence: f64,
+    pub is_reliable: bool,
+}
+
+/// Detect a language and a script of a NUL-terminated UTF-8 text.
+/// Returns `false` and leaves `info` untouched, if the language can not be detected
+/// or the text is not valid UTF-8.
+///
+/// # Safety
+/// `text` must be a valid pointer to a NUL-terminated string, `info` must be a valid pointer
+/// to `WhatlangInfo`. Null pointers are rejected.
+#[no_mangle]
+pub unsafe extern "C" fn whatlang_detect(text: *const c_char, info: *mut WhatlangInfo) -> bool {
+    let detected = match to_str(text).and_then(whatlang::detect) {
+        Some(detected) => detected,
+        None => return false
+    };
+    if info.is_null() {
+        return false;
+    }
+    *info = WhatlangInfo {
+        lang: ffi_lang(detected.lang()),
+        script: ffi_script(detected.script()),
+        confidence: detected.confidence(),
+        is_reliable: detected.is_reliable(),
+    };
+    true
+}
+
+/// Detect a script of a NUL-terminated UTF-8 text.
+/// Returns `false` and leaves `script` untouched, if the script can not be detected
+/// or the text is not valid UTF-8.
+///
+/// # Safety
+/// `text` must be a valid pointer to a NUL-terminated string, `script` must be a valid pointer
+/// to `WhatlangScript`. Null pointers are rejected.
+#[no_mangle]
+pub unsafe extern "C" fn whatlang_detect_script(text: *const c_char, script: *mut WhatlangScript) -> bool {
+    let detected = match to_str(text).and_then(whatlang::detect_script) {
+        Some(detected) => detected,
+        None => return false
+    };
+    if script.is_null() {
+        return false;
+    }
+    *script = ffi_script(detected);
+    true
+}
+
+unsafe fn to_str<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+fn ffi_lang(lang: Lang) -> WhatlangLang {
+    ALL_LANGS[Lang::all().iter().position(|&l| l == lang).unwrap()]
+}
+
+fn ffi_script(script: Script) -> WhatlangScript {
+    ALL_SCRIPTS[Script::all().iter().position(|&s| s == script).unwrap()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_values_match_whatlang() {
+        assert_eq!(ALL_LANGS.len(), Lang::COUNT);
+        for &lang in Lang::all() {
+            assert_eq!(format!("{:?}", ffi_lang(lang)), format!("{:?}", lang));
+            assert_eq!(ffi_lang(lang) as u32, lang as u32);
+        }
+
+        // Historic scripts are in ALL_SCRIPTS even without `historic-scripts` feature
+        assert!(ALL_SCRIPTS.len() >= Script::COUNT);
+        for &script in Script::all() {
+            assert_eq!(format!("{:?}", ffi_script(script)), format!("{:?}", script));
+        }
+    }
+
+    // Values are a part of the C API: a value must never change, new ones are only appended.
+    #[test]
+    fn test_values_are_stable() {
+        let langs = [
+            ("Aka", 1), ("Amh", 2), ("Arb", 3), ("Azj", 4), ("Bel", 5), ("Ben", 6),
+            ("Bho", 7), ("Bul", 8), ("Ceb", 9), ("Ces", 10), ("Chr", 11), ("Cmn", 12),
+            ("Dan", 13), ("Deu", 14), ("Div", 15), ("Ell", 16), ("Eng", 17), ("Epo", 18),
+            ("Est", 19), ("Fin", 20), ("Fra", 21), ("Guj", 22), ("Hat", 23), ("Hau", 24),
+            ("Heb", 25), ("Hin", 26), ("Hrv", 27), ("Hun", 28), ("Hye", 29), ("Ibo", 30),
+            ("Ilo", 31), ("Ind", 32), ("Ita", 33), ("Jav", 34), ("Jpn", 35), ("Kan", 36),
+            ("Kat", 37), ("Khk", 38), ("Khm", 39), ("Kin", 40), ("Kor", 41), ("Kur", 42),
+            ("Lav", 43), ("Lit", 44), ("Mai", 45), ("Mal", 46), ("Mar", 47), ("Mkd", 48),
+            ("Mlg", 49), ("Mya", 50), ("Nep", 51), ("Nld", 52), ("Nno", 53), ("Nob", 54),
+            ("Nqo", 55), ("Nya", 56), ("Ori", 57), ("Orm", 58), ("Pan", 59), ("Pes", 60),
+            ("Pol", 61), ("Por", 62), ("Ron", 63), ("Run", 64), ("Rus", 65), ("Sin", 66),
+            ("Skr", 67), ("Slv", 68), ("Sna", 69), ("Som", 70), ("Spa", 71), ("Srp", 72),
+            ("Swe", 73), ("Tam", 74), ("Tel", 75), ("Tgl", 76), ("Tha", 77), ("Tir", 78),
+            ("Tuk", 79), ("Tur", 80), ("Uig", 81), ("Ukr", 82), ("Urd", 83), ("Uzb", 84),
+            ("Vie", 85), ("Ydd", 86), ("Yor", 87), ("Zgh", 88), ("Zul", 89), ("Slk", 90),
+            ("Swh", 91), ("Kaz", 92), ("Kir", 93), ("Tat", 94), ("Cat", 95), ("Glg", 96),
+            ("Yue", 97), ("Zlm", 98), ("Cym", 99), ("Gle", 100), ("Gla", 101), ("Eus", 102),
+            ("Isl", 103), ("Fao", 104), ("Lat", 105), ("Lao", 106),
+        ];
+        assert_eq!(langs.len(), ALL_LANGS.len());
+        for &(name, value) in langs.iter() {
+            let lang = ALL_LANGS.iter().find(|lang| format!("{:?}", lang) == name).unwrap();
+            assert_eq!(*lang as u32, value, "{}", name);
+        }
+
+        let scripts = [
+            ("Arabic", 1), ("Armenian", 2), ("Balinese", 3), ("Bengali", 4), ("CanadianAboriginal", 5),
+            ("Cherokee", 6), ("Cyrillic", 7), ("Devanagari", 8), ("Ethiopic", 9), ("Georgian", 10),
+            ("Greek", 11), ("Gujarati", 12), ("Gurmukhi", 13), ("Hangul", 14), ("Hebrew", 15),
+            ("Hiragana", 16), ("Javanese", 17), ("Kannada", 18), ("Katakana", 19), ("Khmer", 20),
+            ("Latin", 21), ("Malayalam", 22), ("Mandarin", 23), ("Mongolian", 24), ("Myanmar", 25),
+            ("Nko", 26), ("Oriya", 27), ("Sinhala", 28), ("Sundanese", 29), ("Tamil", 30),
+            ("Telugu", 31), ("Thaana", 32), ("Thai", 33), ("Tifinagh", 34), ("Coptic", 35),
+            ("Glagolitic", 36), ("Gothic", 37), ("OldItalic", 38), ("Phoenician", 39), ("Runic", 40),
+            ("Lao", 41),
+        ];
+        assert_eq!(scripts.len(), ALL_SCRIPTS.len());
+        for &(name, value) in scripts.iter() {
+            let script = ALL_SCRIPTS.iter().find(|script| format!("{:?}", script) == name).unwrap();
+            assert_eq!(*script as u32, value, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_whatlang_detect() {
+        let text = b"Ceci est un texte en fran\xc3\xa7ais, pour tester la d\xc3\xa9tection.\0";
+        let mut info = WhatlangInfo {
+            lang: WhatlangLang::Eng,
+            script: WhatlangScript::Arabic,
+            confidence: 0.0,
+            is_reliable: false,
+        };
+        unsafe {
+            assert!(whatlang_detect(text.as_ptr() as *const c_char, &mut info));
+        }
+        assert_eq!(info.lang, WhatlangLang::Fra);
+        assert_eq!(info.script, WhatlangScript::Latin);
+        assert!(info.confidence > 0.0);
+    }
+
+    #[test]
+    fn test_whatlang_detect_invalid_input() {
+        let mut info = WhatlangInfo {
+            lang: WhatlangLang::Eng,
+            script: WhatlangScript::Latin,
+            confidence: 0.5,
+            is_reliable: false,
+        };
+        let before = info;
+        unsafe {
+            assert!(!whatlang_detect(ptr::null(), &mut info));
+            assert!(!whatlang_detect(b"\xff\xfe\0".as_ptr() as *const c_char, &mut info));
+            assert!(!whatlang_detect(b"123\0".as_ptr() as *const c_char, &mut info));
+            assert!(!whatlang_detect(b"Hello\0".as_ptr() as *const c_char, ptr::null_mut()));
+        }
+        assert_eq!(info, before);
+    }
+
+    #[test]
+    fn test_whatlang_detect_script() {
+        let mut script = WhatlangScript::Latin;
+        unsafe {
+            assert!(whatlang_detect_script("Привет\0".as_ptr() as *const c_char, &mut script));
+            assert_eq!(script, WhatlangScript::Cyrillic);
+            assert!(!whatlang_detect_script("!!!\0".as_ptr() as *const c_char, &mut script));
+        }
+        assert_eq!(script, WhatlangScript::Cyrillic);
+    }
+}
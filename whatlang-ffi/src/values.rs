@@ -0,0 +1,319 @@
+// Stable values of languages and scripts for C. Values are never changed or reused, new languages
+// and scripts get the next free value (for languages it is the id in misc/supported_languages.csv).
+// 0 is never used, so zero-initialized values are invalid. Keep in sync with whatlang, see tests.
+
+/// A language, see `whatlang::Lang`. Values never change, new languages get the next free value.
+#[repr(C)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum WhatlangLang {
+    Aka = 1,
+    Amh = 2,
+    Arb = 3,
+    Azj = 4,
+    Bel = 5,
+    Ben = 6,
+    Bho = 7,
+    Bul = 8,
+    Ceb = 9,
+    Ces = 10,
+    Chr = 11,
+    Cmn = 12,
+    Dan = 13,
+    Deu = 14,
+    Div = 15,
+    Ell = 16,
+    Eng = 17,
+    Epo = 18,
+    Est = 19,
+    Fin = 20,
+    Fra = 21,
+    Guj = 22,
+    Hat = 23,
+    Hau = 24,
+    Heb = 25,
+    Hin = 26,
+    Hrv = 27,
+    Hun = 28,
+    Hye = 29,
+    Ibo = 30,
+    Ilo = 31,
+    Ind = 32,
+    Ita = 33,
+    Jav = 34,
+    Jpn = 35,
+    Kan = 36,
+    Kat = 37,
+    Khk = 38,
+    Khm = 39,
+    Kin = 40,
+    Kor = 41,
+    Kur = 42,
+    Lav = 43,
+    Lit = 44,
+    Mai = 45,
+    Mal = 46,
+    Mar = 47,
+    Mkd = 48,
+    Mlg = 49,
+    Mya = 50,
+    Nep = 51,
+    Nld = 52,
+    Nno = 53,
+    Nob = 54,
+    Nqo = 55,
+    Nya = 56,
+    Ori = 57,
+    Orm = 58,
+    Pan = 59,
+    Pes = 60,
+    Pol = 61,
+    Por = 62,
+    Ron = 63,
+    Run = 64,
+    Rus = 65,
+    Sin = 66,
+    Skr = 67,
+    Slv = 68,
+    Sna = 69,
+    Som = 70,
+    Spa = 71,
+    Srp = 72,
+    Swe = 73,
+    Tam = 74,
+    Tel = 75,
+    Tgl = 76,
+    Tha = 77,
+    Tir = 78,
+    Tuk = 79,
+    Tur = 80,
+    Uig = 81,
+    Ukr = 82,
+    Urd = 83,
+    Uzb = 84,
+    Vie = 85,
+    Ydd = 86,
+    Yor = 87,
+    Zgh = 88,
+    Zul = 89,
+    // Added after the first release of the C API
+    Slk = 90,
+    Swh = 91,
+    Kaz = 92,
+    Kir = 93,
+    Tat = 94,
+    Cat = 95,
+    Glg = 96,
+    Yue = 97,
+    Zlm = 98,
+    Cym = 99,
+    Gle = 100,
+    Gla = 101,
+    Eus = 102,
+    Isl = 103,
+    Fao = 104,
+    Lat = 105,
+    Lao = 106,
+}
+
+/// A script, see `whatlang::Script`. Values never change, new scripts get the next free value.
+#[repr(C)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum WhatlangScript {
+    Arabic = 1,
+    Armenian = 2,
+    Balinese = 3,
+    Bengali = 4,
+    CanadianAboriginal = 5,
+    Cherokee = 6,
+    Cyrillic = 7,
+    Devanagari = 8,
+    Ethiopic = 9,
+    Georgian = 10,
+    Greek = 11,
+    Gujarati = 12,
+    Gurmukhi = 13,
+    Hangul = 14,
+    Hebrew = 15,
+    Hiragana = 16,
+    Javanese = 17,
+    Kannada = 18,
+    Katakana = 19,
+    Khmer = 20,
+    Latin = 21,
+    Malayalam = 22,
+    Mandarin = 23,
+    Mongolian = 24,
+    Myanmar = 25,
+    Nko = 26,
+    Oriya = 27,
+    Sinhala = 28,
+    Sundanese = 29,
+    Tamil = 30,
+    Telugu = 31,
+    Thaana = 32,
+    Thai = 33,
+    Tifinagh = 34,
+    // Historic scripts, detected only with `historic-scripts` feature
+    Coptic = 35,
+    Glagolitic = 36,
+    Gothic = 37,
+    OldItalic = 38,
+    Phoenician = 39,
+    Runic = 40,
+    // Scripts added after the historic ones
+    Lao = 41,
+}
+
+// In the same order as `Lang::all()` and `Script::all()`.
+pub(crate) const ALL_LANGS: [WhatlangLang; 106] = [
+    WhatlangLang::Aka,
+    WhatlangLang::Amh,
+    WhatlangLang::Arb,
+    WhatlangLang::Azj,
+    WhatlangLang::Bel,
+    WhatlangLang::Ben,
+    WhatlangLang::Bho,
+    WhatlangLang::Bul,
+    WhatlangLang::Cat,
+    WhatlangLang::Ceb,
+    WhatlangLang::Ces,
+    WhatlangLang::Chr,
+    WhatlangLang::Cmn,
+    WhatlangLang::Cym,
+    WhatlangLang::Dan,
+    WhatlangLang::Deu,
+    WhatlangLang::Div,
+    WhatlangLang::Ell,
+    WhatlangLang::Eng,
+    WhatlangLang::Epo,
+    WhatlangLang::Est,
+    WhatlangLang::Eus,
+    WhatlangLang::Fao,
+    WhatlangLang::Fin,
+    WhatlangLang::Fra,
+    WhatlangLang::Gla,
+    WhatlangLang::Gle,
+    WhatlangLang::Glg,
+    WhatlangLang::Guj,
+    WhatlangLang::Hat,
+    WhatlangLang::Hau,
+    WhatlangLang::Heb,
+    WhatlangLang::Hin,
+    WhatlangLang::Hrv,
+    WhatlangLang::Hun,
+    WhatlangLang::Hye,
+    WhatlangLang::Ibo,
+    WhatlangLang::Ilo,
+    WhatlangLang::Ind,
+    WhatlangLang::Isl,
+    WhatlangLang::Ita,
+    WhatlangLang::Jav,
+    WhatlangLang::Jpn,
+    WhatlangLang::Kan,
+    WhatlangLang::Kat,
+    WhatlangLang::Kaz,
+    WhatlangLang::Khk,
+    WhatlangLang::Khm,
+    WhatlangLang::Kin,
+    WhatlangLang::Kir,
+    WhatlangLang::Kor,
+    WhatlangLang::Kur,
+    WhatlangLang::Lao,
+    WhatlangLang::Lat,
+    WhatlangLang::Lav,
+    WhatlangLang::Lit,
+    WhatlangLang::Mai,
+    WhatlangLang::Mal,
+    WhatlangLang::Mar,
+    WhatlangLang::Mkd,
+    WhatlangLang::Mlg,
+    WhatlangLang::Mya,
+    WhatlangLang::Nep,
+    WhatlangLang::Nld,
+    WhatlangLang::Nno,
+    WhatlangLang::Nob,
+    WhatlangLang::Nqo,
+    WhatlangLang::Nya,
+    WhatlangLang::Ori,
+    WhatlangLang::Orm,
+    WhatlangLang::Pan,
+    WhatlangLang::Pes,
+    WhatlangLang::Pol,
+    WhatlangLang::Por,
+    WhatlangLang::Ron,
+    WhatlangLang::Run,
+    WhatlangLang::Rus,
+    WhatlangLang::Sin,
+    WhatlangLang::Skr,
+    WhatlangLang::Slk,
+    WhatlangLang::Slv,
+    WhatlangLang::Sna,
+    WhatlangLang::Som,
+    WhatlangLang::Spa,
+    WhatlangLang::Srp,
+    WhatlangLang::Swe,
+    WhatlangLang::Swh,
+    WhatlangLang::Tam,
+    WhatlangLang::Tat,
+    WhatlangLang::Tel,
+    WhatlangLang::Tgl,
+    WhatlangLang::Tha,
+    WhatlangLang::Tir,
+    WhatlangLang::Tuk,
+    WhatlangLang::Tur,
+    WhatlangLang::Uig,
+    WhatlangLang::Ukr,
+    WhatlangLang::Urd,
+    WhatlangLang::Uzb,
+    WhatlangLang::Vie,
+    WhatlangLang::Ydd,
+    WhatlangLang::Yor,
+    WhatlangLang::Yue,
+    WhatlangLang::Zgh,
+    WhatlangLang::Zlm,
+    WhatlangLang::Zul,
+];
+
+pub(crate) const ALL_SCRIPTS: [WhatlangScript; 41] = [
+    WhatlangScript::Arabic,
+    WhatlangScript::Armenian,
+    WhatlangScript::Balinese,
+    WhatlangScript::Bengali,
+    WhatlangScript::CanadianAboriginal,
+    WhatlangScript::Cherokee,
+    WhatlangScript::Cyrillic,
+    WhatlangScript::Devanagari,
+    WhatlangScript::Ethiopic,
+    WhatlangScript::Georgian,
+    WhatlangScript::Greek,
+    WhatlangScript::Gujarati,
+    WhatlangScript::Gurmukhi,
+    WhatlangScript::Hangul,
+    WhatlangScript::Hebrew,
+    WhatlangScript::Hiragana,
+    WhatlangScript::Javanese,
+    WhatlangScript::Kannada,
+    WhatlangScript::Katakana,
+    WhatlangScript::Khmer,
+    WhatlangScript::Lao,
+    WhatlangScript::Latin,
+    WhatlangScript::Malayalam,
+    WhatlangScript::Mandarin,
+    WhatlangScript::Mongolian,
+    WhatlangScript::Myanmar,
+    WhatlangScript::Nko,
+    WhatlangScript::Oriya,
+    WhatlangScript::Sinhala,
+    WhatlangScript::Sundanese,
+    WhatlangScript::Tamil,
+    WhatlangScript::Telugu,
+    WhatlangScript::Thaana,
+    WhatlangScript::Thai,
+    WhatlangScript::Tifinagh,
+    WhatlangScript::Coptic,
+    WhatlangScript::Glagolitic,
+    WhatlangScript::Gothic,
+    WhatlangScript::OldItalic,
+    WhatlangScript::Phoenician,
+    WhatlangScript::Runic,
+];
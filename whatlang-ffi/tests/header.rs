@@ -0,0 +1,19 @@
+extern crate cbindgen;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn test_header_is_up_to_date() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(Path::new(&crate_dir).join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::generate_with_config(&crate_dir, config).unwrap().write(&mut generated);
+
+    let header = fs::read_to_string(Path::new(&crate_dir).join("include/whatlang.h")).unwrap();
+    assert!(
+        header.as_bytes() == &generated[..],
+        "include/whatlang.h is outdated, regenerate it with cbindgen"
+    );
+}
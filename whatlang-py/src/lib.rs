@@ -0,0 +1,148 @@
+//! Python bindings for [whatlang](https://crates.io/crates/whatlang), built with PyO3.
+//!
+//! Build and install into the current virtualenv with `maturin develop` from `whatlang-py`:
+//!
+//! ```python
+//! import whatlang
+//!
+//! info = whatlang.detect("Ĉu vi ne volas eklerni Esperanton?")
+//! print(info.lang, info.script, info.confidence)
+//!
+//! # Batch functions release the GIL, e.g. for a pandas Series
+//! df["lang"] = whatlang.detect_lang_batch(df["text"])
+//! ```
+//!
+//! Languages are represented by ISO 639-3 codes and scripts by their names, as returned by
+//! `Lang::code()` and `Script::name()`. Batch functions accept any sequence, items which are not
+//! strings (e.g. `None` or `NaN`) are not detected.
+use pyo3::prelude::*;
+
+/// A result of language detection, see `whatlang::Info`.
+#[pyclass(frozen, skip_from_py_object, name = "Info", module = "whatlang")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Info(whatlang::Info);
+
+#[pymethods]
+impl Info {
+    /// ISO 639-3 code of the language, e.g. `"epo"`.
+    #[getter]
+    fn lang(&self) -> String {
+        self.0.lang().code().to_string()
+    }
+
+    /// Name of the language in English, e.g. `"Esperanto"`.
+    #[getter]
+    fn lang_eng_name(&self) -> &'static str {
+        self.0.lang().eng_name()
+    }
+
+    /// Name of the script, e.g. `"Latin"`.
+    #[getter]
+    fn script(&self) -> String {
+        self.0.script().name().to_string()
+    }
+
+    #[getter]
+    fn confidence(&self) -> f64 {
+        self.0.confidence()
+    }
+
+    #[getter]
+    fn is_reliable(&self) -> bool {
+        self.0.is_reliable()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Info(lang='{}', script='{}', confidence={})",
+            self.lang(), self.script(), self.confidence()
+        )
+    }
+}
+
+/// Detect a language and a script of the text, returns `None` if it can not be detected.
+#[pyfunction]
+fn detect(text: &str) -> Option<Info> {
+    whatlang::detect(text).map(Info)
+}
+
+/// Detect only a language of the text, returns ISO 639-3 code or `None`.
+#[pyfunction]
+fn detect_lang(text: &str) -> Option<String> {
+    whatlang::detect_lang(text).map(|lang| lang.code().to_string())
+}
+
+/// Detect a script of the text, returns its name or `None`.
+#[pyfunction]
+fn detect_script(text: &str) -> Option<String> {
+    whatlang::detect_script(text).map(|script| script.name().to_string())
+}
+
+/// Detect languages and scripts of many texts without holding the GIL.
+#[pyfunction]
+fn detect_batch(py: Python<'_>, texts: Vec<Bound<'_, PyAny>>) -> Vec<Option<Info>> {
+    let texts = extract_texts(&texts);
+    py.detach(|| texts.iter().map(|text| text.as_deref().and_then(detect)).collect())
+}
+
+/// Detect languages of many texts without holding the GIL, returns ISO 639-3 codes.
+#[pyfunction]
+fn detect_lang_batch(py: Python<'_>, texts: Vec<Bound<'_, PyAny>>) -> Vec<Option<String>> {
+    let texts = extract_texts(&texts);
+    py.detach(|| texts.iter().map(|text| text.as_deref().and_then(detect_lang)).collect())
+}
+
+// Texts are copied while the GIL is held, items which are not strings become `None`.
+fn extract_texts(items: &[Bound<'_, PyAny>]) -> Vec<Option<String>> {
+    items.iter().map(|item| item.extract::<String>().ok()).collect()
+}
+
+#[pymodule]
+#[pyo3(name = "whatlang")]
+fn whatlang_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Info>()?;
+    module.add_function(wrap_pyfunction!(detect, module)?)?;
+    module.add_function(wrap_pyfunction!(detect_lang, module)?)?;
+    module.add_function(wrap_pyfunction!(detect_script, module)?)?;
+    module.add_function(wrap_pyfunction!(detect_batch, module)?)?;
+    module.add_function(wrap_pyfunction!(detect_lang_batch, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::{PyFloat, PyString};
+
+    #[test]
+    fn test_detect() {
+        let info = detect("Ĉu vi ne volas eklerni Esperanton? Bonvolu!").unwrap();
+        assert_eq!(info.lang(), "epo");
+        assert_eq!(info.lang_eng_name(), "Esperanto");
+        assert_eq!(info.script(), "Latin");
+        assert!(info.__repr__().starts_with("Info(lang='epo', script='Latin', confidence="));
+
+        assert_eq!(detect_lang("Та нічого, все нормально. А в тебе як?"), Some("ukr".to_string()));
+        assert_eq!(detect_script("Привет"), Some("Cyrillic".to_string()));
+        assert_eq!(detect("123"), None);
+    }
+
+    #[test]
+    fn test_detect_batch() {
+        Python::initialize();
+        Python::attach(|py| {
+            let texts = vec![
+                PyString::new(py, "There is no reason not to learn Esperanto.").into_any(),
+                py.None().into_bound(py),
+                PyFloat::new(py, f64::NAN).into_any(),
+                PyString::new(py, "Та нічого, все нормально. А в тебе як?").into_any(),
+            ];
+            let langs = detect_lang_batch(py, texts.clone());
+            assert_eq!(langs, vec![Some("eng".to_string()), None, None, Some("ukr".to_string())]);
+
+            let infos = detect_batch(py, texts);
+            assert_eq!(infos[0].map(|info| info.script()), Some("Latin".to_string()));
+            assert_eq!(infos[1], None);
+        });
+    }
+}
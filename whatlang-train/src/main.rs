@@ -0,0 +1,113 @@
+//! Builds trigram profiles (or lists of the most common words) of languages from text corpora.
+//!
+//! ```text
+//! whatlang-train <corpus_dir> [--words <size>]
+//! whatlang-train --bundle <file>
+//! ```
+//!
+//! The corpus directory contains a file per language named by its code (e.g. `ukr.txt`),
+//! every line of a file is a separate text. Profiles are printed in the format of
+//! `misc/data.json`: trigrams joined by `|`, grouped by languages and their scripts.
+//! With `--words` the given number of the most common words of every language is printed instead.
+//! With `--bundle` the built-in profiles are written to a file, which can be opened
+//! (and memory-mapped) by `whatlang::ProfileBundle`.
+extern crate whatlang;
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::process;
+
+use whatlang::{detect_script, train_profile, train_word_list, Lang, ProfileBundle};
+
+// Number of trigrams in profiles of the built-in languages.
+const PROFILE_SIZE: usize = 300;
+
+const USAGE: &str = "Usage: whatlang-train <corpus_dir> [--words <size>]\n       whatlang-train --bundle <file>";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (dir, words) = match args.as_slice() {
+        [flag, path] if flag == "--bundle" => return write_bundle(Path::new(path)),
+        [dir] => (dir, None),
+        [dir, flag, size] if flag == "--words" => match size.parse::<usize>() {
+            Ok(size) => (dir, Some(size)),
+            Err(_) => fail(USAGE)
+        },
+        _ => fail(USAGE)
+    };
+
+    let corpus = load_corpus(Path::new(dir));
+    if corpus.is_empty() {
+        fail("No texts found");
+    }
+
+    let json = match words {
+        Some(size) => {
+            let lists: BTreeMap<String, Vec<String>> = corpus.iter()
+                .map(|&(lang, ref texts)| (lang.code().to_string(), train_word_list(texts, size)))
+                .collect();
+            serde_json::to_string_pretty(&lists)
+        }
+        None => serde_json::to_string_pretty(&train_profiles(&corpus))
+    };
+    println!("{}", json.unwrap());
+}
+
+fn write_bundle(path: &Path) {
+    let file = File::create(path).unwrap_or_else(|err| fail(&format!("Can not create {}: {}", path.display(), err)));
+    let mut writer = BufWriter::new(file);
+    ProfileBundle::write_builtin(&mut writer)
+        .and_then(|_| writer.flush())
+        .unwrap_or_else(|err| fail(&format!("Can not write {}: {}", path.display(), err)));
+}
+
+// Profiles grouped by scripts, like in misc/data.json.
+fn train_profiles(corpus: &[(Lang, Vec<String>)]) -> BTreeMap<String, BTreeMap<String, String>> {
+    let mut scripts: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    for &(lang, ref texts) in corpus {
+        let script = match detect_script(&texts.join("\n")) {
+            Some(script) => script,
+            None => {
+                eprintln!("Skipping {}: no script detected", lang.code());
+                continue;
+            }
+        };
+        let profile = train_profile(texts);
+        if profile.len() < PROFILE_SIZE {
+            eprintln!("Warning: {} has only {} trigrams, the built-in profiles have {}",
+                      lang.code(), profile.len(), PROFILE_SIZE);
+        }
+        scripts.entry(script.name().to_string()).or_default().insert(lang.code().to_string(), profile.join("|"));
+    }
+    scripts
+}
+
+fn load_corpus(dir: &Path) -> Vec<(Lang, Vec<String>)> {
+    let entries = fs::read_dir(dir).unwrap_or_else(|err| fail(&format!("Can not read {}: {}", dir.display(), err)));
+    let mut corpus = Vec::new();
+    for entry in entries {
+        let path = entry.unwrap().path();
+        let code = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("");
+        let lang = match Lang::from_code(code) {
+            Some(lang) => lang,
+            None => {
+                eprintln!("Skipping {}: unknown language code", path.display());
+                continue;
+            }
+        };
+        let content = fs::read_to_string(&path).unwrap_or_else(|err| fail(&format!("Can not read {}: {}", path.display(), err)));
+        let texts: Vec<String> = content.lines().filter(|line| !line.trim().is_empty()).map(String::from).collect();
+        corpus.push((lang, texts));
+    }
+    corpus.sort_by(|a, b| a.0.code().cmp(b.0.code()));
+    corpus
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
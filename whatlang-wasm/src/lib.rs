@@ -0,0 +1,110 @@
+//! WebAssembly bindings for [whatlang](https://crates.io/crates/whatlang), built with wasm-bindgen.
+//!
+//! Build with `wasm-pack build --target web whatlang-wasm` and use from JavaScript:
+//!
+//! ```js
+//! import init, { detect } from "./pkg/whatlang_wasm.js";
+//!
+//! await init();
+//! const info = detect("Ĉu vi ne volas eklerni Esperanton?");
+//! console.log(info.lang, info.script, info.confidence);
+//! ```
+//!
+//! Languages are represented by ISO 639-3 codes and scripts by their names, as returned by
+//! `Lang::code()` and `Script::name()`.
+extern crate wasm_bindgen;
+extern crate whatlang;
+#[cfg(all(test, target_arch = "wasm32"))]
+extern crate wasm_bindgen_test;
+
+use wasm_bindgen::prelude::*;
+
+/// A result of language detection, see `whatlang::Info`.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Info(whatlang::Info);
+
+#[wasm_bindgen]
+impl Info {
+    /// ISO 639-3 code of the language, e.g. `"epo"`.
+    #[wasm_bindgen(getter)]
+    pub fn lang(&self) -> String {
+        self.0.lang().code().to_string()
+    }
+
+    /// Name of the language in English, e.g. `"Esperanto"`.
+    #[wasm_bindgen(getter, js_name = langEngName)]
+    pub fn lang_eng_name(&self) -> String {
+        self.0.lang().eng_name().to_string()
+    }
+
+    /// Name of the script, e.g. `"Latin"`.
+    #[wasm_bindgen(getter)]
+    pub fn script(&self) -> String {
+        self.0.script().name().to_string()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f64 {
+        self.0.confidence()
+    }
+
+    #[wasm_bindgen(getter, js_name = isReliable)]
+    pub fn is_reliable(&self) -> bool {
+        self.0.is_reliable()
+    }
+}
+
+/// Detect a language and a script of the text, returns `undefined` if it can not be detected.
+#[wasm_bindgen]
+pub fn detect(text: &str) -> Option<Info> {
+    whatlang::detect(text).map(Info)
+}
+
+/// Detect only a language of the text, returns ISO 639-3 code or `undefined`.
+#[wasm_bindgen(js_name = detectLang)]
+pub fn detect_lang(text: &str) -> Option<String> {
+    whatlang::detect_lang(text).map(|lang| lang.code().to_string())
+}
+
+/// Detect a script of the text, returns its name or `undefined`.
+#[wasm_bindgen(js_name = detectScript)]
+pub fn detect_script(text: &str) -> Option<String> {
+    whatlang::detect_script(text).map(|script| script.name().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_detect() {
+        let info = detect("Ĉu vi ne volas eklerni Esperanton? Bonvolu!").unwrap();
+        assert_eq!(info.lang(), "epo");
+        assert_eq!(info.lang_eng_name(), "Esperanto");
+        assert_eq!(info.script(), "Latin");
+        assert!(info.confidence() > 0.0);
+
+        assert_eq!(detect("123"), None);
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_detect_lang_and_script() {
+        assert_eq!(detect_lang("Та нічого, все нормально. А в тебе як?"), Some("ukr".to_string()));
+        assert_eq!(detect_script("Привет"), Some("Cyrillic".to_string()));
+        assert_eq!(detect_script("!!!"), None);
+    }
+
+    // There is no clock on wasm32-unknown-unknown, so the time limit must be ignored
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    fn test_detect_with_max_duration() {
+        let options = whatlang::Options::new().set_max_duration(::std::time::Duration::from_secs(1));
+        let info = whatlang::detect_with_options("Ĉu vi ne volas eklerni Esperanton? Bonvolu!", &options).unwrap();
+        assert_eq!(info.lang(), whatlang::Lang::Epo);
+    }
+}